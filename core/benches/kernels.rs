@@ -0,0 +1,109 @@
+//! Times the slice kernels against going item by item through the fallible operators of the
+//! items, as list arithmetic did before them. Run with `cargo bench -p cupid_core`.
+
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use cupid_core::lang::{
+    invokable::{
+        kernel::{self, Side},
+        operator::{OperatorAdd, OperatorMultiply},
+    },
+    type_system::{Atom, Bits, List, TypeFloat, TypeInt},
+};
+
+const ITEMS: usize = 1_000_000;
+const RUNS: usize = 20;
+
+// The fastest of a few runs, the others having been slowed down by something else
+fn fastest<T>(mut f: impl FnMut() -> T) -> Duration {
+    (0..RUNS)
+        .map(|_| {
+            let start = Instant::now();
+            black_box(f());
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn report<A, B>(name: &str, by_item: impl FnMut() -> A, kernel: impl FnMut() -> B) {
+    let (before, after) = (fastest(by_item), fastest(kernel));
+    println!(
+        "{name:<16} item by item {:>9.3?}  kernel {:>9.3?}  {:>5.1}x",
+        before,
+        after,
+        before.as_secs_f64() / after.as_secs_f64()
+    );
+}
+
+fn main() {
+    let ints: Vec<TypeInt> = (0..ITEMS as i32).map(|i| TypeInt(i % 1000 - 500)).collect();
+    let floats: Vec<TypeFloat> = ints.iter().map(|i| TypeFloat(i.0 as f64 / 7.0)).collect();
+    let (int_list, float_list) = (List::new(ints.clone()), List::new(floats.clone()));
+    println!("{ITEMS} items, fastest of {RUNS} runs");
+
+    report(
+        "ints + ints",
+        || <&List<TypeInt> as OperatorAdd<_, _>>::add(&int_list, &int_list),
+        || kernel::ints(Side::Slice(&ints), Side::Slice(&ints), |l, r| l + r),
+    );
+    report(
+        "ints * int",
+        || {
+            // an atom was repeated into a list first
+            let three = Atom::new(TypeInt(3)).repeat(ITEMS);
+            <&List<TypeInt> as OperatorMultiply<_, _>>::mul(&int_list, &three)
+        },
+        || kernel::ints(Side::Slice(&ints), Side::Atom(TypeInt(3)), |l, r| l * r),
+    );
+    report(
+        "floats + floats",
+        || <&List<TypeFloat> as OperatorAdd<_, _>>::add(&float_list, &float_list),
+        || kernel::floats(Side::Slice(&floats), Side::Slice(&floats), |l, r| l + r),
+    );
+    report(
+        "floats * floats",
+        || <&List<TypeFloat> as OperatorMultiply<_, _>>::mul(&float_list, &float_list),
+        || kernel::floats(Side::Slice(&floats), Side::Slice(&floats), |l, r| l * r),
+    );
+    report(
+        "ints < int",
+        || {
+            let tests = ints.iter().map(|i| i.partial_cmp(&TypeInt(0)));
+            tests
+                .map(|o| o == Some(std::cmp::Ordering::Less))
+                .collect::<Vec<bool>>()
+                .into_iter()
+                .collect::<Bits>()
+        },
+        || kernel::compare(Side::Slice(&ints), Side::Atom(TypeInt(0)), |l, r| l < r),
+    );
+    report(
+        "floats = floats",
+        || {
+            let tests = floats.iter().zip(&floats).map(|(l, r)| l.partial_cmp(r));
+            tests
+                .map(|o| o == Some(std::cmp::Ordering::Equal))
+                .collect::<Vec<bool>>()
+                .into_iter()
+                .collect::<Bits>()
+        },
+        || kernel::compare(Side::Slice(&floats), Side::Slice(&floats), |l, r| l == r),
+    );
+    report(
+        "sum ints",
+        || {
+            let values: Vec<i32> = ints.iter().map(|i| i.0).collect();
+            let items = values.iter().filter(|&&v| v != i32::MIN);
+            items.fold(0i32, |sum, &v| {
+                let (sum, overflowed) = sum.overflowing_add(v);
+                black_box(overflowed || sum == i32::MIN);
+                sum
+            })
+        },
+        || kernel::sum_ints(&ints),
+    );
+}
@@ -18,10 +18,6 @@ fn main() {
             name: "a".to_string(),
             value: SuperType::Int(10.into()),
         },
-        Instruction::Lambda {
-            args_push: vec![],
-            body: vec![],
-        },
     ]);
 
     match global_context.unwind() {
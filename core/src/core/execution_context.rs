@@ -1,9 +1,22 @@
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, rc::Rc, sync::Arc};
+
+use rayon::ThreadPool;
 
 use crate::{
     Error,
     core::{instruction::Instruction, variable::Variables},
-    lang::SuperType,
+    lang::{
+        SuperType,
+        invokable::{
+            self, Invokable,
+            function::Function,
+            join, list,
+            operator::{self, Operator, Overflow},
+            parallel,
+        },
+        parse_tree::{self, ParseTreeNode},
+        type_system::{TypeFunction, TypeLambda},
+    },
 };
 
 #[derive(Clone, Debug, Default)]
@@ -13,12 +26,16 @@ pub struct ExecutionContext {
     variables: Variables,           // Local variables
     instructions: Vec<Instruction>, // Stack of instructions - what the program does
     rvalues: Vec<EphemeralValue>,   // Stack of rvalues - temporary values for calculations
+    overflow: Overflow,             // What int arithmetic does when results do not fit
+    pool: Option<Arc<ThreadPool>>,  // Secondary threads sharing the work on long lists
+    lambda: bool,                   // Lambdas see their own variables and the globals only
+    worker: bool,                   // Secondary threads read the globals but cannot set them
 }
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum EphemeralValue {
     Owned(SuperType),
-    Ref(Rc<SuperType>),
+    Ref(Arc<SuperType>),
 }
 
 impl From<SuperType> for EphemeralValue {
@@ -27,13 +44,199 @@ impl From<SuperType> for EphemeralValue {
     }
 }
 
-impl From<Rc<SuperType>> for EphemeralValue {
-    fn from(value: Rc<SuperType>) -> Self {
+impl From<Arc<SuperType>> for EphemeralValue {
+    fn from(value: Arc<SuperType>) -> Self {
         EphemeralValue::Ref(value)
     }
 }
 
+impl EphemeralValue {
+    pub fn into_owned(self) -> SuperType {
+        match self {
+            EphemeralValue::Owned(value) => value,
+            EphemeralValue::Ref(arc) => Arc::try_unwrap(arc).unwrap_or_else(|arc| (*arc).clone()),
+        }
+    }
+}
+
+// x op: y, x[i]: y and x[i] op: y on the value of x
+fn amend(
+    data: &mut SuperType,
+    index: Option<SuperType>,
+    op: Option<Operator>,
+    value: SuperType,
+) -> Result<(), Error> {
+    match (index, op) {
+        (Some(index), Some(op)) => {
+            let value = op.invoke_2(list::index(data, index.clone())?, value)?;
+            list::amend(data, index, value)
+        }
+        (Some(index), None) => list::amend(data, index, value),
+        (None, Some(Operator::Join)) => list::append(data, value),
+        (None, Some(op)) => {
+            *data = op.invoke_2(data.clone(), value)?;
+            Ok(())
+        }
+        (None, None) => Err(Error::MalformedProgram(
+            "Amending without an index or an operator.".to_owned(),
+        )),
+    }
+}
+
+// Statements are pushed onto the instruction stack, so they are compiled in reverse
+fn compile(node: &ParseTreeNode) -> Vec<Instruction> {
+    let mut instructions = Vec::new();
+    node.compile(&mut instructions);
+    instructions.reverse();
+    instructions
+}
+
 impl ExecutionContext {
+    /// Lexes, parses and runs a program, returning the value of its last statement.
+    /// Every statement but the last is silent, so is a trailing assignment.
+    pub fn run(&mut self, source: &str) -> Result<SuperType, Error> {
+        let is_function = |name: &str| self.lookup(name).is_some_and(|value| value.is_function());
+        let statements = parse_tree::parse_with(source, is_function)?;
+        self.push_statements(&statements, true);
+
+        let (overflow, pool) = (self.overflow, self.pool.clone());
+        let outcome =
+            parallel::with_pool(pool, || operator::with_overflow(overflow, || self.unwind()));
+        if outcome.is_err() {
+            self.clear();
+        }
+        outcome.map(EphemeralValue::into_owned)
+    }
+
+    /// Makes int arithmetic wrap around, the default, or raise Error::Overflow.
+    pub fn set_overflow(&mut self, overflow: Overflow) {
+        self.overflow = overflow;
+    }
+
+    /// Splits operators and aggregations over long lists between `threads` secondary threads,
+    /// as q's `-s`. With 0 or 1, the default, everything runs on the calling thread.
+    pub fn set_threads(&mut self, threads: usize) -> Result<(), Error> {
+        self.pool = parallel::pool(threads)?;
+        Ok(())
+    }
+
+    // Every statement but the last is silent, and so is a trailing assignment when `top_level`
+    fn push_statements(&mut self, statements: &[ParseTreeNode], top_level: bool) {
+        // the first statement runs first so it goes on top of the stack
+        for (i, statement) in statements.iter().enumerate().rev() {
+            let assigns = matches!(
+                statement,
+                ParseTreeNode::Assign { .. }
+                    | ParseTreeNode::AssignGlobal { .. }
+                    | ParseTreeNode::Amend { .. }
+            );
+            match i + 1 < statements.len() || (top_level && assigns) {
+                true => self.push_statement_silent(compile(statement)),
+                false => self.push_statement(compile(statement)),
+            }
+        }
+    }
+
+    // Runs `f` on a child context where `bindings` are local variables
+    fn in_child<T>(
+        &mut self,
+        bindings: &[(String, Arc<SuperType>)],
+        lambda: bool,
+        f: impl FnOnce(&mut ExecutionContext) -> Result<T, Error>,
+    ) -> Result<T, Error> {
+        // the child needs a shared handle on its parent, lend it ourselves for the duration
+        let parent = Rc::new(RefCell::new(std::mem::take(self)));
+        let outcome = {
+            let mut child = ExecutionContext::default();
+            child.parent = Some(parent.clone());
+            child.lambda = lambda;
+            for (name, value) in bindings {
+                child.variables.set_ref(name.clone(), value.clone());
+            }
+            f(&mut child)
+        };
+        *self = Rc::try_unwrap(parent)
+            .map_err(|_| Error::MalformedProgram("Child context outlived its parent.".to_owned()))?
+            .into_inner();
+        outcome
+    }
+
+    /// Evaluates an expression in a child context where `bindings` are local variables.
+    pub(crate) fn evaluate(
+        &mut self,
+        bindings: &[(String, Arc<SuperType>)],
+        node: &ParseTreeNode,
+    ) -> Result<SuperType, Error> {
+        self.in_child(bindings, false, |child| {
+            child.push_statement(compile(node));
+            child.unwind().map(EphemeralValue::into_owned)
+        })
+    }
+
+    // Runs a lambda in a child context, its parameters bound to the arguments
+    fn call(&mut self, lambda: &TypeLambda, arguments: Vec<SuperType>) -> Result<SuperType, Error> {
+        // as for builtins, f[] passes a single Nothing
+        let arguments = match (arguments.is_empty(), lambda.params.is_empty()) {
+            (true, false) => vec![SuperType::Nothing],
+            _ => arguments,
+        };
+        if arguments.len() != lambda.params.len() {
+            return Err(Error::Rank);
+        }
+        let values = arguments.into_iter().map(Arc::new);
+        let bindings: Vec<_> = lambda.params.iter().cloned().zip(values).collect();
+        self.in_child(&bindings, true, |child| {
+            child.push_statements(&lambda.body, false);
+            child.unwind().map(EphemeralValue::into_owned)
+        })
+    }
+
+    /// Applies a function to its arguments. Lambdas, and the builtins that run them, need the
+    /// context, the other builtins do not.
+    pub(crate) fn apply(
+        &mut self,
+        function: &SuperType,
+        arguments: Vec<SuperType>,
+    ) -> Result<SuperType, Error> {
+        match function {
+            SuperType::Lambda(lambda) => self.call(lambda, arguments),
+            SuperType::Function(TypeFunction(builtin @ (Function::Peach | Function::Fc))) => {
+                let [f, x] = <[SuperType; 2]>::try_from(arguments).map_err(|_| Error::Rank)?;
+                match builtin {
+                    Function::Peach => self.peach(&f, x),
+                    _ => self.fc(&f, x),
+                }
+            }
+            SuperType::Function(TypeFunction(
+                builtin @ (Function::WindowJoin | Function::WindowJoin1),
+            )) => {
+                let [w, c, t, aggregates] =
+                    <[SuperType; 4]>::try_from(arguments).map_err(|_| Error::Rank)?;
+                let prevailing = *builtin == Function::WindowJoin;
+                join::window_join(w, c, t, aggregates, prevailing, |f, args| {
+                    self.apply(f, args)
+                })
+            }
+            function => invokable::invoke(function, arguments),
+        }
+    }
+
+    /// A context for a secondary thread, which reads `globals` but cannot set them.
+    pub(crate) fn worker(globals: Variables) -> Self {
+        let mut worker = ExecutionContext::default();
+        worker.variables = globals;
+        worker.worker = true;
+        worker
+    }
+
+    /// The variables of the global context.
+    pub(crate) fn globals(&self) -> Variables {
+        match &self.parent {
+            Some(parent_ctx) => parent_ctx.borrow().globals(),
+            None => self.variables.clone(),
+        }
+    }
+
     pub fn push_statement(&mut self, instructions: Vec<Instruction>) {
         self.instructions.push(Instruction::EndStatement);
         self.instructions
@@ -55,11 +258,11 @@ impl ExecutionContext {
             match instr {
                 Instruction::BeginStatementSilent(instructions) => {
                     is_statement_silent = true;
-                    self.instructions.extend(instructions.into_iter());
+                    self.instructions.extend(instructions);
                 }
                 Instruction::BeginStatement(instructions) => {
                     is_statement_silent = false;
-                    self.instructions.extend(instructions.into_iter());
+                    self.instructions.extend(instructions);
                 }
                 Instruction::EndStatement => {
                     match is_statement_silent {
@@ -71,13 +274,6 @@ impl ExecutionContext {
                         false => return self.do_return(false),
                     }
                 }
-                Instruction::Lambda { args_push, body } => {
-                    // create a new context with the current as parent
-                    // run/unwind the context
-
-                    // grab the returned value and push it onto the rvalue stack
-                    todo!("Lambda execution not implemented yet");
-                }
                 Instruction::Return => return self.do_return(true),
                 Instruction::SetVariableNew { name, value } => {
                     let var = self.variables.set_new(name, value);
@@ -88,16 +284,16 @@ impl ExecutionContext {
                     self.rvalues.push(var.into());
                 }
                 Instruction::GetVariable { name } => {
-                    // Get variable from the current context
-                    let var = self.variables.get(&name).ok_or(Error::Name(name))?;
+                    // Get variable from the current context, then from the enclosing ones
+                    let var = self.lookup(&name).ok_or(Error::Name(name))?;
                     self.rvalues.push(var.into());
                 }
                 Instruction::SetGlobalVariableNew { name, value } => {
-                    let var = self.set_global_new(name, value);
+                    let var = self.set_global_new(name, value)?;
                     self.rvalues.push(var.into());
                 }
                 Instruction::SetGlobalVariableRef { name, value } => {
-                    let var = self.set_global_ref(name, value);
+                    let var = self.set_global_ref(name, value)?;
                     self.rvalues.push(var.into());
                 }
                 Instruction::GetGlobalVariable { name } => {
@@ -105,12 +301,62 @@ impl ExecutionContext {
                     self.rvalues.push(var.into());
                 }
 
-                Instruction::UnaryOperation { op: _, value: _ } => {}
-                Instruction::BinaryOperation {
-                    op: _,
-                    left: _,
-                    right: _,
-                } => {}
+                // the value of another variable is shared, it is copied when one of them changes
+                Instruction::Assign { name } => {
+                    let var = match self.pop_rvalue()? {
+                        EphemeralValue::Ref(value) => self.variables.set_ref(name, value),
+                        EphemeralValue::Owned(value) => self.variables.set_new(name, value),
+                    };
+                    self.rvalues.push(var.into());
+                }
+                Instruction::AssignGlobal { name } => {
+                    let var = match self.pop_rvalue()? {
+                        EphemeralValue::Ref(value) => self.set_global_ref(name, value)?,
+                        EphemeralValue::Owned(value) => self.set_global_new(name, value)?,
+                    };
+                    self.rvalues.push(var.into());
+                }
+                Instruction::Amend { name, op, indexed } => {
+                    let index = match indexed {
+                        true => Some(self.pop_value()?),
+                        false => None,
+                    };
+                    let value = self.pop_value()?;
+                    let var = self.amend_variable(&name, |data| amend(data, index, op, value))?;
+                    self.rvalues.push(var.into());
+                }
+                Instruction::Invoke { arity } => {
+                    let function = self.pop_value()?;
+                    let arguments = (0..arity)
+                        .map(|_| self.pop_value())
+                        .collect::<Result<Vec<_>, _>>()?;
+                    // parse trees given as data run in place, as if they had been written out
+                    match ParseTreeNode::from_invocation(&function, &arguments)? {
+                        Some(node) => self.instructions.extend(compile(&node)),
+                        None => {
+                            let result = self.apply(&function, arguments)?;
+                            self.rvalues.push(result.into());
+                        }
+                    }
+                }
+                Instruction::EnList { count } => {
+                    let items = (0..count)
+                        .map(|_| self.pop_value())
+                        .collect::<Result<Vec<_>, _>>()?;
+                    self.rvalues.push(SuperType::from_items(items).into());
+                }
+                Instruction::Query(query) => {
+                    let result = self.query(&query)?;
+                    self.rvalues.push(result.into());
+                }
+
+                Instruction::UnaryOperation { op, value } => {
+                    let function = invokable::builtin(&op).ok_or(Error::Name(op))?;
+                    self.rvalues.push(function.invoke_1(value)?.into());
+                }
+                Instruction::BinaryOperation { op, left, right } => {
+                    self.rvalues.push(op.invoke_2(left, right)?.into());
+                }
 
                 Instruction::Literal(value) => {
                     self.rvalues.push(value.into());
@@ -125,21 +371,76 @@ impl ExecutionContext {
             .unwrap_or(EphemeralValue::Owned(SuperType::Nothing)))
     }
 
-    fn set_global_new(&mut self, name: String, value: SuperType) -> Rc<SuperType> {
+    fn pop_rvalue(&mut self) -> Result<EphemeralValue, Error> {
+        self.rvalues
+            .pop()
+            .ok_or_else(|| Error::MalformedProgram("No value left on the stack.".to_owned()))
+    }
+
+    fn pop_value(&mut self) -> Result<SuperType, Error> {
+        self.pop_rvalue().map(EphemeralValue::into_owned)
+    }
+
+    fn lookup(&self, name: &str) -> Option<Arc<SuperType>> {
+        self.variables.get(name).or_else(|| match &self.parent {
+            // a lambda does not see the variables of its caller
+            Some(_) if self.lambda => self.get_global(name),
+            Some(parent_ctx) => parent_ctx.borrow().lookup(name),
+            None => None,
+        })
+    }
+
+    pub(crate) fn set_global_new(
+        &mut self,
+        name: String,
+        value: SuperType,
+    ) -> Result<Arc<SuperType>, Error> {
         match &self.parent {
             Some(parent_ctx) => parent_ctx.borrow_mut().set_global_new(name, value),
-            None => self.variables.set_new(name, value),
+            None if self.worker => Err(Error::NoUpdate),
+            None => Ok(self.variables.set_new(name, value)),
         }
     }
 
-    fn set_global_ref(&mut self, name: String, value: Rc<SuperType>) -> Rc<SuperType> {
+    fn set_global_ref(
+        &mut self,
+        name: String,
+        value: Arc<SuperType>,
+    ) -> Result<Arc<SuperType>, Error> {
         match &self.parent {
             Some(parent_ctx) => parent_ctx.borrow_mut().set_global_ref(name, value),
-            None => self.variables.set_ref(name, value),
+            None if self.worker => Err(Error::NoUpdate),
+            None => Ok(self.variables.set_ref(name, value)),
+        }
+    }
+
+    // Changes a variable through `f` in the context that defines it, as lookup finds it
+    fn amend_variable(
+        &mut self,
+        name: &str,
+        f: impl FnOnce(&mut SuperType) -> Result<(), Error>,
+    ) -> Result<Arc<SuperType>, Error> {
+        match &self.parent {
+            Some(_) if self.variables.contains(name) => self.variables.amend(name, f),
+            Some(_) if self.lambda => self.amend_global(name, f),
+            Some(parent_ctx) => parent_ctx.borrow_mut().amend_variable(name, f),
+            None => self.amend_global(name, f),
+        }
+    }
+
+    fn amend_global(
+        &mut self,
+        name: &str,
+        f: impl FnOnce(&mut SuperType) -> Result<(), Error>,
+    ) -> Result<Arc<SuperType>, Error> {
+        match &self.parent {
+            Some(parent_ctx) => parent_ctx.borrow_mut().amend_global(name, f),
+            None if self.worker => Err(Error::NoUpdate),
+            None => self.variables.amend(name, f),
         }
     }
 
-    fn get_global(&self, name: &str) -> Option<Rc<SuperType>> {
+    pub(crate) fn get_global(&self, name: &str) -> Option<Arc<SuperType>> {
         match &self.parent {
             Some(parent_ctx) => parent_ctx.borrow().get_global(name),
             None => self.variables.get(name),
@@ -164,16 +465,12 @@ impl ExecutionContext {
         // clear the context so we can move local refs
         self.clear();
 
-        // attempt to unwrap Rc if possible
+        // attempt to unwrap Arc if possible
         match val {
-            EphemeralValue::Ref(rc) => {
-                // println!("Returning value: {:?}", val);
-                println!("Strong reference count: {}", Rc::strong_count(&rc));
-                match Rc::try_unwrap(rc) {
-                    Ok(inner) => Ok(EphemeralValue::Owned(inner)),
-                    Err(rc) => Ok(EphemeralValue::Ref(rc)),
-                }
-            }
+            EphemeralValue::Ref(arc) => match Arc::try_unwrap(arc) {
+                Ok(inner) => Ok(EphemeralValue::Owned(inner)),
+                Err(arc) => Ok(EphemeralValue::Ref(arc)),
+            },
             v => Ok(v),
         }
     }
@@ -193,11 +490,12 @@ impl ExecutionContext {
 impl Drop for ExecutionContext {
     fn drop(&mut self) {
         self.clear();
-        println!("Dropped");
     }
 }
 
 #[cfg(test)]
+// the doc comments of the tests quote shell sessions, output lines and all
+#[allow(clippy::doc_lazy_continuation)]
 mod tests {
     use super::*;
 
@@ -245,4 +543,144 @@ mod tests {
             EphemeralValue::Ref(SuperType::Int(42.into()).into())
         );
     }
+
+    #[test]
+    fn test_execution_context_run_statements() {
+        let mut context = ExecutionContext::default();
+
+        assert_eq!(context.run("x: 1 2 3"), Ok(SuperType::Nothing));
+        assert_eq!(
+            context.run("y: 10; x + y"),
+            Ok(SuperType::from(vec![11, 12, 13]))
+        );
+        assert_eq!(context.run("z"), Err(Error::Name("z".to_owned())));
+    }
+
+    #[test]
+    fn test_overflow_is_chosen_per_context() {
+        let mut wrapping = ExecutionContext::default();
+        assert_eq!(wrapping.run("2147483647+1"), Ok(SuperType::from(i32::MIN)));
+        assert_eq!(
+            wrapping.run("sum 2147483647 1 1"),
+            Ok(SuperType::from(i32::MIN + 1))
+        );
+
+        let mut checked = ExecutionContext::default();
+        checked.set_overflow(Overflow::Raise);
+        assert_eq!(checked.run("2147483647+1"), Err(Error::Overflow));
+        assert_eq!(checked.run("sum 2147483647 1"), Err(Error::Overflow));
+        assert_eq!(checked.run("1+1"), Ok(SuperType::from(2)));
+        // the mode only holds while the context runs
+        assert_eq!(wrapping.run("2147483647*2"), Ok(SuperType::from(-2)));
+    }
+
+    #[test]
+    fn test_lambdas() {
+        let mut context = ExecutionContext::default();
+        assert_eq!(context.run("{x+y}[1;2]"), Ok(SuperType::from(3)));
+        assert_eq!(
+            context.run("f:{[a;b] c:a*b; c+1}; f[2;3]"),
+            Ok(SuperType::from(7))
+        );
+        assert_eq!(
+            context.run("f:{x*2}; f 3 4"),
+            Ok(SuperType::from(vec![6, 8]))
+        );
+        assert_eq!(context.run("{1}[]"), Ok(SuperType::from(1)));
+        assert_eq!(context.run("{x+y}[1;2;3]"), Err(Error::Rank));
+        assert_eq!(context.run("{x+y}").unwrap().to_string(), "{[x;y] ..}");
+
+        // a lambda sees the globals rather than the locals of its caller
+        assert_eq!(
+            context.run("a:100; g:{x+a}; h:{[a] g a}; h 1"),
+            Ok(SuperType::from(101))
+        );
+        // and sets globals with ::
+        assert_eq!(context.run("{c:x; d::x}[5]; d"), Ok(SuperType::from(5)));
+        assert!(matches!(context.run("c"), Err(Error::Name(_))));
+    }
+
+    #[test]
+    fn test_amends() {
+        let mut context = ExecutionContext::default();
+        assert_eq!(context.run("x:til 3; x,:3 4"), Ok(SuperType::Nothing));
+        assert_eq!(
+            context.run("x,:5; x[0]:9; x[1 2]+:10; x-:1; x"),
+            Ok(SuperType::from(vec![8, 10, 11, 2, 3, 4]))
+        );
+        assert_eq!(context.run("x[0]:1.5"), Err(Error::Type));
+        assert_eq!(context.run("x[9]:1"), Err(Error::Length));
+        // a typed list keeps its type, a failed amend leaving the variable as it was
+        assert_eq!(context.run("x,:`a"), Err(Error::Type));
+        assert_eq!(context.run("x,:1.5"), Err(Error::Type));
+        assert_eq!(
+            context.run("x,:(); x"),
+            Ok(SuperType::from(vec![8, 10, 11, 2, 3, 4]))
+        );
+        assert_eq!(context.run("y,:1"), Err(Error::Name("y".to_owned())));
+
+        assert_eq!(
+            context
+                .run("b:10b; b,:1b; b[0]:0b; s:`a; s,:`b`c; (b;s)")
+                .unwrap()
+                .to_string(),
+            "(001b;`a`b`c)"
+        );
+        assert_eq!(
+            context.run("d:`a`b!1 2; d[`c]:3; d[`a]*:5; d"),
+            context.run("`a`b`c!5 2 3")
+        );
+
+        // a lambda amends its locals, then the globals
+        assert_eq!(
+            context.run("n:1; f:{n+:x; x,:0; x}; f 1 2"),
+            Ok(SuperType::from(vec![1, 2, 0]))
+        );
+        assert_eq!(context.run("n"), Ok(SuperType::from(vec![2, 3])));
+
+        // but peach cannot reach them
+        context.set_threads(2).unwrap();
+        assert_eq!(context.run("{n,:x} peach 1 2"), Err(Error::NoUpdate));
+    }
+
+    #[test]
+    fn test_amends_copy_on_write() {
+        let mut context = ExecutionContext::default();
+        context.run("x:til 1000; y:x").unwrap();
+        let shared = |context: &ExecutionContext| {
+            Arc::ptr_eq(
+                &context.variables.get("x").unwrap(),
+                &context.variables.get("y").unwrap(),
+            )
+        };
+        assert!(shared(&context));
+
+        // x gets a copy of its own the first time, then grows in place
+        context.run("x,:1000").unwrap();
+        assert!(!shared(&context));
+        let held = Arc::as_ptr(&context.variables.get("x").unwrap());
+        context.run("x,:1001; x[0]:-1").unwrap();
+        assert_eq!(Arc::as_ptr(&context.variables.get("x").unwrap()), held);
+
+        assert_eq!(context.run("count y"), Ok(SuperType::from(1000)));
+        assert_eq!(
+            context.run("(count x;first x;last x)"),
+            Ok(SuperType::from(vec![1002, -1, 1001]))
+        );
+    }
+
+    #[test]
+    fn test_threads_give_the_serial_results() {
+        let program = "x:1.1*til 300000; y:-7+til 300000; \
+                       (x+x;x%3;y*y;x<y;sum x;avg y;min x;max y;sum y)";
+        let serial = ExecutionContext::default().run(program);
+        let mut threaded = ExecutionContext::default();
+        threaded.set_threads(4).unwrap();
+        assert_eq!(threaded.run(program), serial);
+
+        // overflows in the threads raise on the calling one
+        threaded.set_overflow(Overflow::Raise);
+        assert_eq!(threaded.run("2147483000+til 300000"), Err(Error::Overflow));
+        assert_eq!(threaded.run("sum 300000#2147483"), Err(Error::Overflow));
+    }
 }
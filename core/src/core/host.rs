@@ -1,8 +1,22 @@
-use crate::actor::Actor;
+use crate::{Error, actor::Actor};
 
 use crate::core::execution_context::ExecutionContext;
 
+#[derive(Default)]
 pub struct Host {
     global_context: ExecutionContext,
+    #[allow(dead_code)] // actors are not scheduled yet
     actors: Vec<Box<dyn Actor>>,
 }
+
+impl Host {
+    pub fn global_context(&mut self) -> &mut ExecutionContext {
+        &mut self.global_context
+    }
+
+    /// Gives the host `threads` secondary threads for operations on long lists, see
+    /// `ExecutionContext::set_threads`.
+    pub fn set_threads(&mut self, threads: usize) -> Result<(), Error> {
+        self.global_context.set_threads(threads)
+    }
+}
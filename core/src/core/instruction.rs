@@ -1,6 +1,6 @@
-use std::rc::Rc;
+use std::sync::Arc;
 
-use crate::lang::{SuperType, invokable::operator::Operator};
+use crate::lang::{SuperType, invokable::operator::Operator, parse_tree::Query};
 
 #[derive(Debug, Clone)]
 pub enum Instruction {
@@ -11,12 +11,6 @@ pub enum Instruction {
     // Ending A non-silent statement implicitely invokes Return (which returns the last rvalue)
     EndStatement,
 
-    // A Lambda creates a new context to run with. That context becomes a child of the current context.
-    Lambda {
-        args_push: Vec<Instruction>,
-        body: Vec<Instruction>,
-    },
-
     // A Return ends the current context and returns the last rvalue pushed onto the rvalue stack.
     Return,
 
@@ -26,7 +20,7 @@ pub enum Instruction {
     },
     SetVariableRef {
         name: String,
-        value: Rc<SuperType>,
+        value: Arc<SuperType>,
     },
     GetVariable {
         name: String,
@@ -37,12 +31,38 @@ pub enum Instruction {
     },
     SetGlobalVariableRef {
         name: String,
-        value: Rc<SuperType>,
+        value: Arc<SuperType>,
     },
     GetGlobalVariable {
         name: String,
     },
 
+    // Pops the last rvalue and assigns it to a local variable
+    Assign {
+        name: String,
+    },
+    // Pops the last rvalue and assigns it to a global variable
+    AssignGlobal {
+        name: String,
+    },
+
+    // Pops the index when `indexed` then the value, and changes the variable where it is defined
+    Amend {
+        name: String,
+        op: Option<Operator>,
+        indexed: bool,
+    },
+
+    // Pops a function then its arguments, first argument on top, and pushes the result
+    Invoke {
+        arity: usize,
+    },
+
+    // Pops `count` rvalues, first item on top, into a list
+    EnList {
+        count: usize,
+    },
+
     UnaryOperation {
         op: String,
         value: SuperType,
@@ -53,4 +73,7 @@ pub enum Instruction {
         right: SuperType,
     },
     Literal(SuperType),
+
+    // Runs a qSQL query, its phrases are evaluated in child contexts
+    Query(Box<Query>),
 }
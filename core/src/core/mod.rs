@@ -1,4 +1,6 @@
 pub mod execution_context;
 pub mod host;
 pub mod instruction;
+mod peach;
+mod query;
 pub mod variable;
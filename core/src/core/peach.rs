@@ -0,0 +1,129 @@
+//! peach and .Q.fc, which apply a function on the secondary threads. Each thread runs it in a
+//! context of its own that reads the globals as they were when it started, and setting one
+//! there raises 'noupdate, as in q.
+
+use std::ops::Range;
+
+use rayon::ThreadPool;
+
+use crate::{
+    Error,
+    core::execution_context::ExecutionContext,
+    lang::{
+        SuperType,
+        invokable::{list, operator, parallel},
+    },
+};
+
+impl ExecutionContext {
+    /// f peach x: f applied to each item of x, the items shared between the secondary threads.
+    /// Without any, it is f applied to each item on the calling thread.
+    pub(crate) fn peach(&mut self, f: &SuperType, x: SuperType) -> Result<SuperType, Error> {
+        if x.is_atom() {
+            return self.apply(f, vec![x]);
+        }
+        let items = x.into_items();
+        let Some(pool) = parallel::current() else {
+            let results = items.into_iter().map(|item| self.apply(f, vec![item]));
+            return Ok(SuperType::from_items(results.collect::<Result<_, _>>()?));
+        };
+        let runs = self.on_workers(&pool, items.len(), |worker, run| {
+            let results = items[run]
+                .iter()
+                .map(|item| worker.apply(f, vec![item.clone()]));
+            results.collect::<Result<Vec<_>, _>>()
+        });
+        Ok(SuperType::from_items(runs?.concat()))
+    }
+
+    /// .Q.fc[f;x]: f applied to x cut into one run of items for each secondary thread, the
+    /// results joined. f must take a list and give one back, as it does for a vector.
+    pub(crate) fn fc(&mut self, f: &SuperType, x: SuperType) -> Result<SuperType, Error> {
+        let pool = match parallel::current() {
+            Some(pool) if !x.is_atom() => pool,
+            _ => return self.apply(f, vec![x]),
+        };
+        let runs = self.on_workers(&pool, x.count(), |worker, run| {
+            worker.apply(f, vec![x.select(&run.collect::<Vec<_>>())?])
+        });
+        list::raze(SuperType::from_items(runs?))
+    }
+
+    // Runs `f` in a worker context for each run of `len` items on the threads of `pool`, the
+    // first error in the order of the runs standing for them all
+    fn on_workers<T: Send>(
+        &self,
+        pool: &ThreadPool,
+        len: usize,
+        f: impl Fn(&mut ExecutionContext, Range<usize>) -> Result<T, Error> + Sync,
+    ) -> Result<Vec<T>, Error> {
+        let globals = self.globals();
+        let overflow = operator::overflow();
+        let runs = parallel::split(pool, len, 1, |run| {
+            let mut worker = ExecutionContext::worker(globals.clone());
+            operator::with_overflow(overflow, || f(&mut worker, run))
+        });
+        runs.into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn threaded() -> ExecutionContext {
+        let mut context = ExecutionContext::default();
+        context.set_threads(3).unwrap();
+        context
+    }
+
+    #[test]
+    fn test_values_cross_threads() {
+        fn shareable<T: Send + Sync>() {}
+        shareable::<SuperType>();
+    }
+
+    #[test]
+    fn test_peach_gives_what_each_gives() {
+        for program in [
+            "{x*2} peach 1 2 3",
+            "g:10; {x+g} peach til 7",
+            "{[s] count s} peach (\"ab\";\"c\";\"\")",
+            "{x} peach ()",
+            "sum peach (1 2;3 4 5)",
+            ".Q.fc[{x+1};til 10]",
+        ] {
+            let serial = ExecutionContext::default().run(program);
+            assert!(serial.is_ok(), "{program}");
+            assert_eq!(threaded().run(program), serial, "{program}");
+        }
+    }
+
+    #[test]
+    fn test_workers_cannot_set_globals() {
+        let mut context = threaded();
+        assert_eq!(context.run("{a::x} peach 1 2"), Err(Error::NoUpdate));
+        assert!(matches!(context.run("a"), Err(Error::Name(_))));
+        // locals are fine, and without secondary threads peach runs in place
+        assert_eq!(
+            context.run("{b:x; b} peach 1 2"),
+            Ok(SuperType::from(vec![1, 2]))
+        );
+        let mut serial = ExecutionContext::default();
+        assert_eq!(serial.run("{a::x} peach 1 2; a"), Ok(SuperType::from(2)));
+    }
+
+    #[test]
+    fn test_workers_overflow_as_the_context_does() {
+        let mut context = threaded();
+        context.set_overflow(operator::Overflow::Raise);
+        assert_eq!(
+            context.run("{x+2147483647} peach 0 1"),
+            Err(Error::Overflow)
+        );
+        assert_eq!(
+            context.run("{x+1} peach 0 1"),
+            Ok(SuperType::from(vec![1, 2]))
+        );
+    }
+}
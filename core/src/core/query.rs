@@ -0,0 +1,523 @@
+use std::sync::Arc;
+
+use crate::{
+    Error,
+    core::execution_context::ExecutionContext,
+    lang::{
+        SuperType,
+        invokable::list,
+        parse_tree::{ParseTreeNode, Query, QueryKind, column_name},
+        type_system::{Grouping, TypeDictionary, TypeMixedList, TypeTable, row_keys},
+    },
+};
+
+type Bindings = Vec<(String, Arc<SuperType>)>;
+
+// The table a query reads from, keyed tables are unkeyed and rekeyed on the way out
+struct Source {
+    table: TypeTable,
+    keys: Vec<String>,
+}
+
+impl Source {
+    fn new(value: SuperType) -> Result<Self, Error> {
+        match value {
+            SuperType::Table(table) => Ok(Source {
+                table,
+                keys: vec![],
+            }),
+            SuperType::Dictionary(keyed) if keyed.is_keyed_table() => {
+                let keys = match keyed.keys() {
+                    SuperType::Table(keys) => keys.columns().to_vec(),
+                    _ => unreachable!(),
+                };
+                Ok(Source {
+                    table: list::unkey(keyed)?,
+                    keys,
+                })
+            }
+            _ => Err(Error::Type),
+        }
+    }
+
+    // Keys the table again when it still starts with the key columns
+    fn rekey(&self, table: TypeTable) -> Result<SuperType, Error> {
+        if self.keys.is_empty() || !table.columns().starts_with(&self.keys) {
+            return Ok(SuperType::Table(table));
+        }
+        list::dict(
+            SuperType::from(self.keys.len() as i32),
+            SuperType::Table(table),
+        )
+    }
+
+    // The columns at `rows` as variables, along with the virtual row index `i`
+    fn bindings(&self, rows: &[usize]) -> Result<Bindings, Error> {
+        let mut bindings = vec![(
+            "i".to_owned(),
+            Arc::new(SuperType::from(
+                rows.iter().map(|&r| r as i32).collect::<Vec<_>>(),
+            )),
+        )];
+        for (name, column) in self.table.columns().iter().zip(self.table.data()) {
+            bindings.push((name.clone(), Arc::new(column.select(rows)?)));
+        }
+        Ok(bindings)
+    }
+}
+
+// Keeps the rows where the constraint is true
+fn filter(rows: Vec<usize>, mask: SuperType) -> Result<Vec<usize>, Error> {
+    match mask {
+        SuperType::Bools(mask) if mask.len() == rows.len() => {
+            Ok(mask.ones().into_iter().map(|i| rows[i]).collect())
+        }
+        SuperType::Bools(_) => Err(Error::Length),
+        SuperType::Bool(keep) => match keep.data().0 {
+            true => Ok(rows),
+            false => Ok(vec![]),
+        },
+        _ => Err(Error::Type),
+    }
+}
+
+// Computed columns are as long as the longest of them, atoms are repeated
+fn broadcast_columns(columns: Vec<SuperType>) -> Result<Vec<SuperType>, Error> {
+    let count = columns
+        .iter()
+        .filter(|column| !column.is_atom())
+        .map(SuperType::count)
+        .max()
+        .unwrap_or(1);
+    columns
+        .into_iter()
+        .map(|column| column.broadcast(count))
+        .collect()
+}
+
+// Writes `values` at `rows` of `column`, the other rows of a new column are nulls
+fn amend(
+    column: Option<&SuperType>,
+    count: usize,
+    rows: &[usize],
+    values: SuperType,
+) -> Result<SuperType, Error> {
+    let values = values.broadcast(rows.len())?;
+    let mut items = match column {
+        Some(column) => column.clone().into_items(),
+        None => vec![values.null_item(); count],
+    };
+    for (&row, value) in rows.iter().zip(values.into_items()) {
+        items[row] = value;
+    }
+    Ok(SuperType::from_items(items))
+}
+
+/// Rows sharing the same values in the `by` columns, in order of first appearance.
+struct Groups {
+    keys: Vec<Vec<SuperType>>,
+    rows: Vec<Vec<usize>>,
+}
+
+impl Groups {
+    fn new(by: &[SuperType], rows: &[usize]) -> Result<Self, Error> {
+        if by.iter().any(|column| column.count() != rows.len()) {
+            return Err(Error::Length);
+        }
+        let columns: Vec<&SuperType> = by.iter().collect();
+        let grouping = Grouping::new(row_keys(&columns));
+        let keys = grouping
+            .firsts()
+            .into_iter()
+            .map(|first| by.iter().filter_map(|column| column.item(first)).collect())
+            .collect();
+        let rows = grouping
+            .into_positions()
+            .into_iter()
+            .map(|positions| positions.into_iter().map(|p| rows[p]).collect())
+            .collect();
+        Ok(Groups { keys, rows })
+    }
+
+    // One list per `by` column holding the key of each group
+    fn key_columns(&self, count: usize) -> Vec<SuperType> {
+        (0..count)
+            .map(|i| SuperType::from_items(self.keys.iter().map(|key| key[i].clone()).collect()))
+            .collect()
+    }
+}
+
+impl ExecutionContext {
+    /// Runs a qSQL query: constraints filter rows left to right, then columns are computed
+    /// over what is left, per group when there is a `by` phrase.
+    /// A table given by name is read from the global, which updates and deletes then amend,
+    /// returning the name as q does.
+    pub(crate) fn query(&mut self, query: &Query) -> Result<SuperType, Error> {
+        let name = match self.evaluate(&[], &query.from)? {
+            SuperType::Symbol(name) => name.data().0.clone(),
+            table => return self.run_query(query, Source::new(table)?),
+        };
+        let table = self.get_global(&name).ok_or(Error::Name(name.clone()))?;
+        let result = self.run_query(query, Source::new((*table).clone())?)?;
+        match query.kind {
+            QueryKind::Update | QueryKind::Delete => {
+                self.set_global_new(name.clone(), result)?;
+                Ok(SuperType::symbol(name.as_str()))
+            }
+            QueryKind::Select | QueryKind::Exec => Ok(result),
+        }
+    }
+
+    fn run_query(&mut self, query: &Query, source: Source) -> Result<SuperType, Error> {
+        let mut rows: Vec<usize> = (0..source.table.row_count()).collect();
+        for constraint in &query.constraints {
+            let mask = self.evaluate(&source.bindings(&rows)?, constraint)?;
+            rows = filter(rows, mask)?;
+        }
+
+        let groups = match query.by.is_empty() {
+            true => None,
+            false => {
+                let bindings = source.bindings(&rows)?;
+                let by = query
+                    .by
+                    .iter()
+                    .map(|(_, node)| self.evaluate(&bindings, node))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Some(Groups::new(&by, &rows)?)
+            }
+        };
+
+        match query.kind {
+            QueryKind::Select | QueryKind::Exec => match groups {
+                None => self.select(query, &source, &rows),
+                Some(groups) => self.select_by(query, &source, &groups),
+            },
+            QueryKind::Update => self.update(query, &source, &rows, groups),
+            QueryKind::Delete => delete(query, source, &rows),
+        }
+    }
+
+    fn compute(
+        &mut self,
+        columns: &[(Option<String>, ParseTreeNode)],
+        bindings: &Bindings,
+    ) -> Result<Vec<SuperType>, Error> {
+        columns
+            .iter()
+            .map(|(_, node)| self.evaluate(bindings, node))
+            .collect()
+    }
+
+    fn select(
+        &mut self,
+        query: &Query,
+        source: &Source,
+        rows: &[usize],
+    ) -> Result<SuperType, Error> {
+        if query.columns.is_empty() {
+            return match query.kind {
+                QueryKind::Select => source.rekey(source.table.select_rows(rows)?),
+                _ => Err(Error::NotYetImplemented),
+            };
+        }
+
+        let names: Vec<String> = query
+            .columns
+            .iter()
+            .map(|(name, node)| column_name(name, node))
+            .collect();
+        let values = self.compute(&query.columns, &source.bindings(rows)?)?;
+
+        match query.kind {
+            QueryKind::Exec if query.columns.len() == 1 && query.columns[0].0.is_none() => {
+                Ok(values.into_iter().next().unwrap())
+            }
+            QueryKind::Exec => Ok(SuperType::Dictionary(TypeDictionary::new(
+                SuperType::symbols(names),
+                SuperType::MixedList(TypeMixedList(values)),
+            )?)),
+            _ => Ok(SuperType::Table(TypeTable::new(
+                names,
+                broadcast_columns(values)?,
+            )?)),
+        }
+    }
+
+    fn select_by(
+        &mut self,
+        query: &Query,
+        source: &Source,
+        groups: &Groups,
+    ) -> Result<SuperType, Error> {
+        let by_names: Vec<String> = query
+            .by
+            .iter()
+            .map(|(name, node)| column_name(name, node))
+            .collect();
+
+        // without columns, select by keeps the last row of each group
+        let (names, values) = match query.columns.is_empty() {
+            true => {
+                let last: Vec<usize> = groups
+                    .rows
+                    .iter()
+                    .filter_map(|r| r.last().copied())
+                    .collect();
+                let (names, data): (Vec<String>, Vec<SuperType>) = source
+                    .table
+                    .columns()
+                    .iter()
+                    .zip(source.table.data())
+                    .filter(|(name, _)| !by_names.contains(name))
+                    .map(|(name, column)| Ok((name.clone(), column.select(&last)?)))
+                    .collect::<Result<Vec<_>, Error>>()?
+                    .into_iter()
+                    .unzip();
+                (names, data)
+            }
+            false => {
+                let mut cells = vec![Vec::with_capacity(groups.rows.len()); query.columns.len()];
+                for rows in &groups.rows {
+                    let values = self.compute(&query.columns, &source.bindings(rows)?)?;
+                    for (column, value) in cells.iter_mut().zip(values) {
+                        column.push(value);
+                    }
+                }
+                let names = query
+                    .columns
+                    .iter()
+                    .map(|(name, node)| column_name(name, node))
+                    .collect();
+                (
+                    names,
+                    cells.into_iter().map(SuperType::from_items).collect(),
+                )
+            }
+        };
+
+        let keys = groups.key_columns(by_names.len());
+        // when every column is a key there are no values to key, only the distinct keys
+        if names.is_empty() {
+            return match (query.kind, keys.len()) {
+                (QueryKind::Exec, 1) => Ok(keys.into_iter().next().unwrap()),
+                _ => Ok(SuperType::Table(TypeTable::new(by_names, keys)?)),
+            };
+        }
+        match query.kind {
+            QueryKind::Exec => {
+                let keys = match keys.len() {
+                    1 => keys.into_iter().next().unwrap(),
+                    _ => SuperType::Table(TypeTable::new(by_names, keys)?),
+                };
+                let values = match query.columns.len() == 1 && query.columns[0].0.is_none() {
+                    true => values.into_iter().next().unwrap(),
+                    false => SuperType::Table(TypeTable::new(names, values)?),
+                };
+                Ok(SuperType::Dictionary(TypeDictionary::new(keys, values)?))
+            }
+            _ => Ok(SuperType::Dictionary(TypeDictionary::new(
+                SuperType::Table(TypeTable::new(by_names, keys)?),
+                SuperType::Table(TypeTable::new(names, values)?),
+            )?)),
+        }
+    }
+
+    fn update(
+        &mut self,
+        query: &Query,
+        source: &Source,
+        rows: &[usize],
+        groups: Option<Groups>,
+    ) -> Result<SuperType, Error> {
+        let count = source.table.row_count();
+        let groups = groups.map_or_else(|| vec![rows.to_vec()], |groups| groups.rows);
+
+        let (mut names, mut data) = source.table.clone().into_parts();
+        for rows in &groups {
+            let values = self.compute(&query.columns, &source.bindings(rows)?)?;
+            for ((name, node), value) in query.columns.iter().zip(values) {
+                let name = column_name(name, node);
+                match names.iter().position(|n| *n == name) {
+                    Some(i) => data[i] = amend(Some(&data[i]), count, rows, value)?,
+                    None => {
+                        data.push(amend(None, count, rows, value)?);
+                        names.push(name);
+                    }
+                }
+            }
+        }
+        source.rekey(TypeTable::new(names, data)?)
+    }
+}
+
+fn delete(query: &Query, source: Source, rows: &[usize]) -> Result<SuperType, Error> {
+    // delete columns from t, or delete from t where ... for rows
+    if !query.columns.is_empty() {
+        let dropped: Vec<String> = query
+            .columns
+            .iter()
+            .map(|(name, node)| column_name(name, node))
+            .collect();
+        // with constraints too, the columns are cleared on the rows they keep
+        if !query.constraints.is_empty() {
+            let count = source.table.row_count();
+            let (names, mut data) = source.table.clone().into_parts();
+            for (name, column) in names.iter().zip(data.iter_mut()) {
+                if dropped.contains(name) {
+                    *column = amend(Some(column), count, rows, column.null_item())?;
+                }
+            }
+            return source.rekey(TypeTable::new(names, data)?);
+        }
+        let (names, data): (Vec<String>, Vec<SuperType>) = source
+            .table
+            .clone()
+            .into_parts()
+            .0
+            .into_iter()
+            .zip(source.table.data().iter().cloned())
+            .filter(|(name, _)| !dropped.contains(name))
+            .unzip();
+        return source.rekey(TypeTable::new(names, data)?);
+    }
+
+    let kept: Vec<usize> = (0..source.table.row_count())
+        .filter(|row| rows.binary_search(row).is_err())
+        .collect();
+    source.rekey(source.table.select_rows(&kept)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{run, with_tables};
+
+    #[test]
+    fn test_select_where_filters_left_to_right() {
+        let result = run(&with_tables("select px from t where sym=`a, px>1")).unwrap();
+        let expected = TypeTable::new(vec!["px".to_owned()], vec![SuperType::from(vec![3])]);
+        assert_eq!(result, SuperType::Table(expected.unwrap()));
+    }
+
+    #[test]
+    fn test_select_by_groups() {
+        let result = run(&with_tables("select n:count qty by sym from t")).unwrap();
+        let SuperType::Dictionary(keyed) = result else {
+            panic!("expected a keyed table");
+        };
+        assert_eq!(
+            list::index(keyed.values(), SuperType::symbol("n")),
+            Ok(SuperType::from(vec![2, 1, 1]))
+        );
+    }
+
+    #[test]
+    fn test_select_by_every_column_gives_the_distinct_keys() {
+        assert_eq!(
+            run(&with_tables(
+                "select by sym, px from (select sym, px from t), ([] sym:`a; px:1)"
+            )),
+            run("([] sym:`a`b`a`c; px:1 2 3 4)")
+        );
+        assert_eq!(
+            run(&with_tables("exec by sym from select sym from t")),
+            Ok(SuperType::symbols(vec![
+                "a".to_owned(),
+                "b".to_owned(),
+                "c".to_owned()
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_exec_returns_column() {
+        assert_eq!(
+            run(&with_tables("exec qty from t where px<3")).unwrap(),
+            SuperType::from(vec![10, 20])
+        );
+    }
+
+    #[test]
+    fn test_update_and_delete() {
+        let result = run(&with_tables(
+            "exec qty from update qty:0 from t where sym=`a",
+        ))
+        .unwrap();
+        assert_eq!(result, SuperType::from(vec![0, 20, 0, 40]));
+
+        let result = run(&with_tables("count delete from t where px>2")).unwrap();
+        assert_eq!(result, SuperType::from(2));
+
+        let result = run(&with_tables("cols: delete px from t; count cols`sym")).unwrap();
+        assert_eq!(result, SuperType::from(4));
+    }
+
+    #[test]
+    fn test_update_new_column_on_some_rows() {
+        let result = run(&with_tables("exec z from update z:1 from t where sym=`a"));
+        assert_eq!(result, run("1 0N 1 0N"));
+        let result = run(&with_tables("exec m from update m:max px by sym from t"));
+        assert_eq!(result, run("3 2 3 4"));
+        let result = run(&with_tables("exec s from update s:`x from t where px>2"));
+        assert_eq!(result, run("```x`x"));
+    }
+
+    #[test]
+    fn test_delete_columns_where_clears_them() {
+        let result = run(&with_tables(
+            "d:delete px from t where sym=`a; (exec px from d; exec qty from d)",
+        ));
+        assert_eq!(result, run("(0N 2 0N 4; 10 20 30 40)"));
+    }
+
+    #[test]
+    fn test_tables_by_name_are_amended() {
+        let result = run(&with_tables(
+            "r:![`t; enlist (=;`sym;enlist `a); 0b; (enlist `px)!enlist 0]; (r; exec px from t)",
+        ));
+        assert_eq!(result, run("(`t; 0 2 0 4)"));
+        let result = run(&with_tables(
+            "update qty:1 from `t; delete from `t where px>3; (exec qty from t; exec px from `t)",
+        ));
+        assert_eq!(result, run("(1 1 1; 1 2 3)"));
+        // a lambda amends the global
+        let result = run(&with_tables("{update px:x from `t}[7]; exec px from t"));
+        assert_eq!(result, run("7 7 7 7"));
+        assert_eq!(
+            run("update a:1 from `nope"),
+            Err(Error::Name("nope".to_owned()))
+        );
+    }
+
+    #[test]
+    fn test_functional_forms() {
+        let expected = run(&with_tables("select q:qty from t where px>2")).unwrap();
+        assert_eq!(
+            run(&with_tables(
+                "?[t; enlist (>;`px;2); 0b; (enlist `q)!enlist `qty]"
+            ))
+            .unwrap(),
+            expected
+        );
+        assert_eq!(
+            run(&with_tables(
+                "eval parse \"select q:qty from t where px>2\""
+            ))
+            .unwrap(),
+            expected
+        );
+
+        let result = run(&with_tables(
+            "p: parse \"exec qty by sym from t\"; ?[t; p 2; p 3; p 4]",
+        ))
+        .unwrap();
+        assert_eq!(result, run(&with_tables("exec qty by sym from t")).unwrap());
+
+        let result = run(&with_tables("![`t; enlist (=;`sym;,`a); 0b; 0#`sym]; t")).unwrap();
+        assert_eq!(
+            result,
+            run(&with_tables("delete from t where sym=`a")).unwrap()
+        );
+    }
+}
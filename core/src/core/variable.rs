@@ -1,26 +1,42 @@
-use std::{collections::HashMap, rc::Rc};
+use std::{collections::HashMap, sync::Arc};
 
-use crate::lang::SuperType;
+use crate::{Error, lang::SuperType};
 
 #[derive(Clone, Debug, Default)]
-pub struct Variables(HashMap<String, Rc<SuperType>>);
+pub struct Variables(HashMap<String, Arc<SuperType>>);
 
 impl Variables {
-    pub fn set_ref(&mut self, name: String, value: Rc<SuperType>) -> Rc<SuperType> {
+    pub fn set_ref(&mut self, name: String, value: Arc<SuperType>) -> Arc<SuperType> {
         self.0.insert(name, value.clone());
         value
     }
 
-    pub fn set_new(&mut self, name: String, value: SuperType) -> Rc<SuperType> {
-        let rc_value = Rc::new(value);
-        self.0.insert(name, rc_value.clone());
-        rc_value
+    pub fn set_new(&mut self, name: String, value: SuperType) -> Arc<SuperType> {
+        let value = Arc::new(value);
+        self.0.insert(name, value.clone());
+        value
     }
 
-    pub fn get(&self, name: &str) -> Option<Rc<SuperType>> {
+    pub fn get(&self, name: &str) -> Option<Arc<SuperType>> {
         self.0.get(name).cloned()
     }
 
+    pub fn contains(&self, name: &str) -> bool {
+        self.0.contains_key(name)
+    }
+
+    /// Changes a variable through `f` in place, copying its value first only when something
+    /// else holds it too.
+    pub fn amend(
+        &mut self,
+        name: &str,
+        f: impl FnOnce(&mut SuperType) -> Result<(), Error>,
+    ) -> Result<Arc<SuperType>, Error> {
+        let value = self.0.get_mut(name).ok_or(Error::Name(name.to_owned()))?;
+        f(Arc::make_mut(value))?;
+        Ok(value.clone())
+    }
+
     pub fn clear(&mut self) {
         self.0.clear();
     }
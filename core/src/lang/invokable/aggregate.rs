@@ -0,0 +1,760 @@
+use std::collections::VecDeque;
+
+use crate::{
+    Error,
+    lang::{
+        SuperType,
+        invokable::{
+            arithmetic::{self, is_float},
+            comparison::{self, Operand, floats, ints},
+            kernel,
+            math::{INT_NULL, each},
+            operator, parallel,
+        },
+        type_system::{Atom, Nullable, TypeDictionary, TypeInt, TypeMixedList},
+    },
+};
+
+pub(crate) type Monadic = fn(SuperType) -> Result<SuperType, Error>;
+type Dyadic = fn(SuperType, SuperType) -> Result<SuperType, Error>;
+
+/// The numbers the aggregations run over, with q's nulls and infinities.
+/// Ints wrap around, noting overflows for the kernels to raise afterwards.
+trait Number: Copy + PartialOrd + Send + Sync {
+    const NULL: Self;
+    const ZERO: Self;
+    const ONE: Self;
+    const LOWEST: Self;
+    const HIGHEST: Self;
+
+    fn is_null(self) -> bool;
+    fn add(self, other: Self) -> Self;
+    fn sub(self, other: Self) -> Self;
+    fn mul(self, other: Self) -> Self;
+    fn to_float(self) -> f64;
+}
+
+impl Number for i32 {
+    const NULL: Self = INT_NULL;
+    const ZERO: Self = 0;
+    const ONE: Self = 1;
+    const LOWEST: Self = TypeInt::NEGATIVE_INFINITY.unwrap().0;
+    const HIGHEST: Self = TypeInt::INFINITY.unwrap().0;
+
+    fn is_null(self) -> bool {
+        self == INT_NULL
+    }
+    fn add(self, other: Self) -> Self {
+        let (value, overflowed) = self.overflowing_add(other);
+        if overflowed || value == INT_NULL {
+            operator::note_overflow();
+        }
+        value
+    }
+    fn sub(self, other: Self) -> Self {
+        let (value, overflowed) = self.overflowing_sub(other);
+        if overflowed || value == INT_NULL {
+            operator::note_overflow();
+        }
+        value
+    }
+    fn mul(self, other: Self) -> Self {
+        let (value, overflowed) = self.overflowing_mul(other);
+        if overflowed || value == INT_NULL {
+            operator::note_overflow();
+        }
+        value
+    }
+    fn to_float(self) -> f64 {
+        match self {
+            INT_NULL => f64::NAN,
+            i => i as f64,
+        }
+    }
+}
+
+impl Number for f64 {
+    const NULL: Self = f64::NAN;
+    const ZERO: Self = 0.0;
+    const ONE: Self = 1.0;
+    const LOWEST: Self = f64::NEG_INFINITY;
+    const HIGHEST: Self = f64::INFINITY;
+
+    fn is_null(self) -> bool {
+        self.is_nan()
+    }
+    fn add(self, other: Self) -> Self {
+        self + other
+    }
+    fn sub(self, other: Self) -> Self {
+        self - other
+    }
+    fn mul(self, other: Self) -> Self {
+        self * other
+    }
+    fn to_float(self) -> f64 {
+        self
+    }
+}
+
+enum Numbers {
+    Ints(Vec<i32>),
+    Floats(Vec<f64>),
+}
+
+fn items<T>(operand: Operand<T>) -> Vec<T> {
+    match operand {
+        Operand::Atom(v) => vec![v],
+        Operand::List(v) => v,
+    }
+}
+
+// The values of an atom or a typed list of numbers, bools and bytes as ints
+fn numbers(value: &SuperType) -> Result<Numbers, Error> {
+    match is_float(value) {
+        true => floats(value).map(|f| Numbers::Floats(items(f))),
+        false => ints(value).map(|i| Numbers::Ints(items(i))),
+    }
+    .ok_or(Error::Type)
+}
+
+pub(crate) fn to_floats(value: &SuperType) -> Result<Vec<f64>, Error> {
+    Ok(match numbers(value)? {
+        Numbers::Ints(v) => v.into_iter().map(Number::to_float).collect(),
+        Numbers::Floats(v) => v,
+    })
+}
+
+// Runs the same generic kernel over ints or floats
+macro_rules! with_numbers {
+    ($value:expr, $v:ident => $body:expr) => {
+        match numbers($value)? {
+            Numbers::Ints($v) => SuperType::from(operator::checking_overflow(|| $body)?),
+            Numbers::Floats($v) => SuperType::from($body),
+        }
+    };
+}
+
+// A running or moving function of an atom gives an atom
+fn shaped(value: &SuperType, result: SuperType) -> SuperType {
+    match value.is_atom() {
+        true => result.item(0).unwrap_or(result),
+        false => result,
+    }
+}
+
+fn fold<T: Number>(values: &[T], start: T, f: impl Fn(T, T) -> T) -> T {
+    values
+        .iter()
+        .filter(|v| !v.is_null())
+        .fold(start, |acc, &v| f(acc, v))
+}
+
+// As fold for a `pick` of one of two items, which gives the same over runs of the items as
+// over all of them, so long lists are split between the secondary threads
+fn extreme<T: Number>(values: &[T], start: T, pick: fn(T, T) -> T) -> T {
+    let runs = parallel::runs(values.len(), 1, |run| fold(&values[run], start, pick));
+    fold(&runs, start, pick)
+}
+
+fn lesser<T: Number>(a: T, b: T) -> T {
+    if b < a { b } else { a }
+}
+
+fn greater<T: Number>(a: T, b: T) -> T {
+    if b > a { b } else { a }
+}
+
+/// Dictionaries aggregate their values and tables each column into a dictionary of the results.
+pub(crate) fn columns(value: &SuperType, this: Monadic) -> Option<Result<SuperType, Error>> {
+    match value {
+        SuperType::Dictionary(dictionary) => Some(this(dictionary.values().clone())),
+        SuperType::Table(table) => Some((|| {
+            let values = table
+                .data()
+                .iter()
+                .cloned()
+                .map(this)
+                .collect::<Result<Vec<_>, _>>()?;
+            let names = SuperType::symbols(table.columns().to_vec());
+            Ok(SuperType::Dictionary(TypeDictionary::new(
+                names,
+                SuperType::from_items(values),
+            )?))
+        })()),
+        _ => None,
+    }
+}
+
+// As columns, and mixed lists combine their items with `combine`, e.g. the sum of lists is
+// their item-wise sum
+fn aggregate_items(
+    value: &SuperType,
+    this: Monadic,
+    combine: Dyadic,
+) -> Option<Result<SuperType, Error>> {
+    match value {
+        SuperType::MixedList(list) => Some(match list.0.split_first() {
+            Some((first, rest)) => rest.iter().cloned().try_fold(first.clone(), combine),
+            None => this(SuperType::from(Vec::<i32>::new())),
+        }),
+        value => columns(value, this),
+    }
+}
+
+/// sum x: the sum of the items of x, nulls left out.
+pub fn sum(value: SuperType) -> Result<SuperType, Error> {
+    match &value {
+        // counting the set bits a word at a time
+        SuperType::Bools(bits) => return Ok(SuperType::from(bits.count_ones() as i32)),
+        SuperType::Ints(ints) => {
+            let sum = operator::checking_overflow(|| kernel::sum_ints(ints.data()))?;
+            return Ok(SuperType::from(Atom::new(sum)));
+        }
+        SuperType::Bytes(bytes) => {
+            let sum = operator::checking_overflow(|| kernel::sum_bytes(bytes.data()))?;
+            return Ok(SuperType::from(Atom::new(sum)));
+        }
+        SuperType::Floats(floats) => {
+            return Ok(SuperType::from(kernel::sum_floats(floats.data(), |f| f.0)));
+        }
+        _ => {}
+    }
+    if let Some(result) = aggregate_items(&value, sum, arithmetic::add) {
+        return result;
+    }
+    Ok(with_numbers!(&value, v => fold(&v, Number::ZERO, Number::add)))
+}
+
+/// prd x: the product of the items of x, nulls left out.
+pub fn prd(value: SuperType) -> Result<SuperType, Error> {
+    if let Some(result) = aggregate_items(&value, prd, arithmetic::mul) {
+        return result;
+    }
+    Ok(with_numbers!(&value, v => fold(&v, Number::ONE, Number::mul)))
+}
+
+// Chars are ordered by their codes, the blank standing for the extreme of no chars
+fn extreme_char(value: &SuperType, pick: fn(u8, u8) -> u8) -> Option<SuperType> {
+    match value {
+        SuperType::Char(_) => Some(value.clone()),
+        SuperType::Chars(chars) => {
+            let codes = chars.data().iter().map(|c| c.0);
+            Some(SuperType::char(codes.reduce(pick).unwrap_or(b' ')))
+        }
+        _ => None,
+    }
+}
+
+/// min x: the smallest item of x, infinity when x has no items.
+pub fn min(value: SuperType) -> Result<SuperType, Error> {
+    if let Some(result) = aggregate_items(&value, min, comparison::min) {
+        return result;
+    }
+    if let Some(result) = extreme_char(&value, std::cmp::min) {
+        return Ok(result);
+    }
+    Ok(with_numbers!(&value, v => extreme(&v, Number::HIGHEST, lesser)))
+}
+
+/// max x: the largest item of x, minus infinity when x has no items.
+pub fn max(value: SuperType) -> Result<SuperType, Error> {
+    if let Some(result) = aggregate_items(&value, max, comparison::max) {
+        return result;
+    }
+    if let Some(result) = extreme_char(&value, std::cmp::max) {
+        return Ok(result);
+    }
+    Ok(with_numbers!(&value, v => extreme(&v, Number::LOWEST, greater)))
+}
+
+/// avg x: the mean of the items of x as a float, nulls left out.
+pub fn avg(value: SuperType) -> Result<SuperType, Error> {
+    if let SuperType::MixedList(list) = &value {
+        let count = SuperType::from(list.0.len() as i32);
+        return arithmetic::div(sum(value)?, count);
+    }
+    if let Some(result) = columns(&value, avg) {
+        return result;
+    }
+    let values = to_floats(&value)?;
+    let count = values.iter().filter(|v| !v.is_nan()).count();
+    Ok(SuperType::from(
+        kernel::sum_floats(&values, |v| v) / count as f64,
+    ))
+}
+
+/// med x: the median of the items of x as a float, nulls left out.
+pub fn med(value: SuperType) -> Result<SuperType, Error> {
+    match &value {
+        SuperType::MixedList(list) if list.0.is_empty() => return Ok(SuperType::from(f64::NAN)),
+        SuperType::MixedList(_) => return Err(Error::Type),
+        _ => {}
+    }
+    if let Some(result) = columns(&value, med) {
+        return result;
+    }
+    let mut values: Vec<f64> = to_floats(&value)?
+        .into_iter()
+        .filter(|v| !v.is_nan())
+        .collect();
+    values.sort_by(f64::total_cmp);
+    let median = match values.len() {
+        0 => f64::NAN,
+        n if n % 2 == 1 => values[n / 2],
+        n => (values[n / 2 - 1] + values[n / 2]) / 2.0,
+    };
+    Ok(SuperType::from(median))
+}
+
+// Mixed lists run item after item with `step`, dictionaries and tables on their values and
+// columns
+fn running_items(
+    value: &SuperType,
+    this: Monadic,
+    step: Option<Dyadic>,
+) -> Option<Result<SuperType, Error>> {
+    match (value, step) {
+        (SuperType::MixedList(list), Some(step)) => {
+            let mut items: Vec<SuperType> = Vec::with_capacity(list.0.len());
+            for item in &list.0 {
+                let next = match items.last() {
+                    Some(previous) => match step(previous.clone(), item.clone()) {
+                        Ok(next) => next,
+                        Err(error) => return Some(Err(error)),
+                    },
+                    None => item.clone(),
+                };
+                items.push(next);
+            }
+            Some(Ok(SuperType::MixedList(TypeMixedList(items))))
+        }
+        (SuperType::MixedList(_), None) => Some(Err(Error::Type)),
+        (value, _) => each(value, this),
+    }
+}
+
+// Nulls leave the running value as it is
+fn scan<T: Number>(values: &[T], start: T, f: impl Fn(T, T) -> T) -> Vec<T> {
+    let mut acc = start;
+    values
+        .iter()
+        .map(|&v| {
+            if !v.is_null() {
+                acc = f(acc, v);
+            }
+            acc
+        })
+        .collect()
+}
+
+// As scan, but null until the first item that is not
+fn scan_extreme<T: Number>(values: &[T], pick: fn(T, T) -> T) -> Vec<T> {
+    let mut acc: Option<T> = None;
+    values
+        .iter()
+        .map(|&v| {
+            if !v.is_null() {
+                acc = Some(acc.map_or(v, |acc| pick(acc, v)));
+            }
+            acc.unwrap_or(T::NULL)
+        })
+        .collect()
+}
+
+/// sums x: the running sums of x.
+pub fn sums(value: SuperType) -> Result<SuperType, Error> {
+    if let Some(result) = running_items(&value, sums, Some(arithmetic::add)) {
+        return result;
+    }
+    Ok(shaped(
+        &value,
+        with_numbers!(&value, v => scan(&v, Number::ZERO, Number::add)),
+    ))
+}
+
+/// prds x: the running products of x.
+pub fn prds(value: SuperType) -> Result<SuperType, Error> {
+    if let Some(result) = running_items(&value, prds, Some(arithmetic::mul)) {
+        return result;
+    }
+    Ok(shaped(
+        &value,
+        with_numbers!(&value, v => scan(&v, Number::ONE, Number::mul)),
+    ))
+}
+
+/// mins x: the running minimums of x.
+pub fn mins(value: SuperType) -> Result<SuperType, Error> {
+    if let Some(result) = running_items(&value, mins, Some(comparison::min)) {
+        return result;
+    }
+    Ok(shaped(
+        &value,
+        with_numbers!(&value, v => scan_extreme(&v, lesser)),
+    ))
+}
+
+/// maxs x: the running maximums of x.
+pub fn maxs(value: SuperType) -> Result<SuperType, Error> {
+    if let Some(result) = running_items(&value, maxs, Some(comparison::max)) {
+        return result;
+    }
+    Ok(shaped(
+        &value,
+        with_numbers!(&value, v => scan_extreme(&v, greater)),
+    ))
+}
+
+/// avgs x: the running means of x, nulls left out.
+pub fn avgs(value: SuperType) -> Result<SuperType, Error> {
+    if let Some(result) = running_items(&value, avgs, None) {
+        return result;
+    }
+    let (mut total, mut count) = (0.0, 0);
+    let averages: Vec<f64> = to_floats(&value)?
+        .into_iter()
+        .map(|v| {
+            if !v.is_nan() {
+                total += v;
+                count += 1;
+            }
+            total / count as f64
+        })
+        .collect();
+    Ok(shaped(&value, SuperType::from(averages)))
+}
+
+// Each item with the one before it, the first item as it is
+fn pairwise<T: Copy, U>(values: &[T], first: impl Fn(T) -> U, f: impl Fn(T, T) -> U) -> Vec<U> {
+    let head = values.first().map(|&v| first(v));
+    head.into_iter()
+        .chain(values.windows(2).map(|w| f(w[1], w[0])))
+        .collect()
+}
+
+fn delta<T: Number>(v: T, previous: T) -> T {
+    match v.is_null() || previous.is_null() {
+        true => T::NULL,
+        false => v.sub(previous),
+    }
+}
+
+/// deltas x: the differences between each item of x and the one before it.
+pub fn deltas(value: SuperType) -> Result<SuperType, Error> {
+    if let SuperType::MixedList(list) = &value {
+        let items = pairwise(
+            &list.0.iter().collect::<Vec<_>>(),
+            |v| Ok(v.clone()),
+            |v, p| arithmetic::sub(v.clone(), p.clone()),
+        );
+        return Ok(SuperType::from_items(
+            items.into_iter().collect::<Result<_, _>>()?,
+        ));
+    }
+    if let Some(result) = each(&value, deltas) {
+        return result;
+    }
+    Ok(shaped(
+        &value,
+        with_numbers!(&value, v => pairwise(&v, |v| v, delta)),
+    ))
+}
+
+/// ratios x: the ratios between each item of x and the one before it.
+pub fn ratios(value: SuperType) -> Result<SuperType, Error> {
+    if let SuperType::MixedList(list) = &value {
+        let items = pairwise(
+            &list.0.iter().collect::<Vec<_>>(),
+            |v| Ok(v.clone()),
+            |v, p| arithmetic::div(v.clone(), p.clone()),
+        );
+        return Ok(SuperType::from_items(
+            items.into_iter().collect::<Result<_, _>>()?,
+        ));
+    }
+    if let Some(result) = each(&value, ratios) {
+        return result;
+    }
+    let values = to_floats(&value)?;
+    let ratios = pairwise(&values, |v| v, |v, p| v / p);
+    Ok(shaped(&value, SuperType::from(ratios)))
+}
+
+// The size of a moving window, a positive int
+fn window(n: &SuperType) -> Result<usize, Error> {
+    match ints(n) {
+        Some(Operand::Atom(n)) if n > 0 && n != INT_NULL => Ok(n as usize),
+        Some(Operand::Atom(_)) => Err(Error::Domain),
+        _ => Err(Error::Type),
+    }
+}
+
+// Dictionaries and tables move over their values and columns, mixed lists have no windows
+fn moving_items(
+    n: &SuperType,
+    value: &SuperType,
+    this: Dyadic,
+) -> Option<Result<SuperType, Error>> {
+    match value {
+        SuperType::MixedList(_) => Some(Err(Error::Type)),
+        value => each(value, |value| this(n.clone(), value)),
+    }
+}
+
+// Sums of the last n items, nulls as zeros
+fn window_sums<T: Number>(values: &[T], n: usize) -> Vec<T> {
+    let zeroed = |v: T| if v.is_null() { T::ZERO } else { v };
+    let mut total = T::ZERO;
+    (0..values.len())
+        .map(|i| {
+            total = total.add(zeroed(values[i]));
+            if i >= n {
+                total = total.sub(zeroed(values[i - n]));
+            }
+            total
+        })
+        .collect()
+}
+
+// The extremes of the last n items, kept in a queue of the positions that may still be picked
+fn window_extremes<T: Number>(values: &[T], n: usize, beats: fn(&T, &T) -> bool) -> Vec<T> {
+    let mut queue: VecDeque<usize> = VecDeque::new();
+    (0..values.len())
+        .map(|i| {
+            if queue.front().is_some_and(|&front| front + n <= i) {
+                queue.pop_front();
+            }
+            if !values[i].is_null() {
+                while queue
+                    .back()
+                    .is_some_and(|&back| !beats(&values[back], &values[i]))
+                {
+                    queue.pop_back();
+                }
+                queue.push_back(i);
+            }
+            queue.front().map_or(T::NULL, |&front| values[front])
+        })
+        .collect()
+}
+
+// The count, sum and sum of squares of the items that are not null among the last n
+fn window_moments(values: &[f64], n: usize) -> Vec<(usize, f64, f64)> {
+    let (mut count, mut total, mut squares) = (0, 0.0, 0.0);
+    (0..values.len())
+        .map(|i| {
+            if !values[i].is_nan() {
+                count += 1;
+                total += values[i];
+                squares += values[i] * values[i];
+            }
+            if i >= n && !values[i - n].is_nan() {
+                count -= 1;
+                total -= values[i - n];
+                squares -= values[i - n] * values[i - n];
+            }
+            (count, total, squares)
+        })
+        .collect()
+}
+
+/// n msum x: the sums of the last n items of x.
+pub fn msum(n: SuperType, value: SuperType) -> Result<SuperType, Error> {
+    if let Some(result) = moving_items(&n, &value, msum) {
+        return result;
+    }
+    let n = window(&n)?;
+    Ok(shaped(
+        &value,
+        with_numbers!(&value, v => window_sums(&v, n)),
+    ))
+}
+
+/// n mmax x: the largest of the last n items of x.
+pub fn mmax(n: SuperType, value: SuperType) -> Result<SuperType, Error> {
+    if let Some(result) = moving_items(&n, &value, mmax) {
+        return result;
+    }
+    let n = window(&n)?;
+    Ok(shaped(
+        &value,
+        with_numbers!(&value, v => window_extremes(&v, n, |a, b| a > b)),
+    ))
+}
+
+/// n mmin x: the smallest of the last n items of x.
+pub fn mmin(n: SuperType, value: SuperType) -> Result<SuperType, Error> {
+    if let Some(result) = moving_items(&n, &value, mmin) {
+        return result;
+    }
+    let n = window(&n)?;
+    Ok(shaped(
+        &value,
+        with_numbers!(&value, v => window_extremes(&v, n, |a, b| a < b)),
+    ))
+}
+
+/// n mcount x: how many of the last n items of x are not null.
+pub fn mcount(n: SuperType, value: SuperType) -> Result<SuperType, Error> {
+    if let Some(result) = moving_items(&n, &value, mcount) {
+        return result;
+    }
+    let counts: Vec<i32> = window_moments(&to_floats(&value)?, window(&n)?)
+        .into_iter()
+        .map(|(count, _, _)| count as i32)
+        .collect();
+    Ok(shaped(&value, SuperType::from(counts)))
+}
+
+/// n mavg x: the means of the last n items of x, nulls left out.
+pub fn mavg(n: SuperType, value: SuperType) -> Result<SuperType, Error> {
+    if let Some(result) = moving_items(&n, &value, mavg) {
+        return result;
+    }
+    let averages: Vec<f64> = window_moments(&to_floats(&value)?, window(&n)?)
+        .into_iter()
+        .map(|(count, total, _)| total / count as f64)
+        .collect();
+    Ok(shaped(&value, SuperType::from(averages)))
+}
+
+/// n mdev x: the standard deviations of the last n items of x, nulls left out.
+pub fn mdev(n: SuperType, value: SuperType) -> Result<SuperType, Error> {
+    if let Some(result) = moving_items(&n, &value, mdev) {
+        return result;
+    }
+    let deviations: Vec<f64> = window_moments(&to_floats(&value)?, window(&n)?)
+        .into_iter()
+        .map(|(count, total, squares)| {
+            let mean = total / count as f64;
+            (squares / count as f64 - mean * mean).max(0.0).sqrt()
+        })
+        .collect();
+    Ok(shaped(&value, SuperType::from(deviations)))
+}
+
+/// a ema x: the exponential moving average of x with smoothing a, nulls left out.
+pub fn ema(alpha: SuperType, value: SuperType) -> Result<SuperType, Error> {
+    if let Some(result) = moving_items(&alpha, &value, ema) {
+        return result;
+    }
+    let alpha = match floats(&alpha) {
+        Some(Operand::Atom(alpha)) => alpha,
+        _ => return Err(Error::Type),
+    };
+    let mut average = f64::NAN;
+    let averages: Vec<f64> = to_floats(&value)?
+        .into_iter()
+        .map(|v| {
+            average = match (average.is_nan(), v.is_nan()) {
+                (_, true) => average,
+                (true, false) => v,
+                (false, false) => alpha * v + (1.0 - alpha) * average,
+            };
+            average
+        })
+        .collect();
+    Ok(shaped(&value, SuperType::from(averages)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::run;
+
+    #[test]
+    fn test_aggregations_skip_nulls() {
+        let ints = SuperType::from(vec![3, INT_NULL, 1, 2]);
+        assert_eq!(sum(ints.clone()), Ok(SuperType::from(6)));
+        assert_eq!(prd(ints.clone()), Ok(SuperType::from(6)));
+        assert_eq!(min(ints.clone()), Ok(SuperType::from(1)));
+        assert_eq!(max(ints.clone()), Ok(SuperType::from(3)));
+        assert_eq!(avg(ints.clone()), Ok(SuperType::from(2.0)));
+        assert_eq!(med(ints), Ok(SuperType::from(2.0)));
+        assert_eq!(run("sum 1.5 2.5").unwrap(), SuperType::from(4.0));
+        assert_eq!(run("max 0#1 2").unwrap(), SuperType::from(-i32::MAX));
+        assert_eq!(run("sum (1 2;3 4)").unwrap(), SuperType::from(vec![4, 6]));
+    }
+
+    #[test]
+    fn test_aggregations_of_chars_and_no_items() {
+        assert_eq!(run("max \"abc\"").unwrap(), SuperType::char(b'c'));
+        assert_eq!(run("min \"bca\"").unwrap(), SuperType::char(b'a'));
+        assert_eq!(run("max \"\"").unwrap(), SuperType::char(b' '));
+        assert!(matches!(run("med ()").unwrap(), SuperType::Float(f) if f.data().0.is_nan()));
+        assert_eq!(run("med (1;`a)"), Err(Error::Type));
+    }
+
+    #[test]
+    fn test_aggregations_over_tables() {
+        assert_eq!(
+            run("exec s from select s:sum px by sym from ([] sym:`a`b`a; px:1 2 3)").unwrap(),
+            SuperType::from(vec![4, 2])
+        );
+        assert_eq!(
+            run("(sum ([] a:1 2; b:3.5 4.5))`b").unwrap(),
+            SuperType::from(8.0)
+        );
+    }
+
+    #[test]
+    fn test_running_functions() {
+        let ints = SuperType::from(vec![INT_NULL, 3, INT_NULL, 1, 5]);
+        let null = INT_NULL;
+        assert_eq!(sums(ints.clone()), Ok(SuperType::from(vec![0, 3, 3, 4, 9])));
+        assert_eq!(
+            mins(ints.clone()),
+            Ok(SuperType::from(vec![null, 3, 3, 1, 1]))
+        );
+        assert_eq!(
+            maxs(ints.clone()),
+            Ok(SuperType::from(vec![null, 3, 3, 3, 5]))
+        );
+        assert_eq!(
+            deltas(ints),
+            Ok(SuperType::from(vec![null, null, null, null, 4]))
+        );
+        assert_eq!(run("prds 1 2 3").unwrap(), SuperType::from(vec![1, 2, 6]));
+        assert_eq!(
+            run("avgs 1 2 3").unwrap(),
+            SuperType::from(vec![1.0, 1.5, 2.0])
+        );
+        assert_eq!(
+            run("ratios 1 2 6").unwrap(),
+            SuperType::from(vec![1.0, 2.0, 3.0])
+        );
+        assert_eq!(run("sums (1 2;3 4)").unwrap(), run("(1 2;4 6)").unwrap());
+    }
+
+    #[test]
+    fn test_moving_functions() {
+        assert_eq!(run("2 msum 1 2 3").unwrap(), SuperType::from(vec![1, 3, 5]));
+        assert_eq!(
+            run("2 mavg 1 2 3").unwrap(),
+            SuperType::from(vec![1.0, 1.5, 2.5])
+        );
+        assert_eq!(
+            run("3 mmax 1 3 2 0 0").unwrap(),
+            SuperType::from(vec![1, 3, 3, 3, 2])
+        );
+        assert_eq!(
+            run("2 mmin 1 3 2 0").unwrap(),
+            SuperType::from(vec![1, 1, 2, 0])
+        );
+        assert_eq!(
+            run("2 mdev 1 3 3").unwrap(),
+            SuperType::from(vec![0.0, 1.0, 0.0])
+        );
+        assert_eq!(
+            run("0.5 ema 1 3 3").unwrap(),
+            SuperType::from(vec![1.0, 2.0, 2.5])
+        );
+        let ints = SuperType::from(vec![1, INT_NULL, 3]);
+        assert_eq!(
+            mcount(SuperType::from(2), ints),
+            Ok(SuperType::from(vec![1, 1, 1]))
+        );
+        assert_eq!(run("0 msum 1 2"), Err(Error::Domain));
+    }
+}
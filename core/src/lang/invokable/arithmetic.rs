@@ -0,0 +1,391 @@
+use crate::{
+    Error,
+    lang::{
+        SuperType,
+        invokable::{
+            kernel::{self, Side},
+            operator::{self, OperatorAdd, OperatorDivide, OperatorMultiply, OperatorSubtract},
+        },
+        type_system::{
+            Atom, InnerTypeTrait, List, TypeDate, TypeDictionary, TypeFloat, TypeInt, TypeMixedList,
+        },
+    },
+};
+
+fn cast_atom<A: InnerTypeTrait + Copy, B: InnerTypeTrait + From<A>>(atom: &Atom<A>) -> Atom<B> {
+    Atom::new(B::from(*atom.data()))
+}
+
+fn cast_list<A: InnerTypeTrait + Copy, B: InnerTypeTrait + From<A>>(list: &List<A>) -> List<B> {
+    List::new(list.data().iter().map(|&v| B::from(v)).collect())
+}
+
+// Bools and bytes are promoted to ints, and everything to floats when `float` is set
+pub(crate) fn promote(value: SuperType, float: bool) -> Result<SuperType, Error> {
+    use SuperType::*;
+    let promoted = match value {
+        Bool(b) if float => Float(cast_atom(&b)),
+        Bool(b) => Int(cast_atom(&b)),
+        Bools(b) if float => Floats(cast_list(&b.to_list())),
+        Bools(b) => Ints(cast_list(&b.to_list())),
+        Byte(b) if float => Float(cast_atom(&b)),
+        Byte(b) => Int(cast_atom(&b)),
+        Bytes(b) if float => Floats(cast_list(&b)),
+        Bytes(b) => Ints(cast_list(&b)),
+        Int(i) if float => Float(cast_atom(&i)),
+        Ints(i) if float => Floats(cast_list(&i)),
+        numeric @ (Int(_) | Ints(_) | Float(_) | Floats(_)) => numeric,
+        _ => return Err(Error::Type),
+    };
+    Ok(promoted)
+}
+
+pub(crate) fn is_float(value: &SuperType) -> bool {
+    matches!(value, SuperType::Float(_) | SuperType::Floats(_))
+}
+
+/// Applies `op` item by item when either side is a mixed list or a dictionary,
+/// returns None when both sides are plain atoms or typed lists.
+pub(crate) fn atomic_dyad(
+    lhs: &SuperType,
+    rhs: &SuperType,
+    op: fn(SuperType, SuperType) -> Result<SuperType, Error>,
+) -> Option<Result<SuperType, Error>> {
+    match (lhs, rhs) {
+        (SuperType::Dictionary(l), SuperType::Dictionary(r)) => Some(match l.keys() == r.keys() {
+            true => op(l.values().clone(), r.values().clone())
+                .and_then(|values| TypeDictionary::new(l.keys().clone(), values))
+                .map(SuperType::Dictionary),
+            false => Err(Error::NotYetImplemented),
+        }),
+        (SuperType::Dictionary(d), other) => Some(
+            op(d.values().clone(), other.clone())
+                .and_then(|values| TypeDictionary::new(d.keys().clone(), values))
+                .map(SuperType::Dictionary),
+        ),
+        (other, SuperType::Dictionary(d)) => Some(
+            op(other.clone(), d.values().clone())
+                .and_then(|values| TypeDictionary::new(d.keys().clone(), values))
+                .map(SuperType::Dictionary),
+        ),
+        (SuperType::MixedList(_), _) | (_, SuperType::MixedList(_)) => {
+            let count = if lhs.is_atom() {
+                rhs.count()
+            } else {
+                lhs.count()
+            };
+            if !lhs.is_atom() && !rhs.is_atom() && lhs.count() != rhs.count() {
+                return Some(Err(Error::Length));
+            }
+            let items = (0..count)
+                .map(|i| {
+                    let l = if lhs.is_atom() {
+                        lhs.clone()
+                    } else {
+                        lhs.item(i).unwrap()
+                    };
+                    let r = if rhs.is_atom() {
+                        rhs.clone()
+                    } else {
+                        rhs.item(i).unwrap()
+                    };
+                    op(l, r)
+                })
+                .collect::<Result<Vec<_>, _>>();
+            Some(
+                items.map(|items| match items.iter().all(SuperType::is_atom) {
+                    true => SuperType::from_items(items),
+                    false => SuperType::MixedList(TypeMixedList(items)),
+                }),
+            )
+        }
+        _ => None,
+    }
+}
+
+// The ints of an int atom or list as a side of a kernel
+fn int_side(value: &SuperType) -> Option<Side<'_, TypeInt>> {
+    match value {
+        SuperType::Int(i) => Some(Side::Atom(*i.data())),
+        SuperType::Ints(i) => Some(Side::Slice(i.data())),
+        _ => None,
+    }
+}
+
+fn float_side(value: &SuperType) -> Option<Side<'_, TypeFloat>> {
+    match value {
+        SuperType::Float(f) => Some(Side::Atom(*f.data())),
+        SuperType::Floats(f) => Some(Side::Slice(f.data())),
+        _ => None,
+    }
+}
+
+// Two atoms go through the operator of their type, lists through the kernels once their lengths
+// are checked
+macro_rules! numeric_dyad {
+    ($trait_name:ident, $method_name:ident, $lhs:expr, $rhs:expr, $int_op:expr, $float_op:expr) => {{
+        let (lhs, rhs) = ($lhs, $rhs);
+        match (&lhs, &rhs) {
+            (SuperType::Int(l), SuperType::Int(r)) => {
+                return Ok(SuperType::Int(<&Atom<TypeInt> as $trait_name<
+                    &Atom<TypeInt>,
+                    Atom<TypeInt>,
+                >>::$method_name(l, r)?));
+            }
+            (SuperType::Float(l), SuperType::Float(r)) => {
+                return Ok(SuperType::Float(<&Atom<TypeFloat> as $trait_name<
+                    &Atom<TypeFloat>,
+                    Atom<TypeFloat>,
+                >>::$method_name(l, r)?));
+            }
+            _ => {}
+        }
+        if !lhs.is_atom() && !rhs.is_atom() && lhs.count() != rhs.count() {
+            return Err(Error::Length);
+        }
+        if let (Some(l), Some(r)) = (int_side(&lhs), int_side(&rhs)) {
+            let result = operator::checking_overflow(|| kernel::ints(l, r, $int_op))?;
+            return Ok(SuperType::from(List::new(result)));
+        }
+        if let (Some(l), Some(r)) = (float_side(&lhs), float_side(&rhs)) {
+            return Ok(SuperType::from(List::new(kernel::floats(l, r, $float_op))));
+        }
+        Err(Error::Type)
+    }};
+}
+
+// What adding or subtracting dates gives
+#[derive(Clone, Copy, PartialEq)]
+enum Dated {
+    // a date plus or minus days is a date
+    Shift,
+    // a date minus a date is the days between them, and days minus a date is meaningless
+    Difference,
+    // dates cannot be multiplied or divided
+    None,
+}
+
+fn is_date(value: &SuperType) -> bool {
+    matches!(value, SuperType::Date(_) | SuperType::Dates(_))
+}
+
+// Dates as the ints of their days, which share the null and infinities of ints
+fn days(value: SuperType) -> SuperType {
+    match value {
+        SuperType::Date(d) => SuperType::from(d.data().0),
+        SuperType::Dates(d) => SuperType::from(d.data().iter().map(|d| d.0).collect::<Vec<_>>()),
+        other => other,
+    }
+}
+
+fn dates(value: SuperType) -> Result<SuperType, Error> {
+    match value {
+        SuperType::Int(i) => Ok(SuperType::from(Atom::new(TypeDate(i.data().0)))),
+        SuperType::Ints(i) => Ok(SuperType::from(List::new(
+            i.data().iter().map(|i| TypeDate(i.0)).collect(),
+        ))),
+        _ => Err(Error::Type),
+    }
+}
+
+// Works on the days of dates, a date and days giving a date and two dates the days between them
+fn date_dyad(
+    lhs: SuperType,
+    rhs: SuperType,
+    op: fn(SuperType, SuperType) -> Result<SuperType, Error>,
+    dated: Dated,
+) -> Result<SuperType, Error> {
+    let to_dates = match (is_date(&lhs), is_date(&rhs), dated) {
+        (true, true, Dated::Difference) => false,
+        (true, false, Dated::Shift | Dated::Difference) | (false, true, Dated::Shift) => true,
+        _ => return Err(Error::Type),
+    };
+    if is_float(&lhs) || is_float(&rhs) {
+        return Err(Error::Type);
+    }
+    let result = op(days(lhs), days(rhs))?;
+    match to_dates {
+        true => dates(result),
+        false => Ok(result),
+    }
+}
+
+macro_rules! impl_arithmetic {
+    ($fn_name:ident, $trait_name:ident, $method_name:ident, $always_float:expr, $dated:expr, $int_op:expr, $float_op:expr) => {
+        pub fn $fn_name(lhs: SuperType, rhs: SuperType) -> Result<SuperType, Error> {
+            if let Some(result) = atomic_dyad(&lhs, &rhs, $fn_name) {
+                return result;
+            }
+            if is_date(&lhs) || is_date(&rhs) {
+                return date_dyad(lhs, rhs, $fn_name, $dated);
+            }
+            let float = $always_float || is_float(&lhs) || is_float(&rhs);
+            numeric_dyad!(
+                $trait_name,
+                $method_name,
+                promote(lhs, float)?,
+                promote(rhs, float)?,
+                $int_op,
+                $float_op
+            )
+        }
+    };
+}
+
+impl_arithmetic!(
+    add,
+    OperatorAdd,
+    add,
+    false,
+    Dated::Shift,
+    |l, r| l + r,
+    |l, r| l + r
+);
+impl_arithmetic!(
+    sub,
+    OperatorSubtract,
+    sub,
+    false,
+    Dated::Difference,
+    |l, r| l - r,
+    |l, r| l - r
+);
+impl_arithmetic!(
+    mul,
+    OperatorMultiply,
+    mul,
+    false,
+    Dated::None,
+    |l, r| l * r,
+    |l, r| l * r
+);
+// % always divides as floats, as in q, so ints never reach its int kernel
+impl_arithmetic!(
+    div,
+    OperatorDivide,
+    div,
+    true,
+    Dated::None,
+    |_, _| unreachable!(),
+    |l, r| l / r
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_broadcasts_atom() {
+        let result = add(SuperType::from(1), SuperType::from(vec![1, 2, 3]));
+        assert_eq!(result, Ok(SuperType::from(vec![2, 3, 4])));
+    }
+
+    #[test]
+    fn test_mixed_numeric_types_promote() {
+        let result = mul(SuperType::from(vec![true, false]), SuperType::from(2.5));
+        assert_eq!(result, Ok(SuperType::from(vec![2.5, 0.0])));
+
+        let result = div(SuperType::from(3), SuperType::from(2));
+        assert_eq!(result, Ok(SuperType::from(1.5)));
+    }
+
+    #[test]
+    fn test_length_and_type_errors() {
+        let result = sub(SuperType::from(vec![1, 2]), SuperType::from(vec![1, 2, 3]));
+        assert_eq!(result, Err(Error::Length));
+
+        let result = add(SuperType::from(1), SuperType::symbol("a"));
+        assert_eq!(result, Err(Error::Type));
+    }
+
+    #[test]
+    fn test_nulls_propagate() {
+        let ints = SuperType::from(vec![1, i32::MIN, 3]);
+        let result = add(ints.clone(), SuperType::from(1));
+        assert_eq!(result, Ok(SuperType::from(vec![2, i32::MIN, 4])));
+
+        // the int null becomes the float null, not a very negative number
+        let result = div(ints, SuperType::from(2)).unwrap();
+        assert_eq!(result.to_string(), "0.5 0n 1.5");
+        let result = mul(SuperType::from(f64::NAN), SuperType::from(2));
+        assert_eq!(result.unwrap().to_string(), "0n");
+    }
+
+    #[test]
+    fn test_dates_shift_by_days() {
+        use crate::testing::{run, show};
+
+        assert_eq!(show("0Nd+1"), "0Nd");
+        assert_eq!(show("(`date$0 31)+1 0N"), "2000.01.02 0N");
+        assert_eq!(show("1b+`date$0"), "2000.01.02");
+        assert_eq!(show("(`date$31)-`date$0 0N"), "31 0N");
+        assert_eq!(show("(`date$31)-1"), "2000.01.31");
+        for program in ["1-0Nd", "0Nd*2", "0Nd+1.5", "0Nd+0Nd"] {
+            assert_eq!(run(program), Err(Error::Type), "{program}");
+        }
+    }
+
+    #[test]
+    fn test_overflow_wraps_or_raises() {
+        use crate::lang::invokable::operator::{Overflow, with_overflow};
+
+        let max = SuperType::from(i32::MAX - 1);
+        // wrapping onto the null, as q does
+        let result = add(max.clone(), SuperType::from(vec![1, 2]));
+        assert_eq!(result, Ok(SuperType::from(vec![i32::MAX, i32::MIN])));
+        let result = mul(max.clone(), SuperType::from(2));
+        assert_eq!(result, Ok(SuperType::from(-4)));
+
+        let raised = with_overflow(Overflow::Raise, || add(max.clone(), SuperType::from(2)));
+        assert_eq!(raised, Err(Error::Overflow));
+        let fits = with_overflow(Overflow::Raise, || add(max, SuperType::from(1)));
+        assert_eq!(fits, Ok(SuperType::from(i32::MAX)));
+
+        // the list kernels raise once the whole list is done
+        let list = SuperType::from(vec![1, i32::MAX, 3]);
+        let raised = with_overflow(Overflow::Raise, || mul(list.clone(), SuperType::from(2)));
+        assert_eq!(raised, Err(Error::Overflow));
+        let nulls = with_overflow(Overflow::Raise, || add(list, SuperType::from(i32::MIN)));
+        assert_eq!(nulls, Ok(SuperType::from(vec![i32::MIN; 3])));
+        assert_eq!(
+            add(SuperType::from(vec![1, 2]), SuperType::from(vec![1])),
+            Err(Error::Length)
+        );
+    }
+
+    #[test]
+    fn test_promoted_division_by_zero_is_null() {
+        use crate::lang::type_system::{Nullable, TypeBool, TypeByte, TypeInt};
+
+        assert_eq!(TypeByte::div(&TypeByte(1), &TypeByte(0)), Ok(TypeInt::NULL));
+        assert_eq!(TypeByte::div(&TypeByte(7), &TypeByte(2)), Ok(TypeInt(3)));
+        assert_eq!(
+            TypeBool::div(&TypeBool(true), &TypeBool(false)),
+            Ok(TypeInt::NULL)
+        );
+        assert_eq!(
+            TypeBool::div(&TypeBool(true), &TypeBool(true)),
+            Ok(TypeInt(1))
+        );
+        assert_eq!(
+            TypeByte::add(&TypeByte(255), &TypeByte(1)),
+            Ok(TypeInt(256))
+        );
+    }
+
+    #[test]
+    fn test_overflow_left_unchecked_does_not_raise_later() {
+        use crate::lang::invokable::operator::{self, Overflow, with_overflow};
+
+        // an error between an overflow and its check leaves the overflow noted
+        let result = with_overflow(Overflow::Raise, || {
+            operator::note_overflow();
+            add(SuperType::from(vec![1, 2]), SuperType::from(vec![3]))
+        });
+        assert_eq!(result, Err(Error::Length));
+        let result = with_overflow(Overflow::Raise, || {
+            add(SuperType::from(vec![1, 2]), SuperType::from(3))
+        });
+        assert_eq!(result, Ok(SuperType::from(vec![4, 5])));
+    }
+}
@@ -0,0 +1,302 @@
+use crate::{
+    Error,
+    lang::{
+        SuperType,
+        invokable::{math::each, matrix},
+        type_system::{
+            Atom, List, Nullable, TypeBool, TypeByte, TypeChar, TypeDate, TypeFloat, TypeInt,
+            TypeSymbol,
+        },
+    },
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Target {
+    Bool,
+    Byte,
+    Int,
+    Float,
+    Char,
+    Symbol,
+    Date,
+}
+
+impl Target {
+    // The type named by a symbol, as printed by type_name, the empty symbol meaning symbol and
+    // ints standing in for longs as they do for the letters
+    fn from_name(name: &str) -> Option<Target> {
+        let target = match name {
+            "boolean" => Target::Bool,
+            "byte" => Target::Byte,
+            "int" | "long" => Target::Int,
+            "float" => Target::Float,
+            "char" => Target::Char,
+            "" | "symbol" => Target::Symbol,
+            "date" => Target::Date,
+            _ => return None,
+        };
+        Some(target)
+    }
+
+    // The type named by a q type letter, ints standing in for q's ints and longs
+    fn from_letter(letter: char) -> Option<Target> {
+        let target = match letter {
+            'b' => Target::Bool,
+            'x' => Target::Byte,
+            'i' | 'j' => Target::Int,
+            'f' => Target::Float,
+            'c' => Target::Char,
+            's' => Target::Symbol,
+            'd' => Target::Date,
+            _ => return None,
+        };
+        Some(target)
+    }
+
+    fn type_id(self) -> i16 {
+        match self {
+            Target::Bool => -1,
+            Target::Byte => -4,
+            Target::Int => -6,
+            Target::Float => -9,
+            Target::Char => -10,
+            Target::Symbol => -11,
+            Target::Date => -14,
+        }
+    }
+}
+
+// Applies `$f` to the items of `$value`, an atom or a typed list of one of the listed variants
+macro_rules! map_typed {
+    ($value:expr, |$item:ident| $f:expr, $($atom:ident => $list:ident),*) => {
+        match $value {
+            $(
+                SuperType::$atom(atom) => {
+                    let $item = *atom.data();
+                    Ok(SuperType::from(Atom::new($f)))
+                }
+                SuperType::$list(list) => Ok(SuperType::from(List::new(
+                    list.data().iter().map(|&$item| $f).collect(),
+                ))),
+            )*
+            _ => Err(Error::Type),
+        }
+    };
+}
+
+// Chars as their bytes and dates as their day numbers, so that they cast as ints do
+fn as_ints(value: SuperType) -> SuperType {
+    match value {
+        SuperType::Char(c) => SuperType::from(c.data().0 as i32),
+        SuperType::Chars(c) => {
+            SuperType::from(c.data().iter().map(|c| c.0 as i32).collect::<Vec<_>>())
+        }
+        SuperType::Date(d) => SuperType::from(d.data().0),
+        SuperType::Dates(d) => SuperType::from(d.data().iter().map(|d| d.0).collect::<Vec<_>>()),
+        other => other,
+    }
+}
+
+fn to_char(value: TypeInt) -> TypeChar {
+    match value.is_null() {
+        true => TypeChar::NULL,
+        false => u8::try_from(value.0).map_or(TypeChar::NULL, TypeChar),
+    }
+}
+
+// Every source type goes through From, its own one included
+#[allow(clippy::useless_conversion)]
+fn convert(target: Target, value: SuperType) -> Result<SuperType, Error> {
+    if let Some(result) = each(&value, |item| convert(target, item)) {
+        return result;
+    }
+    if value.type_id().abs() == target.type_id().abs() {
+        return Ok(value);
+    }
+    macro_rules! numeric {
+        ($value:expr, |$item:ident| $f:expr) => {
+            map_typed!(as_ints($value), |$item| $f, Bool => Bools, Byte => Bytes, Int => Ints, Float => Floats)
+        };
+    }
+    match target {
+        Target::Bool => numeric!(value, |v| TypeBool::from(v)),
+        Target::Byte => numeric!(value, |v| TypeByte::from(v)),
+        Target::Int => numeric!(value, |v| TypeInt::from(v)),
+        Target::Float => numeric!(value, |v| TypeFloat::from(v)),
+        Target::Char => numeric!(value, |v| to_char(TypeInt::from(v))),
+        Target::Date => match value {
+            SuperType::Char(_) | SuperType::Chars(_) => Err(Error::Type),
+            value => numeric!(value, |v| TypeDate(TypeInt::from(v).0)),
+        },
+        Target::Symbol => match value {
+            SuperType::Char(c) => Ok(SuperType::symbol(
+                String::from_utf8_lossy(&[c.data().0]).into_owned(),
+            )),
+            SuperType::Chars(c) => Ok(SuperType::symbol(c.text())),
+            _ => Err(Error::Type),
+        },
+    }
+}
+
+fn parse_bool(text: &str) -> TypeBool {
+    TypeBool(text.starts_with(['1', 't', 'T', 'y', 'Y']))
+}
+
+fn parse_byte(text: &str) -> TypeByte {
+    let digits = text.strip_prefix("0x").unwrap_or(text);
+    TypeByte(u8::from_str_radix(digits, 16).unwrap_or(0))
+}
+
+fn parse_int(text: &str) -> TypeInt {
+    match text {
+        "0W" => TypeInt::INFINITY.unwrap(),
+        "-0W" => TypeInt::NEGATIVE_INFINITY.unwrap(),
+        text => text.parse().map_or(TypeInt::NULL, TypeInt),
+    }
+}
+
+fn parse_float(text: &str) -> TypeFloat {
+    match text {
+        "0w" => TypeFloat::INFINITY.unwrap(),
+        "-0w" => TypeFloat::NEGATIVE_INFINITY.unwrap(),
+        text => text.parse().map_or(TypeFloat::NULL, TypeFloat),
+    }
+}
+
+// yyyy.mm.dd, yyyy-mm-dd or yyyymmdd
+fn parse_date(text: &str) -> TypeDate {
+    let digits: String = match text.as_bytes() {
+        [_, _, _, _, b'.' | b'-', _, _, sep, _, _] if *sep == text.as_bytes()[4] => {
+            text.chars().filter(|c| *c != '.' && *c != '-').collect()
+        }
+        _ => text.to_owned(),
+    };
+    if digits.len() != 8 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return TypeDate::NULL;
+    }
+    let (year, month, day) = (&digits[..4], &digits[4..6], &digits[6..]);
+    match (year.parse(), month.parse(), day.parse()) {
+        (Ok(year), Ok(month), Ok(day)) => {
+            TypeDate::from_ymd(year, month, day).unwrap_or(TypeDate::NULL)
+        }
+        _ => TypeDate::NULL,
+    }
+}
+
+// Reads an atom of the target type out of a string, nulls when it does not read as one
+fn parse(target: Target, value: SuperType) -> Result<SuperType, Error> {
+    if let SuperType::MixedList(_) = value
+        && let Some(result) = each(&value, |item| parse(target, item))
+    {
+        return result;
+    }
+    let text: String = match &value {
+        SuperType::Char(c) => String::from_utf8_lossy(&[c.data().0]).into_owned(),
+        SuperType::Chars(c) => c.text(),
+        _ => return Err(Error::Type),
+    };
+    let text = text.trim();
+    Ok(match target {
+        Target::Bool => Atom::new(parse_bool(text)).into(),
+        Target::Byte => Atom::new(parse_byte(text)).into(),
+        Target::Int => Atom::new(parse_int(text)).into(),
+        Target::Float => Atom::new(parse_float(text)).into(),
+        Target::Char => value,
+        Target::Symbol => Atom::new(TypeSymbol(text.to_owned())).into(),
+        Target::Date => Atom::new(parse_date(text)).into(),
+    })
+}
+
+/// t$x: x cast to the type named by the symbol or the type letter t, rounding floats to
+/// ints. An uppercase letter reads the strings of x instead. x mmu y when t names no type.
+pub fn cast(lhs: SuperType, rhs: SuperType) -> Result<SuperType, Error> {
+    match &lhs {
+        SuperType::Symbol(name) => {
+            let target = Target::from_name(&name.data().0).ok_or(Error::Type)?;
+            convert(target, rhs)
+        }
+        SuperType::Char(letter) => {
+            let letter = letter.data().0 as char;
+            let target = Target::from_letter(letter.to_ascii_lowercase()).ok_or(Error::Type)?;
+            match letter.is_ascii_uppercase() {
+                true => parse(target, rhs),
+                false => convert(target, rhs),
+            }
+        }
+        _ => matrix::mmu(lhs, rhs),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{run, show};
+
+    #[test]
+    fn test_casts_between_types() {
+        assert_eq!(run("`int$3.7"), Ok(SuperType::from(4)));
+        assert_eq!(run("\"j\"$2.5 -2.5"), Ok(SuperType::from(vec![3, -3])));
+        assert_eq!(run("`float$1 2"), Ok(SuperType::from(vec![1.0, 2.0])));
+        assert_eq!(show("`boolean$0 2"), "01b");
+        assert_eq!(show("\"x\"$255 16"), "0xff10");
+        assert_eq!(show("`char$97 98"), "\"ab\"");
+        assert_eq!(run("`int$\"a\""), Ok(SuperType::from(97)));
+        assert_eq!(show("`$\"abc\""), "`abc");
+        assert_eq!(show("`symbol$(\"ab\";\"c\")"), "`ab`c");
+        assert_eq!(show("`date$0 366"), "2000.01.01 2001.01.01");
+        assert_eq!(run("`int$`a"), Err(Error::Type));
+        assert_eq!(run("`long$3.7 0n"), Ok(SuperType::from(vec![4, i32::MIN])));
+        assert_eq!(run("`guid$1"), Err(Error::Type));
+    }
+
+    #[test]
+    fn test_casts_keep_nulls_and_infinities() {
+        assert_eq!(show("`float$0N 0W 1"), "0n 0w 1");
+        assert_eq!(show("`int$0n -0w 1.5"), "0N -0W 2");
+        assert_eq!(show("`date$0N 0W"), "0N 0Wd");
+    }
+
+    #[test]
+    fn test_casts_reach_into_containers() {
+        assert_eq!(show("`float$(1;2 3)"), "(1f;2 3f)");
+        assert_eq!(show("`float$`a`b!1 2"), "a| 1f\nb| 2f\n");
+    }
+
+    #[test]
+    fn test_parse_strings() {
+        assert_eq!(run("\"J\"$\"42\""), Ok(SuperType::from(42)));
+        assert_eq!(
+            run("\"I\"$(\"1\";\" 2 \";\"x\")"),
+            Ok(SuperType::from(vec![1, 2, TypeInt::NULL.0]))
+        );
+        assert_eq!(run("\"F\"$\"1.5\""), Ok(SuperType::from(1.5)));
+        assert_eq!(show("\"B\"$(\"true\";\"0\")"), "10b");
+        assert_eq!(show("\"X\"$\"ff\""), "0xff");
+        assert_eq!(show("\"S\"$\"abc\""), "`abc");
+        assert_eq!(show("\"D\"$\"2024.01.01\""), "2024.01.01");
+        assert_eq!(
+            show("\"D\"$(\"2024-02-29\";\"20230301\";\"2023.02.29\")"),
+            "2024.02.29 2023.03.01 0N"
+        );
+        assert_eq!(run("\"J\"$42"), Err(Error::Type));
+    }
+
+    #[test]
+    fn test_dates_round_trip() {
+        for (date, days) in [
+            ((2000, 1, 1), 0),
+            ((1999, 12, 31), -1),
+            ((2024, 1, 1), 8766),
+        ] {
+            let parsed = TypeDate::from_ymd(date.0, date.1, date.2).unwrap();
+            assert_eq!(parsed, TypeDate(days));
+            assert_eq!(parsed.ymd(), date);
+        }
+        assert_eq!(TypeDate::from_ymd(2023, 2, 29), None);
+    }
+
+    #[test]
+    fn test_matrices_still_multiply() {
+        assert_eq!(run("1 2 $ 3 4"), Ok(SuperType::from(11.0)));
+    }
+}
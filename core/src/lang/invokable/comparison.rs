@@ -0,0 +1,455 @@
+use std::cmp::Ordering;
+
+use crate::{
+    Error,
+    lang::{
+        SuperType,
+        invokable::{
+            arithmetic::{atomic_dyad, is_float},
+            kernel::{self, Side},
+            search,
+        },
+        type_system::{Bits, Nullable, TypeDictionary, TypeTable, with_atom, with_list},
+    },
+};
+
+/// A primitive view over an atom or a typed list, used by the operations that only need the
+/// underlying values.
+pub(crate) enum Operand<T> {
+    Atom(T),
+    List(Vec<T>),
+}
+
+impl<T> Operand<T> {
+    pub(crate) fn zip_with<U>(
+        self,
+        rhs: Operand<T>,
+        f: impl Fn(&T, &T) -> U,
+    ) -> Result<Operand<U>, Error> {
+        Ok(match (self, rhs) {
+            (Operand::Atom(l), Operand::Atom(r)) => Operand::Atom(f(&l, &r)),
+            (Operand::Atom(l), Operand::List(r)) => {
+                Operand::List(r.iter().map(|r| f(&l, r)).collect())
+            }
+            (Operand::List(l), Operand::Atom(r)) => {
+                Operand::List(l.iter().map(|l| f(l, &r)).collect())
+            }
+            (Operand::List(l), Operand::List(r)) => {
+                if l.len() != r.len() {
+                    return Err(Error::Length);
+                }
+                Operand::List(l.iter().zip(r.iter()).map(|(l, r)| f(l, r)).collect())
+            }
+        })
+    }
+
+    pub(crate) fn map<U>(self, f: impl Fn(T) -> U) -> Operand<U> {
+        match self {
+            Operand::Atom(v) => Operand::Atom(f(v)),
+            Operand::List(v) => Operand::List(v.into_iter().map(f).collect()),
+        }
+    }
+}
+
+impl<T> From<Operand<T>> for SuperType
+where
+    SuperType: From<T> + From<Vec<T>>,
+{
+    fn from(operand: Operand<T>) -> Self {
+        match operand {
+            Operand::Atom(v) => SuperType::from(v),
+            Operand::List(v) => SuperType::from(v),
+        }
+    }
+}
+
+pub(crate) fn bools(value: &SuperType) -> Option<Operand<bool>> {
+    match value {
+        SuperType::Bool(b) => Some(Operand::Atom(b.data().0)),
+        SuperType::Bools(b) => Some(Operand::List(b.iter().collect())),
+        _ => None,
+    }
+}
+
+pub(crate) fn ints(value: &SuperType) -> Option<Operand<i32>> {
+    match value {
+        SuperType::Int(i) => Some(Operand::Atom(i.data().0)),
+        SuperType::Ints(i) => Some(Operand::List(i.data().iter().map(|i| i.0).collect())),
+        SuperType::Byte(b) => Some(Operand::Atom(b.data().0 as i32)),
+        SuperType::Bytes(b) => Some(Operand::List(b.data().iter().map(|b| b.0 as i32).collect())),
+        other => bools(other).map(|b| b.map(|b| b as i32)),
+    }
+}
+
+pub(crate) fn floats(value: &SuperType) -> Option<Operand<f64>> {
+    match value {
+        SuperType::Float(f) => Some(Operand::Atom(f.data().0)),
+        SuperType::Floats(f) => Some(Operand::List(f.data().iter().map(|f| f.0).collect())),
+        other => ints(other).map(|i| i.map(|i| i as f64)),
+    }
+}
+
+pub(crate) fn chars(value: &SuperType) -> Option<Operand<u8>> {
+    match value {
+        SuperType::Char(c) => Some(Operand::Atom(c.data().0)),
+        SuperType::Chars(c) => Some(Operand::List(c.data().iter().map(|c| c.0).collect())),
+        _ => None,
+    }
+}
+
+pub(crate) fn symbols(value: &SuperType) -> Option<Operand<&str>> {
+    match value {
+        SuperType::Symbol(s) => Some(Operand::Atom(s.data().0.as_str())),
+        SuperType::Symbols(s) => Some(Operand::List(
+            s.data().iter().map(|s| s.0.as_str()).collect(),
+        )),
+        _ => None,
+    }
+}
+
+pub(crate) fn dates(value: &SuperType) -> Option<Operand<i32>> {
+    match value {
+        SuperType::Date(d) => Some(Operand::Atom(d.data().0)),
+        SuperType::Dates(d) => Some(Operand::List(d.data().iter().map(|d| d.0).collect())),
+        _ => None,
+    }
+}
+
+// Nulls of a type are equal to each other, float nulls being NaNs that are not equal to anything
+fn float_order(l: &f64, r: &f64) -> Option<Ordering> {
+    match l.is_nan() && r.is_nan() {
+        true => Some(Ordering::Equal),
+        false => l.partial_cmp(r),
+    }
+}
+
+fn both_null<T: Nullable>(l: T, r: T) -> bool {
+    l.is_null() && r.is_null()
+}
+
+fn compare(
+    lhs: &SuperType,
+    rhs: &SuperType,
+    predicate: fn(Option<Ordering>) -> bool,
+) -> Result<SuperType, Error> {
+    if (is_float(lhs) || is_float(rhs))
+        && let (Some(l), Some(r)) = (floats(lhs), floats(rhs))
+    {
+        return Ok(l.zip_with(r, |l, r| predicate(float_order(l, r)))?.into());
+    }
+    if let (Some(l), Some(r)) = (ints(lhs), ints(rhs)) {
+        return Ok(l.zip_with(r, |l, r| predicate(l.partial_cmp(r)))?.into());
+    }
+    if let (Some(l), Some(r)) = (chars(lhs), chars(rhs)) {
+        return Ok(l.zip_with(r, |l, r| predicate(l.partial_cmp(r)))?.into());
+    }
+    if let (Some(l), Some(r)) = (symbols(lhs), symbols(rhs)) {
+        return Ok(l.zip_with(r, |l, r| predicate(l.partial_cmp(r)))?.into());
+    }
+    if let (Some(l), Some(r)) = (dates(lhs), dates(rhs)) {
+        return Ok(l.zip_with(r, |l, r| predicate(l.partial_cmp(r)))?.into());
+    }
+    Err(Error::Type)
+}
+
+// Compares lists of ints, floats or bytes with an atom or a list of the same type in a kernel,
+// None for anything else
+macro_rules! compare_kernel {
+    ($lhs:expr, $rhs:expr, |$l:ident, $r:ident| $test:expr) => {
+        compare_kernel!(@arms $lhs, $rhs, $l, $r, $test, Int => Ints, Float => Floats, Byte => Bytes)
+    };
+    (@arms $lhs:expr, $rhs:expr, $l:ident, $r:ident, $test:expr, $($atom:ident => $list:ident),*) => {
+        match ($lhs, $rhs) {
+            $(
+                (SuperType::$list(l), SuperType::$list(r)) => match l.data().len() == r.data().len() {
+                    true => Some(Ok(kernel::compare(Side::Slice(l.data()), Side::Slice(r.data()), |$l, $r| $test))),
+                    false => Some(Err(Error::Length)),
+                },
+                (SuperType::$list(l), SuperType::$atom(r)) => {
+                    Some(Ok(kernel::compare(Side::Slice(l.data()), Side::Atom(*r.data()), |$l, $r| $test)))
+                }
+                (SuperType::$atom(l), SuperType::$list(r)) => {
+                    Some(Ok(kernel::compare(Side::Atom(*l.data()), Side::Slice(r.data()), |$l, $r| $test)))
+                }
+            )*
+            _ => None,
+        }
+        .map(|result| result.map(SuperType::Bools))
+    };
+}
+
+macro_rules! impl_comparison {
+    ($fn_name:ident, $predicate:expr, |$l:ident, $r:ident| $test:expr) => {
+        pub fn $fn_name(lhs: SuperType, rhs: SuperType) -> Result<SuperType, Error> {
+            if let Some(result) = compare_kernel!(&lhs, &rhs, |$l, $r| $test) {
+                return result;
+            }
+            if let Some(result) = atomic_dyad(&lhs, &rhs, $fn_name) {
+                return result;
+            }
+            compare(&lhs, &rhs, $predicate)
+        }
+    };
+}
+
+pub fn equals(lhs: SuperType, rhs: SuperType) -> Result<SuperType, Error> {
+    // attributed lists find the equal items without comparing all of them
+    if let Some(mask) = search::equal_mask(&lhs, &rhs).or_else(|| search::equal_mask(&rhs, &lhs)) {
+        return Ok(mask);
+    }
+    if let Some(result) = compare_kernel!(&lhs, &rhs, |l, r| l == r || both_null(l, r)) {
+        return result;
+    }
+    if let Some(result) = atomic_dyad(&lhs, &rhs, equals) {
+        return result;
+    }
+    compare(&lhs, &rhs, |o| o == Some(Ordering::Equal))
+}
+
+// The kernels test with the operators of PartialOrd, which agree with partial_cmp but for two
+// nulls, equal as in `float_order`
+impl_comparison!(not_equals, |o| o != Some(Ordering::Equal), |l, r| l != r
+    && !both_null(l, r));
+impl_comparison!(less_than, |o| o == Some(Ordering::Less), |l, r| l < r);
+impl_comparison!(
+    up_to,
+    |o| matches!(o, Some(Ordering::Less | Ordering::Equal)),
+    |l, r| l <= r || both_null(l, r)
+);
+impl_comparison!(
+    at_least,
+    |o| matches!(o, Some(Ordering::Greater | Ordering::Equal)),
+    |l, r| l >= r || both_null(l, r)
+);
+impl_comparison!(greater_than, |o| o == Some(Ordering::Greater), |l, r| l > r);
+
+// Runs `op` a word at a time when both sides are packed bools, or one is a bool atom
+fn bits_dyad(
+    lhs: &SuperType,
+    rhs: &SuperType,
+    op: fn(&Bits, &Bits) -> Result<Bits, Error>,
+) -> Option<Result<SuperType, Error>> {
+    let result = match (lhs, rhs) {
+        (SuperType::Bools(l), SuperType::Bools(r)) => op(l, r),
+        (SuperType::Bools(list), SuperType::Bool(atom))
+        | (SuperType::Bool(atom), SuperType::Bools(list)) => {
+            let repeated: Bits = std::iter::repeat_n(atom.data().0, list.len()).collect();
+            op(list, &repeated)
+        }
+        _ => return None,
+    };
+    Some(result.map(SuperType::Bools))
+}
+
+// & and | are the logical and/or on booleans and the lesser/greater of numbers otherwise
+macro_rules! impl_extremum {
+    ($fn_name:ident, $bits_op:expr, $bool_op:expr, $ordering:expr) => {
+        pub fn $fn_name(lhs: SuperType, rhs: SuperType) -> Result<SuperType, Error> {
+            if let Some(result) = bits_dyad(&lhs, &rhs, $bits_op) {
+                return result;
+            }
+            if let Some(result) = atomic_dyad(&lhs, &rhs, $fn_name) {
+                return result;
+            }
+            if let (Some(l), Some(r)) = (bools(&lhs), bools(&rhs)) {
+                return Ok(l.zip_with(r, $bool_op)?.into());
+            }
+            let pick = |l: f64, r: f64| match l.partial_cmp(&r) == Some($ordering) {
+                true => l,
+                false => r,
+            };
+            if (is_float(&lhs) || is_float(&rhs))
+                && let (Some(l), Some(r)) = (floats(&lhs), floats(&rhs))
+            {
+                return Ok(l.zip_with(r, |&l, &r| pick(l, r))?.into());
+            }
+            if let (Some(l), Some(r)) = (ints(&lhs), ints(&rhs)) {
+                return Ok(l
+                    .zip_with(r, |&l, &r| pick(l as f64, r as f64) as i32)?
+                    .into());
+            }
+            Err(Error::Type)
+        }
+    };
+}
+
+impl_extremum!(
+    min,
+    Bits::and,
+    |l: &bool, r: &bool| *l && *r,
+    Ordering::Less
+);
+impl_extremum!(
+    max,
+    Bits::or,
+    |l: &bool, r: &bool| *l || *r,
+    Ordering::Greater
+);
+
+pub fn not(value: SuperType) -> Result<SuperType, Error> {
+    if let SuperType::MixedList(list) = value {
+        let items = list.0.into_iter().map(not).collect::<Result<Vec<_>, _>>()?;
+        return Ok(SuperType::from_items(items));
+    }
+    if let SuperType::Bools(bits) = &value {
+        return Ok(SuperType::Bools(bits.not()));
+    }
+    if let Some(b) = bools(&value) {
+        return Ok(b.map(|b| !b).into());
+    }
+    if let Some(f) = floats(&value) {
+        return Ok(f.map(|f| f == 0.0).into());
+    }
+    if let Some(c) = chars(&value) {
+        return Ok(c.map(|c| c == 0).into());
+    }
+    Err(Error::Type)
+}
+
+/// null x: which items of x are null, bools and bytes never are.
+pub fn null(value: SuperType) -> Result<SuperType, Error> {
+    match value {
+        SuperType::Nothing => Ok(SuperType::from(true)),
+        SuperType::MixedList(list) => {
+            let items = list
+                .0
+                .into_iter()
+                .map(null)
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(SuperType::from_items(items))
+        }
+        SuperType::Dictionary(dictionary) => {
+            let (keys, values) = dictionary.into_parts();
+            Ok(SuperType::Dictionary(TypeDictionary::new(
+                keys,
+                null(values)?,
+            )?))
+        }
+        SuperType::Table(table) => {
+            let (columns, data) = table.into_parts();
+            let data = data.into_iter().map(null).collect::<Result<Vec<_>, _>>()?;
+            Ok(SuperType::Table(TypeTable::new(columns, data)?))
+        }
+        // bools are never null
+        SuperType::Bools(bits) => Ok(SuperType::from(vec![false; bits.len()])),
+        value => with_list!(&value, list => {
+            let nulls: Vec<bool> = list.data().iter().map(Nullable::is_null).collect();
+            Ok(SuperType::from(nulls))
+        }, other => with_atom!(other, atom => Ok(SuperType::from(atom.data().is_null())), _ => Err(Error::Type))),
+    }
+}
+
+fn truthy(value: &SuperType) -> Result<Vec<bool>, Error> {
+    match not(value.clone())? {
+        SuperType::Bool(b) => Ok(vec![!b.data().0]),
+        SuperType::Bools(b) => Ok(b.iter().map(|b| !b).collect()),
+        _ => Err(Error::Type),
+    }
+}
+
+pub fn all(value: SuperType) -> Result<SuperType, Error> {
+    Ok(SuperType::from(truthy(&value)?.into_iter().all(|b| b)))
+}
+
+pub fn any(value: SuperType) -> Result<SuperType, Error> {
+    Ok(SuperType::from(truthy(&value)?.into_iter().any(|b| b)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::run;
+
+    #[test]
+    fn test_nulls_equal_each_other() {
+        assert_eq!(run("0n=0n"), Ok(SuperType::from(true)));
+        assert_eq!(run("0n=1.5 0n"), Ok(SuperType::from(vec![false, true])));
+        assert_eq!(
+            run("(1.5 0n)=0n 0n"),
+            Ok(SuperType::from(vec![false, true]))
+        );
+        assert_eq!(run("0n<>0n"), Ok(SuperType::from(false)));
+        assert_eq!(run("(0n 1.5)<=0n"), Ok(SuperType::from(vec![true, false])));
+        assert_eq!(run("0n>=0n"), Ok(SuperType::from(true)));
+        assert_eq!(run("0n<0n"), Ok(SuperType::from(false)));
+        assert_eq!(run("0Nd=0Nd"), Ok(SuperType::from(true)));
+        assert_eq!(
+            run("t:([] px:1.5 0n 2 0n); count select from t where px=0n"),
+            Ok(SuperType::from(2))
+        );
+    }
+
+    #[test]
+    fn test_compare_promotes_numbers() {
+        let result = less_than(SuperType::from(vec![1, 2, 3]), SuperType::from(2.5));
+        assert_eq!(result, Ok(SuperType::from(vec![true, true, false])));
+    }
+
+    #[test]
+    fn test_equals_symbols() {
+        let symbols = SuperType::symbols(vec!["a".to_owned(), "b".to_owned()]);
+        let result = equals(symbols, SuperType::symbol("b"));
+        assert_eq!(result, Ok(SuperType::from(vec![false, true])));
+    }
+
+    #[test]
+    fn test_min_max() {
+        assert_eq!(
+            min(SuperType::from(vec![1, 5]), SuperType::from(3)),
+            Ok(SuperType::from(vec![1, 3]))
+        );
+        assert_eq!(
+            max(SuperType::from(vec![true, false]), SuperType::from(false)),
+            Ok(SuperType::from(vec![true, false]))
+        );
+    }
+
+    #[test]
+    fn test_packed_bools() {
+        let run = |program| {
+            crate::core::execution_context::ExecutionContext::default()
+                .run(program)
+                .unwrap()
+                .to_string()
+        };
+        assert_eq!(
+            run("(1100b & 1010b;1100b | 1b;not 101b)"),
+            "(1000b;1111b;010b)"
+        );
+        assert_eq!(
+            run("m:(til 100) within 60 70; (sum m;&m & not m)"),
+            "(11;`int$())"
+        );
+        assert_eq!(
+            run("m:0 = (til 130) mod 64; (&m;m 64 65;sum m)"),
+            "(0 64 128;10b;3)"
+        );
+        assert_eq!(run("(1010b,1b;asc 101b)"), "(10101b;011b)");
+        assert_eq!(run("(attr asc 101b;attr `s#01b)"), "`s`s");
+    }
+
+    #[test]
+    fn test_null() {
+        let context = crate::core::execution_context::ExecutionContext::default;
+        assert_eq!(
+            context().run("null 1 0N 0W"),
+            Ok(SuperType::from(vec![false, true, false]))
+        );
+        assert_eq!(
+            context().run("null (0n;`;\" \";`a;1b)"),
+            Ok(SuperType::from(vec![true, true, true, false, false]))
+        );
+        assert_eq!(
+            context().run("exec px from null ([] px:1.5 0n)"),
+            Ok(SuperType::from(vec![false, true]))
+        );
+        assert_eq!(
+            context().run("null 1.5 0N"),
+            Ok(SuperType::from(vec![false, true]))
+        );
+        assert_eq!(
+            context().run("null 0Nd 0Wd"),
+            Ok(SuperType::from(vec![true, false]))
+        );
+    }
+}
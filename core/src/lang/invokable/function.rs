@@ -0,0 +1,275 @@
+use crate::{
+    Error,
+    lang::{
+        SuperType,
+        invokable::{
+            Invokable, aggregate, invoke, join, math, matrix, regex, search, set, sort, stats,
+            string,
+        },
+    },
+};
+
+// Builtins called by name that take more than one argument
+#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+pub enum Function {
+    // Joins
+    LeftJoin,    // lj
+    InnerJoin,   // ij
+    UnionJoin,   // uj
+    EquiJoin,    // ej
+    PlusJoin,    // pj
+    AsOfJoin,    // aj
+    AsOfJoin0,   // aj0
+    WindowJoin,  // wj
+    WindowJoin1, // wj1
+
+    // Search
+    In,     // in
+    Within, // within
+    Bin,    // bin
+    Binr,   // binr
+
+    // Sorting
+    Xasc,  // xasc
+    Xdesc, // xdesc
+
+    // Sets
+    Union,  // union
+    Inter,  // inter
+    Except, // except
+    Cross,  // cross
+
+    // Moving windows
+    Mavg,   // mavg
+    Msum,   // msum
+    Mmax,   // mmax
+    Mmin,   // mmin
+    Mcount, // mcount
+    Mdev,   // mdev
+    Ema,    // ema
+
+    // Statistics
+    Cov,        // cov
+    Cor,        // cor
+    Wsum,       // wsum
+    Wavg,       // wavg
+    Percentile, // percentile
+
+    // Math
+    Mod,  // mod
+    Div,  // div
+    Xexp, // xexp
+    Xlog, // xlog
+
+    // Matrices
+    Mmu, // mmu
+    Lsq, // lsq
+
+    // Strings
+    Like, // like
+    Ss,   // ss
+    Ssr,  // ssr
+    Vs,   // vs
+    Sv,   // sv
+
+    // Regular expressions
+    Rmatch, // rmatch
+    Rss,    // rss
+    Rfind,  // rfind
+    Rssr,   // rssr
+    Rvs,    // rvs
+
+    // Secondary threads, run by the execution context as they apply functions
+    Peach, // peach
+    Fc,    // .Q.fc
+}
+
+impl Function {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Function::LeftJoin => "lj",
+            Function::InnerJoin => "ij",
+            Function::UnionJoin => "uj",
+            Function::EquiJoin => "ej",
+            Function::PlusJoin => "pj",
+            Function::AsOfJoin => "aj",
+            Function::AsOfJoin0 => "aj0",
+            Function::WindowJoin => "wj",
+            Function::WindowJoin1 => "wj1",
+            Function::In => "in",
+            Function::Within => "within",
+            Function::Bin => "bin",
+            Function::Binr => "binr",
+            Function::Xasc => "xasc",
+            Function::Xdesc => "xdesc",
+            Function::Union => "union",
+            Function::Inter => "inter",
+            Function::Except => "except",
+            Function::Cross => "cross",
+            Function::Mavg => "mavg",
+            Function::Msum => "msum",
+            Function::Mmax => "mmax",
+            Function::Mmin => "mmin",
+            Function::Mcount => "mcount",
+            Function::Mdev => "mdev",
+            Function::Ema => "ema",
+            Function::Cov => "cov",
+            Function::Cor => "cor",
+            Function::Wsum => "wsum",
+            Function::Wavg => "wavg",
+            Function::Percentile => "percentile",
+            Function::Mod => "mod",
+            Function::Div => "div",
+            Function::Xexp => "xexp",
+            Function::Xlog => "xlog",
+            Function::Mmu => "mmu",
+            Function::Lsq => "lsq",
+            Function::Like => "like",
+            Function::Ss => "ss",
+            Function::Ssr => "ssr",
+            Function::Vs => "vs",
+            Function::Sv => "sv",
+            Function::Rmatch => "rmatch",
+            Function::Rss => "rss",
+            Function::Rfind => "rfind",
+            Function::Rssr => "rssr",
+            Function::Rvs => "rvs",
+            Function::Peach => "peach",
+            Function::Fc => ".Q.fc",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Function> {
+        let function = match name {
+            "lj" => Function::LeftJoin,
+            "ij" => Function::InnerJoin,
+            "uj" => Function::UnionJoin,
+            "ej" => Function::EquiJoin,
+            "pj" => Function::PlusJoin,
+            "aj" => Function::AsOfJoin,
+            "aj0" => Function::AsOfJoin0,
+            "wj" => Function::WindowJoin,
+            "wj1" => Function::WindowJoin1,
+            "in" => Function::In,
+            "within" => Function::Within,
+            "bin" => Function::Bin,
+            "binr" => Function::Binr,
+            "xasc" => Function::Xasc,
+            "xdesc" => Function::Xdesc,
+            "union" => Function::Union,
+            "inter" => Function::Inter,
+            "except" => Function::Except,
+            "cross" => Function::Cross,
+            "mavg" => Function::Mavg,
+            "msum" => Function::Msum,
+            "mmax" => Function::Mmax,
+            "mmin" => Function::Mmin,
+            "mcount" => Function::Mcount,
+            "mdev" => Function::Mdev,
+            "ema" => Function::Ema,
+            "cov" => Function::Cov,
+            "cor" => Function::Cor,
+            "wsum" => Function::Wsum,
+            "wavg" => Function::Wavg,
+            "percentile" => Function::Percentile,
+            "mod" => Function::Mod,
+            "div" => Function::Div,
+            "xexp" => Function::Xexp,
+            "xlog" => Function::Xlog,
+            "mmu" => Function::Mmu,
+            "lsq" => Function::Lsq,
+            "like" => Function::Like,
+            "ss" => Function::Ss,
+            "ssr" => Function::Ssr,
+            "vs" => Function::Vs,
+            "sv" => Function::Sv,
+            "rmatch" => Function::Rmatch,
+            "rss" => Function::Rss,
+            "rfind" => Function::Rfind,
+            "rssr" => Function::Rssr,
+            "rvs" => Function::Rvs,
+            "peach" => Function::Peach,
+            ".Q.fc" => Function::Fc,
+            _ => return None,
+        };
+        Some(function)
+    }
+}
+
+impl Invokable for Function {
+    fn invoke_2(&self, arg1: SuperType, arg2: SuperType) -> Result<SuperType, Error> {
+        match self {
+            Function::LeftJoin => join::left_join(arg1, arg2),
+            Function::InnerJoin => join::inner_join(arg1, arg2),
+            Function::UnionJoin => join::union_join(arg1, arg2),
+            Function::PlusJoin => join::plus_join(arg1, arg2),
+            Function::In => search::in_(arg1, arg2),
+            Function::Within => search::within(arg1, arg2),
+            Function::Bin => search::bin(arg1, arg2),
+            Function::Binr => search::binr(arg1, arg2),
+            Function::Xasc => sort::xasc(arg1, arg2),
+            Function::Xdesc => sort::xdesc(arg1, arg2),
+            Function::Union => set::union(arg1, arg2),
+            Function::Inter => set::inter(arg1, arg2),
+            Function::Except => set::except(arg1, arg2),
+            Function::Cross => set::cross(arg1, arg2),
+            Function::Mavg => aggregate::mavg(arg1, arg2),
+            Function::Msum => aggregate::msum(arg1, arg2),
+            Function::Mmax => aggregate::mmax(arg1, arg2),
+            Function::Mmin => aggregate::mmin(arg1, arg2),
+            Function::Mcount => aggregate::mcount(arg1, arg2),
+            Function::Mdev => aggregate::mdev(arg1, arg2),
+            Function::Ema => aggregate::ema(arg1, arg2),
+            Function::Cov => stats::cov(arg1, arg2),
+            Function::Cor => stats::cor(arg1, arg2),
+            Function::Wsum => stats::wsum(arg1, arg2),
+            Function::Wavg => stats::wavg(arg1, arg2),
+            Function::Percentile => stats::percentile(arg1, arg2),
+            Function::Mod => math::modulo(arg1, arg2),
+            Function::Div => math::div(arg1, arg2),
+            Function::Xexp => math::xexp(arg1, arg2),
+            Function::Xlog => math::xlog(arg1, arg2),
+            Function::Mmu => matrix::mmu(arg1, arg2),
+            Function::Lsq => matrix::lsq(arg1, arg2),
+            Function::Like => string::like(arg1, arg2),
+            Function::Ss => string::ss(arg1, arg2),
+            Function::Vs => string::vs(arg1, arg2),
+            Function::Sv => string::sv(arg1, arg2),
+            Function::Rmatch => regex::rmatch(arg1, arg2),
+            Function::Rss => regex::rss(arg1, arg2),
+            Function::Rfind => regex::rfind(arg1, arg2),
+            Function::Rvs => regex::rvs(arg1, arg2),
+            _ => Err(Error::Rank),
+        }
+    }
+
+    fn invoke_3(
+        &self,
+        arg1: SuperType,
+        arg2: SuperType,
+        arg3: SuperType,
+    ) -> Result<SuperType, Error> {
+        match self {
+            Function::EquiJoin => join::equi_join(arg1, arg2, arg3),
+            Function::AsOfJoin => join::as_of_join(arg1, arg2, arg3, false),
+            Function::AsOfJoin0 => join::as_of_join(arg1, arg2, arg3, true),
+            Function::Ssr => string::ssr(arg1, arg2, arg3),
+            Function::Rssr => regex::rssr(arg1, arg2, arg3),
+            _ => Err(Error::Rank),
+        }
+    }
+
+    fn invoke_4(
+        &self,
+        arg1: SuperType,
+        arg2: SuperType,
+        arg3: SuperType,
+        arg4: SuperType,
+    ) -> Result<SuperType, Error> {
+        match self {
+            Function::WindowJoin => join::window_join(arg1, arg2, arg3, arg4, true, invoke),
+            Function::WindowJoin1 => join::window_join(arg1, arg2, arg3, arg4, false, invoke),
+            _ => Err(Error::Rank),
+        }
+    }
+}
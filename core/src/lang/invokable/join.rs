@@ -0,0 +1,652 @@
+use std::collections::HashMap;
+
+use crate::{
+    Error,
+    lang::{
+        SuperType,
+        invokable::{
+            arithmetic,
+            comparison::{self, Operand},
+            list,
+        },
+        type_system::{Attribute, Key, TypeTable, TypeTrait, row_keys, with_list},
+    },
+};
+
+// A table being joined, keyed tables are joined on their rows and keyed again afterwards
+struct Target {
+    table: TypeTable,
+    keys: usize,
+}
+
+impl Target {
+    fn new(value: SuperType) -> Result<Self, Error> {
+        match value {
+            SuperType::Table(table) => Ok(Target { table, keys: 0 }),
+            SuperType::Dictionary(keyed) if keyed.is_keyed_table() => {
+                let keys = match keyed.keys() {
+                    SuperType::Table(keys) => keys.columns().len(),
+                    _ => unreachable!(),
+                };
+                Ok(Target {
+                    table: list::unkey(keyed)?,
+                    keys,
+                })
+            }
+            _ => Err(Error::Type),
+        }
+    }
+
+    fn key_names(&self) -> &[String] {
+        &self.table.columns()[..self.keys]
+    }
+
+    fn finish(keys: usize, table: TypeTable) -> Result<SuperType, Error> {
+        match keys {
+            0 => Ok(SuperType::Table(table)),
+            n => list::dict(SuperType::from(n as i32), SuperType::Table(table)),
+        }
+    }
+}
+
+// The right side of lj, ij and pj must be keyed
+fn keyed(value: SuperType) -> Result<Target, Error> {
+    match Target::new(value)? {
+        Target { keys: 0, .. } => Err(Error::Type),
+        target => Ok(target),
+    }
+}
+
+// The `names` columns of `table`
+fn columns<'a>(table: &'a TypeTable, names: &[String]) -> Result<Vec<&'a SuperType>, Error> {
+    names
+        .iter()
+        .map(|name| table.column(name).ok_or(Error::Name(name.clone())))
+        .collect()
+}
+
+// The key of each row of `table` over the `names` columns, the same for all rows when no names
+fn group_keys(table: &TypeTable, names: &[String]) -> Result<Vec<Key>, Error> {
+    match names.is_empty() {
+        true => Ok(vec![Key::List(Vec::new()); table.row_count()]),
+        false => Ok(row_keys(&columns(table, names)?)),
+    }
+}
+
+// The column names of ej, aj and wj, a symbol or a list of them
+fn names(columns: SuperType) -> Result<Vec<String>, Error> {
+    match columns {
+        SuperType::Symbol(name) => Ok(vec![name.data().0.clone()]),
+        SuperType::Symbols(names) => Ok(names.data().iter().map(|name| name.0.clone()).collect()),
+        _ => Err(Error::Type),
+    }
+}
+
+// For each row of `table`, the row of `keyed` with the same key
+fn lookup(table: &TypeTable, keyed: &Target) -> Result<Vec<Option<usize>>, Error> {
+    let mut index = HashMap::new();
+    for (row, key) in group_keys(&keyed.table, keyed.key_names())?
+        .into_iter()
+        .enumerate()
+    {
+        index.entry(key).or_insert(row);
+    }
+    Ok(group_keys(table, keyed.key_names())?
+        .iter()
+        .map(|key| index.get(key).copied())
+        .collect())
+}
+
+// Numeric items of different types are promoted to the widest of them
+fn unify(items: Vec<SuperType>) -> Result<SuperType, Error> {
+    let numeric = |item: &SuperType| {
+        matches!(
+            item,
+            SuperType::Bool(_) | SuperType::Byte(_) | SuperType::Int(_) | SuperType::Float(_)
+        )
+    };
+    let mixed_types = items
+        .windows(2)
+        .any(|pair| pair[0].type_id() != pair[1].type_id());
+    if !mixed_types || !items.iter().all(numeric) {
+        return Ok(SuperType::from_items(items));
+    }
+    let float = items.iter().any(|item| matches!(item, SuperType::Float(_)));
+    let items = items
+        .into_iter()
+        .map(|item| arithmetic::promote(item, float))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(SuperType::from_items(items))
+}
+
+// The items of `column` at `rows`, `missing` where there is no row
+fn gather(column: &SuperType, rows: &[Option<usize>], missing: &SuperType) -> SuperType {
+    if rows.iter().all(Option::is_some) {
+        let rows: Vec<usize> = rows.iter().flatten().copied().collect();
+        if let Ok(selected) = column.select(&rows) {
+            return selected;
+        }
+    }
+    let items = rows
+        .iter()
+        .map(|row| {
+            row.and_then(|row| column.item(row))
+                .unwrap_or_else(|| missing.clone())
+        })
+        .collect();
+    SuperType::from_items(items)
+}
+
+// Takes the value of `incoming` where a row matched, keeps `existing` otherwise
+fn overwrite(
+    existing: &SuperType,
+    incoming: &SuperType,
+    rows: &[Option<usize>],
+) -> Result<SuperType, Error> {
+    let items = rows
+        .iter()
+        .enumerate()
+        .map(|(i, row)| match row {
+            Some(row) => incoming.item(*row),
+            None => existing.item(i),
+        })
+        .collect::<Option<Vec<_>>>()
+        .ok_or(Error::Length)?;
+    match items.is_empty() {
+        true => Ok(existing.clone()),
+        false => unify(items),
+    }
+}
+
+// Adds the value columns of `keyed` to `table`, `rows` maps each row of `table` to one of `keyed`
+fn merge(table: TypeTable, keyed: &Target, rows: &[Option<usize>]) -> Result<TypeTable, Error> {
+    let (mut names, mut data) = table.into_parts();
+    let values = keyed
+        .table
+        .columns()
+        .iter()
+        .zip(keyed.table.data())
+        .skip(keyed.keys);
+    for (name, column) in values {
+        match names.iter().position(|n| n == name) {
+            Some(i) => data[i] = overwrite(&data[i], column, rows)?,
+            None => {
+                names.push(name.clone());
+                data.push(gather(column, rows, &column.null_item()));
+            }
+        }
+    }
+    TypeTable::new(names, data)
+}
+
+/// t lj kt: adds the columns of kt to t, matching rows on the keys of kt.
+/// Rows without a match get nulls in the new columns and keep their values in the others.
+pub fn left_join(lhs: SuperType, rhs: SuperType) -> Result<SuperType, Error> {
+    let keyed = keyed(rhs)?;
+    let target = Target::new(lhs)?;
+    let rows = lookup(&target.table, &keyed)?;
+    Target::finish(target.keys, merge(target.table, &keyed, &rows)?)
+}
+
+/// t ij kt: like lj, but only keeps the rows of t that match a key of kt.
+pub fn inner_join(lhs: SuperType, rhs: SuperType) -> Result<SuperType, Error> {
+    let keyed = keyed(rhs)?;
+    let target = Target::new(lhs)?;
+    let (kept, rows): (Vec<usize>, Vec<Option<usize>>) = lookup(&target.table, &keyed)?
+        .into_iter()
+        .enumerate()
+        .filter(|(_, row)| row.is_some())
+        .unzip();
+    let table = target.table.select_rows(&kept)?;
+    Target::finish(target.keys, merge(table, &keyed, &rows)?)
+}
+
+// The zero of the type of `column`, what pj adds when there is no match
+fn zero(column: &SuperType) -> Result<SuperType, Error> {
+    match column.null_item() {
+        SuperType::Int(_) => Ok(SuperType::from(0)),
+        SuperType::Float(_) => Ok(SuperType::from(0.0)),
+        zero @ (SuperType::Bool(_) | SuperType::Byte(_)) => Ok(zero),
+        _ => Err(Error::Type),
+    }
+}
+
+/// t pj kt: adds the values of kt to the matching columns of t, zero when a row has no match.
+pub fn plus_join(lhs: SuperType, rhs: SuperType) -> Result<SuperType, Error> {
+    let keyed = keyed(rhs)?;
+    let target = Target::new(lhs)?;
+    let rows = lookup(&target.table, &keyed)?;
+
+    let (mut names, mut data) = target.table.into_parts();
+    let values = keyed
+        .table
+        .columns()
+        .iter()
+        .zip(keyed.table.data())
+        .skip(keyed.keys);
+    for (name, column) in values {
+        let matched = gather(column, &rows, &zero(column)?);
+        match names.iter().position(|n| n == name) {
+            Some(i) => data[i] = arithmetic::add(data[i].clone(), matched)?,
+            None => {
+                names.push(name.clone());
+                data.push(matched);
+            }
+        }
+    }
+    Target::finish(target.keys, TypeTable::new(names, data)?)
+}
+
+/// t1 uj t2: the rows of t1 then those of t2, over the union of their columns.
+/// Missing columns are filled with nulls. Keyed tables update the rows whose key matches.
+pub fn union_join(lhs: SuperType, rhs: SuperType) -> Result<SuperType, Error> {
+    let (lhs, rhs) = (Target::new(lhs)?, Target::new(rhs)?);
+    if lhs.key_names() != rhs.key_names() {
+        return Err(Error::Type);
+    }
+    let matches = match lhs.keys {
+        0 => vec![None; rhs.table.row_count()],
+        _ => lookup(&rhs.table, &lhs)?,
+    };
+
+    let mut names = lhs.table.columns().to_vec();
+    for name in rhs.table.columns() {
+        if !names.contains(name) {
+            names.push(name.clone());
+        }
+    }
+
+    let left_count = lhs.table.row_count();
+    let data = names
+        .iter()
+        .map(|name| {
+            let (left, right) = (lhs.table.column(name), rhs.table.column(name));
+            let null = left
+                .or(right)
+                .map_or(SuperType::Nothing, SuperType::null_item);
+            let mut items = match left {
+                Some(column) => column.clone().into_items(),
+                None => vec![null.clone(); left_count],
+            };
+            for (row, matched) in matches.iter().enumerate() {
+                let item = right.and_then(|column| column.item(row));
+                match (matched, item) {
+                    (Some(i), Some(item)) => items[*i] = item,
+                    (Some(_), None) => {}
+                    (None, item) => items.push(item.unwrap_or_else(|| null.clone())),
+                }
+            }
+            match items.is_empty() {
+                true => Ok(left.or(right).cloned().unwrap_or(SuperType::Nothing)),
+                false => unify(items),
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Target::finish(lhs.keys, TypeTable::new(names, data)?)
+}
+
+/// ej[c;t1;t2]: every pair of rows of t1 and t2 that agree on the columns c.
+pub fn equi_join(columns: SuperType, lhs: SuperType, rhs: SuperType) -> Result<SuperType, Error> {
+    let names = names(columns)?;
+    let (lhs, rhs) = (Target::new(lhs)?, Target::new(rhs)?);
+    let mut index: HashMap<Key, Vec<usize>> = HashMap::new();
+    for (row, key) in group_keys(&rhs.table, &names)?.into_iter().enumerate() {
+        index.entry(key).or_default().push(row);
+    }
+
+    let mut left_rows = Vec::new();
+    let mut right_rows = Vec::new();
+    for (i, key) in group_keys(&lhs.table, &names)?.iter().enumerate() {
+        for j in index.get(key).into_iter().flatten() {
+            left_rows.push(i);
+            right_rows.push(Some(*j));
+        }
+    }
+
+    let right = Target {
+        table: rhs.table,
+        keys: 0,
+    };
+    let table = merge(lhs.table.select_rows(&left_rows)?, &right, &right_rows)?;
+    Ok(SuperType::Table(table))
+}
+
+// The values of a time column, as numbers so that they can be searched
+fn times(column: &SuperType) -> Result<Vec<f64>, Error> {
+    match comparison::floats(column) {
+        Some(Operand::List(times)) => Ok(times),
+        _ => Err(Error::Type),
+    }
+}
+
+// The quotes of aj and wj: the rows of each group, in time order
+struct Quotes {
+    groups: HashMap<Key, Vec<usize>>,
+    times: Vec<f64>,
+}
+
+impl Quotes {
+    // `names` are the group columns followed by the time column
+    fn new(table: &TypeTable, names: &[String]) -> Result<Self, Error> {
+        let (time, groups) = names.split_last().ok_or(Error::Length)?;
+        let column = table.column(time).ok_or(Error::Name(time.clone()))?;
+        let times = times(column)?;
+
+        let mut rows: HashMap<Key, Vec<usize>> = HashMap::new();
+        for (row, key) in group_keys(table, groups)?.into_iter().enumerate() {
+            rows.entry(key).or_default().push(row);
+        }
+
+        // rows of a sorted time column stay sorted within each group; otherwise, parted
+        // groups included, the rows of a group are sorted by time unless they already are
+        let attributes = |column: &SuperType| {
+            with_list!(column, list => list.get_attributes(), other => match other {
+                SuperType::Bools(bits) => bits.get_attributes(),
+                _ => Attribute::None as u8,
+            })
+        };
+        if !Attribute::has_sorted(attributes(column)) {
+            for rows in rows.values_mut() {
+                if !rows.is_sorted_by(|a, b| times[*a] <= times[*b]) {
+                    rows.sort_by(|a, b| times[*a].total_cmp(&times[*b]));
+                }
+            }
+        }
+
+        Ok(Quotes {
+            groups: rows,
+            times,
+        })
+    }
+
+    fn group(&self, key: &Key) -> &[usize] {
+        self.groups.get(key).map_or(&[], Vec::as_slice)
+    }
+
+    // How many quotes of `rows` are before `time`, or at it when `inclusive`
+    fn count_before(&self, rows: &[usize], time: f64, inclusive: bool) -> usize {
+        match inclusive {
+            true => rows.partition_point(|row| self.times[*row] <= time),
+            false => rows.partition_point(|row| self.times[*row] < time),
+        }
+    }
+}
+
+/// aj[c;t;q]: adds to each row of t the columns of the last row of q at or before its time.
+/// c are the columns to match on, the time column last. aj0 also takes the time from q.
+pub fn as_of_join(
+    columns: SuperType,
+    lhs: SuperType,
+    rhs: SuperType,
+    quote_time: bool,
+) -> Result<SuperType, Error> {
+    let names = names(columns)?;
+    let target = Target::new(lhs)?;
+    let quotes_table = Target::new(rhs)?.table;
+    let quotes = Quotes::new(&quotes_table, &names)?;
+
+    let (time, groups) = names.split_last().ok_or(Error::Length)?;
+    let column = target.table.column(time).ok_or(Error::Name(time.clone()))?;
+    let rows: Vec<Option<usize>> = group_keys(&target.table, groups)?
+        .iter()
+        .zip(times(column)?)
+        .map(|(key, time)| {
+            let group = quotes.group(key);
+            match quotes.count_before(group, time, true) {
+                0 => None,
+                n => Some(group[n - 1]),
+            }
+        })
+        .collect();
+
+    // the matched columns already hold the right values, except the time for aj0
+    let skipped = match quote_time {
+        true => groups,
+        false => &names[..],
+    };
+    let (quote_names, quote_data) = quotes_table.into_parts();
+    let (quote_names, quote_data) = quote_names
+        .into_iter()
+        .zip(quote_data)
+        .filter(|(name, _)| !skipped.contains(name))
+        .unzip();
+    let quotes = Target {
+        table: TypeTable::new(quote_names, quote_data)?,
+        keys: 0,
+    };
+    Target::finish(target.keys, merge(target.table, &quotes, &rows)?)
+}
+
+/// wj[w;c;t;(q;(f0;c0);(f1;c1))]: adds to each row of t the aggregates f0, f1 of the columns
+/// c0, c1 of q over its time window, the starts and ends of the windows being the lists in w.
+/// wj also includes the quote prevailing at the start of each window, wj1 only those within.
+/// The aggregates are called through `apply`, so that they may be lambdas.
+pub fn window_join(
+    windows: SuperType,
+    columns: SuperType,
+    lhs: SuperType,
+    aggregates: SuperType,
+    prevailing: bool,
+    mut apply: impl FnMut(&SuperType, Vec<SuperType>) -> Result<SuperType, Error>,
+) -> Result<SuperType, Error> {
+    let (starts, ends) = match windows.into_items().as_slice() {
+        [starts, ends] => (times(starts)?, times(ends)?),
+        _ => return Err(Error::Length),
+    };
+    let mut aggregates = aggregates.into_items().into_iter();
+    let quotes_table = Target::new(aggregates.next().ok_or(Error::Length)?)?.table;
+    let aggregates = aggregates
+        .map(|aggregate| match aggregate.into_items().as_slice() {
+            [function, SuperType::Symbol(name)] => {
+                let name = name.data().0.clone();
+                let column = quotes_table
+                    .column(&name)
+                    .ok_or(Error::Name(name.clone()))?
+                    .clone();
+                Ok((function.clone(), name, column))
+            }
+            _ => Err(Error::Type),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    // each result is named after its column, so one column can only be aggregated once
+    for (i, (_, name, _)) in aggregates.iter().enumerate() {
+        if aggregates[..i].iter().any(|(_, other, _)| other == name) {
+            return Err(Error::Domain);
+        }
+    }
+
+    let names = names(columns)?;
+    let quotes = Quotes::new(&quotes_table, &names)?;
+    let target = Target::new(lhs)?;
+    let keys = group_keys(&target.table, &names[..names.len() - 1])?;
+    if starts.len() != keys.len() || ends.len() != keys.len() {
+        return Err(Error::Length);
+    }
+
+    let windows: Vec<&[usize]> = keys
+        .iter()
+        .zip(starts.iter().zip(&ends))
+        .map(|(key, (start, end))| {
+            let group = quotes.group(key);
+            let before = quotes.count_before(group, *start, false);
+            let first = match prevailing && before == quotes.count_before(group, *start, true) {
+                true => before.saturating_sub(1),
+                false => before,
+            };
+            let last = quotes.count_before(group, *end, true);
+            &group[first.min(last)..last]
+        })
+        .collect();
+
+    let (mut names, mut data) = target.table.into_parts();
+    for (function, name, column) in aggregates {
+        let values = windows
+            .iter()
+            .map(|rows| apply(&function, vec![column.select(rows)?]))
+            .collect::<Result<Vec<_>, _>>()?;
+        let values = SuperType::from_items(values);
+        match names.iter().position(|n| *n == name) {
+            Some(i) => data[i] = values,
+            None => {
+                names.push(name);
+                data.push(values);
+            }
+        }
+    }
+    Target::finish(target.keys, TypeTable::new(names, data)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{run, with_tables};
+
+    #[test]
+    fn test_left_join_fills_nulls() {
+        assert_eq!(
+            run(&with_tables("exec px from t lj kt")).unwrap(),
+            SuperType::from(vec![10, 2, 10, 30])
+        );
+        assert_eq!(
+            run(&with_tables("exec name from t lj kt")).unwrap(),
+            SuperType::symbols(["x", "", "x", "z"].map(String::from).to_vec())
+        );
+    }
+
+    #[test]
+    fn test_inner_and_plus_join() {
+        assert_eq!(
+            run(&with_tables("exec px from t ij kt")).unwrap(),
+            SuperType::from(vec![10, 10, 30])
+        );
+        assert_eq!(
+            run(&with_tables("exec px from t pj ([sym:`a`c] px:1.5 2)")).unwrap(),
+            SuperType::from(vec![2.5, 2.0, 4.5, 6.0])
+        );
+    }
+
+    #[test]
+    fn test_union_join_promotes_and_fills() {
+        let result = run(&with_tables("exec px from t uj ([] px:0.5 1; size:7 8)")).unwrap();
+        assert_eq!(result, SuperType::from(vec![1.0, 2.0, 3.0, 4.0, 0.5, 1.0]));
+        assert_eq!(
+            run(&with_tables("exec size from t uj ([] px:0.5 1; size:7 8)")).unwrap(),
+            SuperType::from(vec![i32::MIN, i32::MIN, i32::MIN, i32::MIN, 7, 8])
+        );
+        assert_eq!(
+            run(&with_tables("count kt uj ([sym:`a`e] px:0 0; name:`q`r)")).unwrap(),
+            SuperType::from(4)
+        );
+    }
+
+    #[test]
+    fn test_equi_join_matches_every_pair() {
+        let result = run(&with_tables("ej[`sym; t; ([] sym:`a`a`b; size:1 2 3)]")).unwrap();
+        let SuperType::Table(table) = result else {
+            panic!("expected a table");
+        };
+        assert_eq!(
+            table.column("px"),
+            Some(&SuperType::from(vec![1, 1, 2, 3, 3]))
+        );
+        assert_eq!(
+            table.column("size"),
+            Some(&SuperType::from(vec![1, 2, 3, 1, 2]))
+        );
+    }
+
+    #[test]
+    fn test_as_of_join_takes_prevailing_quote() {
+        let program = "q:([] sym:`a`b`a`a; time:1 2 3 5; bid:10 20 30 50); \
+            tr:([] sym:`a`a`b`c; time:0 4 2 9; qty:1 2 3 4)";
+        assert_eq!(
+            run(&format!("{program}; exec bid from aj[`sym`time; tr; q]")).unwrap(),
+            SuperType::from(vec![i32::MIN, 30, 20, i32::MIN])
+        );
+        assert_eq!(
+            run(&format!("{program}; exec time from aj[`sym`time; tr; q]")).unwrap(),
+            SuperType::from(vec![0, 4, 2, 9])
+        );
+        assert_eq!(
+            run(&format!("{program}; exec time from aj0[`sym`time; tr; q]")).unwrap(),
+            SuperType::from(vec![0, 3, 2, 9])
+        );
+    }
+
+    #[test]
+    fn test_as_of_join_sorts_unless_attributed() {
+        let program = "q:([] sym:`a`a`a; time:5 1 3; bid:50 10 30); \
+            tr:([] sym:`a`a; time:2 4)";
+        assert_eq!(
+            run(&format!("{program}; exec bid from aj[`sym`time; tr; q]")).unwrap(),
+            SuperType::from(vec![10, 30])
+        );
+        // a parted group column says nothing of the order of the times
+        let program = "q:([] sym:`p#`a`a`a`b; time:3 5 1 2; bid:30 50 10 20); \
+            tr:([] sym:`a`a`b; time:2 4 3)";
+        assert_eq!(
+            run(&format!("{program}; exec bid from aj[`sym`time; tr; q]")).unwrap(),
+            SuperType::from(vec![10, 30, 20])
+        );
+    }
+
+    #[test]
+    fn test_window_join_aggregates() {
+        let program = "q:([] sym:`a`a`a`b; time:1 3 5 4; bid:10 30 50 40); \
+            tr:([] sym:`a`a`b; time:3 6 4); w:(2 5 3; 4 7 5)";
+        assert_eq!(
+            run(&format!(
+                "{program}; exec bid from wj[w; `sym`time; tr; (q; (count; `bid))]"
+            ))
+            .unwrap(),
+            SuperType::from(vec![2, 1, 1])
+        );
+        assert_eq!(
+            run(&format!(
+                "{program}; exec bid from wj1[w; `sym`time; tr; (q; (count; `bid))]"
+            ))
+            .unwrap(),
+            SuperType::from(vec![1, 1, 1])
+        );
+        assert_eq!(
+            run(&format!(
+                "{program}; exec bid from wj[w; `sym`time; tr; (q; (first; `bid))]"
+            ))
+            .unwrap(),
+            SuperType::from(vec![10, 50, 40])
+        );
+    }
+
+    #[test]
+    fn test_window_join_lambda_aggregates() {
+        let program = "q:([] sym:`a`a`a`b; time:1 3 5 4; bid:10 30 50 40); \
+            tr:([] sym:`a`a`b; time:3 6 4); w:(2 5 3; 4 7 5)";
+        assert_eq!(
+            run(&format!(
+                "{program}; exec bid from wj[w; `sym`time; tr; (q; ({{sum 2*x}}; `bid))]"
+            ))
+            .unwrap(),
+            SuperType::from(vec![80, 100, 80])
+        );
+        assert_eq!(
+            run(&format!(
+                "{program}; f:{{first x}}; exec bid from wj1[w; `sym`time; tr; (q; (f; `bid))]"
+            ))
+            .unwrap(),
+            SuperType::from(vec![30, 50, 40])
+        );
+    }
+
+    #[test]
+    fn test_window_join_aggregates_a_column_once() {
+        let program = "q:([] sym:`a`a`a`b; time:1 3 5 4; bid:10 30 50 40); \
+            tr:([] sym:`a`a`b; time:3 6 4); w:(2 5 3; 4 7 5)";
+        assert_eq!(
+            run(&format!(
+                "{program}; wj[w; `sym`time; tr; (q; (max; `bid); (count; `bid))]"
+            )),
+            Err(Error::Domain)
+        );
+    }
+}
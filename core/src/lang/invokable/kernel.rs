@@ -0,0 +1,256 @@
+//! Loops over the items of numeric lists, written so that the compiler can vectorise them: they
+//! cannot fail and do not branch on the items. Callers check types and lengths before running
+//! them, and int overflows are noted for `operator::checking_overflow` rather than stopping the loop.
+//! Long lists are split between the secondary threads, see `parallel`.
+
+use std::ops::Range;
+
+use crate::lang::{
+    invokable::{math::INT_NULL, operator, parallel},
+    type_system::{Bits, TypeByte, TypeFloat, TypeInt},
+};
+
+/// One side of a kernel: the items of a list, or an atom standing for as many copies of itself
+/// as the other side has items.
+#[derive(Clone, Copy, Debug)]
+pub enum Side<'a, T> {
+    Atom(T),
+    Slice(&'a [T]),
+}
+
+impl<T: Copy> Side<'_, T> {
+    // The items of a run, an atom standing for those of any run
+    fn run(&self, run: Range<usize>) -> Self {
+        match *self {
+            Side::Atom(atom) => Side::Atom(atom),
+            Side::Slice(items) => Side::Slice(&items[run]),
+        }
+    }
+}
+
+// The number of items of two sides, two atoms making a single one
+fn len<T>(lhs: &Side<T>, rhs: &Side<T>) -> usize {
+    match (lhs, rhs) {
+        (Side::Slice(l), Side::Slice(r)) => l.len().min(r.len()),
+        (Side::Slice(items), _) | (_, Side::Slice(items)) => items.len(),
+        (Side::Atom(_), Side::Atom(_)) => 1,
+    }
+}
+
+// Maps `$f` over the pairs of items of two sides, each shape getting a loop of its own
+macro_rules! zip_sides {
+    ($lhs:expr, $rhs:expr, |$l:ident, $r:ident| $f:expr) => {
+        match ($lhs, $rhs) {
+            (Side::Slice(l), Side::Slice(r)) => l.iter().zip(r).map(|(&$l, &$r)| $f).collect(),
+            (Side::Atom($l), Side::Slice(r)) => r.iter().map(|&$r| $f).collect(),
+            (Side::Slice(l), Side::Atom($r)) => l.iter().map(|&$l| $f).collect(),
+            (Side::Atom($l), Side::Atom($r)) => std::iter::once($f).collect(),
+        }
+    };
+}
+
+/// Int arithmetic done in 64 bits by `op`, the null in and out, and results that do not fit or
+/// land on the null wrapped around and noted as overflows.
+pub fn ints(
+    lhs: Side<TypeInt>,
+    rhs: Side<TypeInt>,
+    op: impl Fn(i64, i64) -> i64 + Sync,
+) -> Vec<TypeInt> {
+    // the threads hand back whether they overflowed, the flag being one per thread
+    let runs = parallel::runs(len(&lhs, &rhs), 1, |run| {
+        let mut overflowed = false;
+        let result: Vec<TypeInt> = zip_sides!(lhs.run(run.clone()), rhs.run(run), |l, r| {
+            let wide = op(l.0 as i64, r.0 as i64);
+            let value = wide as i32;
+            let null = (l.0 == INT_NULL) | (r.0 == INT_NULL);
+            overflowed |= !null & ((wide != value as i64) | (value == INT_NULL));
+            TypeInt(if null { INT_NULL } else { value })
+        });
+        (result, overflowed)
+    });
+    let (runs, overflowed): (Vec<_>, Vec<_>) = runs.into_iter().unzip();
+    if overflowed.contains(&true) {
+        operator::note_overflow();
+    }
+    parallel::concat(runs)
+}
+
+/// Float arithmetic, nulls being NaNs that carry through on their own.
+pub fn floats(
+    lhs: Side<TypeFloat>,
+    rhs: Side<TypeFloat>,
+    op: impl Fn(f64, f64) -> f64 + Sync,
+) -> Vec<TypeFloat> {
+    let runs = parallel::runs(len(&lhs, &rhs), 1, |run| {
+        zip_sides!(lhs.run(run.clone()), rhs.run(run), |l, r| TypeFloat(op(
+            l.0, r.0
+        )))
+    });
+    parallel::concat(runs)
+}
+
+const WORD: usize = u64::BITS as usize;
+
+// Packs 64 results into a word, the first in the lowest bit
+fn word(bits: impl Iterator<Item = bool>) -> u64 {
+    bits.enumerate()
+        .fold(0, |word, (i, bit)| word | (bit as u64) << i)
+}
+
+// Compares the items of two sides a word at a time
+fn compare_words<T: Copy>(lhs: Side<T>, rhs: Side<T>, test: impl Fn(T, T) -> bool) -> Vec<u64> {
+    match (lhs, rhs) {
+        (Side::Slice(l), Side::Slice(r)) => l
+            .chunks(WORD)
+            .zip(r.chunks(WORD))
+            .map(|(l, r)| word(l.iter().zip(r).map(|(&l, &r)| test(l, r))))
+            .collect(),
+        (Side::Atom(l), Side::Slice(r)) => r
+            .chunks(WORD)
+            .map(|r| word(r.iter().map(|&r| test(l, r))))
+            .collect(),
+        (Side::Slice(l), Side::Atom(r)) => l
+            .chunks(WORD)
+            .map(|l| word(l.iter().map(|&l| test(l, r))))
+            .collect(),
+        (Side::Atom(l), Side::Atom(r)) => vec![test(l, r) as u64],
+    }
+}
+
+/// Compares the items of two sides with `test`, packing the results straight into bits.
+pub fn compare<T: Copy + Sync>(
+    lhs: Side<T>,
+    rhs: Side<T>,
+    test: impl Fn(T, T) -> bool + Sync,
+) -> Bits {
+    let len = len(&lhs, &rhs);
+    // runs start on a word so that their words follow each other
+    let runs = parallel::runs(len, WORD, |run| {
+        compare_words(lhs.run(run.clone()), rhs.run(run), &test)
+    });
+    Bits::from_words(parallel::concat(runs), len)
+}
+
+/// The sum of ints leaving the nulls out, noting an overflow when it does not fit in an int.
+pub fn sum_ints(items: &[TypeInt]) -> TypeInt {
+    // wrapping sums of 64 bits add up the same whichever way the runs split them
+    let runs = parallel::runs(items.len(), 1, |run| {
+        items[run]
+            .iter()
+            .map(|i| if i.0 == INT_NULL { 0 } else { i.0 as i64 })
+            .fold(0i64, i64::wrapping_add)
+    });
+    let sum = runs.into_iter().fold(0i64, i64::wrapping_add);
+    let value = sum as i32;
+    if sum != value as i64 || value == INT_NULL {
+        operator::note_overflow();
+    }
+    TypeInt(value)
+}
+
+/// The sum of bytes as an int, noting an overflow when it does not fit.
+pub fn sum_bytes(items: &[TypeByte]) -> TypeInt {
+    let runs = parallel::runs(items.len(), 1, |run| {
+        items[run].iter().map(|b| b.0 as u64).sum::<u64>()
+    });
+    let sum: u64 = runs.into_iter().sum();
+    let value = sum as i32;
+    if sum != value as u64 {
+        operator::note_overflow();
+    }
+    TypeInt(value)
+}
+
+// Floats are summed in blocks of this many, whichever thread sums them
+const BLOCK: usize = 4096;
+
+/// The sum of the floats of `items`, NaNs left out. Float addition rounding in the order it is
+/// done, the items are summed a block at a time and then the sums of the blocks in order, so
+/// that the result is the same however many threads share the blocks.
+pub fn sum_floats<T: Copy + Sync>(items: &[T], float: impl Fn(T) -> f64 + Sync) -> f64 {
+    let runs = parallel::runs(items.len(), BLOCK, |run| {
+        let blocks = items[run].chunks(BLOCK).map(|block| {
+            let values = block.iter().map(|&item| float(item));
+            values.filter(|v| !v.is_nan()).fold(0.0, |sum, v| sum + v)
+        });
+        blocks.collect::<Vec<f64>>()
+    });
+    runs.into_iter()
+        .flatten()
+        .fold(0.0, |sum, block| sum + block)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lang::invokable::operator::Overflow;
+
+    fn ints_of(values: &[i32]) -> Vec<TypeInt> {
+        values.iter().copied().map(TypeInt).collect()
+    }
+
+    #[test]
+    fn test_kernels_match_the_item_operators() {
+        use crate::lang::invokable::operator::{OperatorAdd, OperatorMultiply};
+        let lhs = ints_of(&[1, INT_NULL, i32::MAX, -5, 40000, i32::MIN + 1]);
+        let rhs = ints_of(&[2, 3, 1, -7, 60000, -1]);
+        let by_item = |f: fn(&TypeInt, &TypeInt) -> Result<TypeInt, crate::Error>| {
+            let pairs = lhs.iter().zip(&rhs);
+            pairs.map(|(l, r)| f(l, r).unwrap()).collect::<Vec<_>>()
+        };
+        let added = ints(Side::Slice(&lhs), Side::Slice(&rhs), |l, r| l + r);
+        assert_eq!(added, by_item(|l, r| TypeInt::add(l, r)));
+        let multiplied = || ints(Side::Slice(&lhs), Side::Slice(&rhs), |l, r| l * r);
+        assert_eq!(multiplied(), by_item(|l, r| TypeInt::mul(l, r)));
+        assert!(operator::checking_overflow(multiplied).is_ok());
+    }
+
+    #[test]
+    fn test_compare_packs_words() {
+        let items = ints_of(&(0..100).collect::<Vec<_>>());
+        let less = compare(Side::Slice(&items), Side::Atom(TypeInt(70)), |l, r| l < r);
+        assert_eq!(less.len(), 100);
+        assert_eq!(less.count_ones(), 70);
+        assert_eq!(less.get(69), Some(true));
+        assert_eq!(less.get(70), Some(false));
+
+        let nan = [TypeFloat(f64::NAN), TypeFloat(1.0)];
+        let equal = compare(Side::Slice(&nan), Side::Slice(&nan), |l, r| l == r);
+        assert_eq!(equal, Bits::from(vec![false, true]));
+    }
+
+    #[test]
+    fn test_sums_leave_nulls_out() {
+        assert_eq!(sum_ints(&ints_of(&[1, INT_NULL, 2])), TypeInt(3));
+        assert_eq!(sum_ints(&[]), TypeInt(0));
+        assert_eq!(sum_bytes(&[TypeByte(255), TypeByte(2)]), TypeInt(257));
+        assert_eq!(sum_floats(&[1.5, f64::NAN, 2.0], |v| v), 3.5);
+    }
+
+    #[test]
+    fn test_threads_give_the_serial_results() {
+        let len = parallel::THRESHOLD * 2 + 77;
+        let items = ints_of(
+            &(0..len as i32)
+                .map(|i| i.wrapping_mul(40_000))
+                .collect::<Vec<_>>(),
+        );
+        let values: Vec<_> = (0..len)
+            .map(|i| TypeFloat(1.0 / (i as f64 + 0.3)))
+            .collect();
+        let kernels = || {
+            let products = || ints(Side::Slice(&items), Side::Atom(TypeInt(7)), |l, r| l * r);
+            let raised = operator::checking_overflow(products).is_err();
+            let products = products();
+            let less = compare(Side::Slice(&items), Side::Atom(TypeInt(0)), |l, r| l < r);
+            let ratios = floats(Side::Slice(&values), Side::Slice(&values), |l, r| l / r);
+            let total = sum_floats(&values, |f| f.0).to_bits();
+            (products, raised, less, ratios, total, sum_ints(&items))
+        };
+        let raising = || operator::with_overflow(Overflow::Raise, kernels);
+        let serial = raising();
+        let split = parallel::with_pool(parallel::pool(3).unwrap(), raising);
+        assert!(serial.1, "the products overflow");
+        assert_eq!(serial, split);
+    }
+}
@@ -0,0 +1,694 @@
+use crate::{
+    Error,
+    lang::{
+        SuperType,
+        invokable::comparison::ints,
+        type_system::{
+            Attribute, List, TypeDictionary, TypeMixedList, TypeTable, TypeTrait, with_atom,
+            with_list,
+        },
+    },
+};
+
+fn int_atom(value: &SuperType) -> Option<i32> {
+    match ints(value)? {
+        super::comparison::Operand::Atom(i) if !matches!(value, SuperType::Bool(_)) => Some(i),
+        _ => None,
+    }
+}
+
+fn symbol_names(value: &SuperType) -> Option<Vec<String>> {
+    match value {
+        SuperType::Symbol(s) => Some(vec![s.data().0.clone()]),
+        SuperType::Symbols(s) => Some(s.data().iter().map(|s| s.0.clone()).collect()),
+        _ => None,
+    }
+}
+
+pub fn enlist(value: SuperType) -> Result<SuperType, Error> {
+    Ok(
+        with_atom!(value, atom => atom.enlist().into(), other => match other {
+            SuperType::Dictionary(d) => match symbol_names(d.keys()) {
+                // a dictionary with symbol keys is a row, enlisting it makes a table
+                Some(columns) => {
+                    let data = d
+                        .values()
+                        .clone()
+                        .into_items()
+                        .into_iter()
+                        .map(enlist)
+                        .collect::<Result<Vec<_>, _>>()?;
+                    SuperType::Table(TypeTable::new(columns, data)?)
+                }
+                None => SuperType::MixedList(TypeMixedList(vec![SuperType::Dictionary(d)])),
+            },
+            other => SuperType::MixedList(TypeMixedList(vec![other])),
+        }),
+    )
+}
+
+pub fn first(value: SuperType) -> Result<SuperType, Error> {
+    if value.is_atom() {
+        return Ok(value);
+    }
+    Ok(value.item(0).unwrap_or(SuperType::Nothing))
+}
+
+pub fn last(value: SuperType) -> Result<SuperType, Error> {
+    if value.is_atom() {
+        return Ok(value);
+    }
+    let count = value.count();
+    Ok(count
+        .checked_sub(1)
+        .and_then(|i| value.item(i))
+        .unwrap_or(SuperType::Nothing))
+}
+
+pub fn reverse(value: SuperType) -> Result<SuperType, Error> {
+    if value.is_atom() {
+        return Ok(value);
+    }
+    let indices: Vec<usize> = (0..value.count()).rev().collect();
+    match value {
+        SuperType::Dictionary(d) => {
+            let (keys, values) = d.into_parts();
+            Ok(SuperType::Dictionary(TypeDictionary::new(
+                keys.select(&indices)?,
+                values.select(&indices)?,
+            )?))
+        }
+        other => other.select(&indices),
+    }
+}
+
+pub fn raze(value: SuperType) -> Result<SuperType, Error> {
+    match value {
+        SuperType::MixedList(list) => list
+            .0
+            .into_iter()
+            .try_fold(SuperType::MixedList(TypeMixedList(vec![])), join),
+        other => Ok(other),
+    }
+}
+
+pub fn til(value: SuperType) -> Result<SuperType, Error> {
+    match int_atom(&value) {
+        Some(n) if n >= 0 => Ok(SuperType::from((0..n).collect::<Vec<i32>>())),
+        Some(_) => Err(Error::Domain),
+        None => Err(Error::Type),
+    }
+}
+
+/// Monadic `&`: the indices of the true booleans, or each index repeated by its count.
+pub fn where_(value: SuperType) -> Result<SuperType, Error> {
+    if let SuperType::Bools(bits) = &value {
+        let indices = bits
+            .ones()
+            .into_iter()
+            .map(|i| i as i32)
+            .collect::<Vec<_>>();
+        return Ok(SuperType::from(indices));
+    }
+    match ints(&value) {
+        Some(super::comparison::Operand::List(counts)) => {
+            let mut indices = Vec::new();
+            for (i, &count) in counts.iter().enumerate() {
+                if count < 0 {
+                    return Err(Error::Domain);
+                }
+                indices.extend(std::iter::repeat_n(i as i32, count as usize));
+            }
+            Ok(SuperType::from(indices))
+        }
+        _ => Err(Error::Type),
+    }
+}
+
+/// Monadic `!`: til of an int, the keys of a dictionary.
+pub fn til_or_key(value: SuperType) -> Result<SuperType, Error> {
+    match value {
+        SuperType::Dictionary(d) => Ok(d.into_parts().0),
+        other => til(other),
+    }
+}
+
+/// Monadic `+`: transposes a dictionary of columns into a table, and back.
+pub fn flip(value: SuperType) -> Result<SuperType, Error> {
+    match value {
+        SuperType::Dictionary(d) => {
+            let columns = symbol_names(d.keys()).ok_or(Error::Type)?;
+            let (_, values) = d.into_parts();
+            let count = values
+                .clone()
+                .into_items()
+                .iter()
+                .filter(|column| !column.is_atom())
+                .map(SuperType::count)
+                .max()
+                .unwrap_or(1);
+            let data = values
+                .into_items()
+                .into_iter()
+                .map(|column| column.broadcast(count))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(SuperType::Table(TypeTable::new(columns, data)?))
+        }
+        SuperType::Table(table) => {
+            let (columns, data) = table.into_parts();
+            Ok(SuperType::Dictionary(TypeDictionary::new(
+                SuperType::symbols(columns),
+                SuperType::MixedList(TypeMixedList(data)),
+            )?))
+        }
+        _ => Err(Error::Type),
+    }
+}
+
+pub fn attributes(value: SuperType) -> Result<SuperType, Error> {
+    let bits = with_list!(&value, list => list.get_attributes(), other => match other {
+        SuperType::Bools(bools) => bools.get_attributes(),
+        _ => Attribute::None as u8,
+    });
+    let name = match bits {
+        b if Attribute::has_sorted(b) => "s",
+        b if Attribute::has_unique(b) => "u",
+        b if Attribute::has_parted(b) => "p",
+        b if Attribute::has_grouped(b) => "g",
+        _ => "",
+    };
+    Ok(SuperType::symbol(name))
+}
+
+// Concatenates two values of the same type without going through their items
+macro_rules! join_typed {
+    ($lhs:expr, $rhs:expr, $($atom_variant:ident => $list_variant:ident),*) => {
+        match ($lhs, $rhs) {
+            $(
+                (SuperType::$list_variant(l), SuperType::$list_variant(r)) => {
+                    let mut data = l.into_data();
+                    data.extend(r.into_data());
+                    return Ok(SuperType::from(List::new(data)));
+                }
+                (SuperType::$list_variant(l), SuperType::$atom_variant(r)) => {
+                    let mut data = l.into_data();
+                    data.push(r.into_data());
+                    return Ok(SuperType::from(List::new(data)));
+                }
+                (SuperType::$atom_variant(l), SuperType::$list_variant(r)) => {
+                    let mut data = vec![l.into_data()];
+                    data.extend(r.into_data());
+                    return Ok(SuperType::from(List::new(data)));
+                }
+                (SuperType::$atom_variant(l), SuperType::$atom_variant(r)) => {
+                    return Ok(SuperType::from(List::new(vec![
+                        l.into_data(),
+                        r.into_data(),
+                    ])));
+                }
+            )*
+            other => other,
+        }
+    };
+}
+
+pub fn join(lhs: SuperType, rhs: SuperType) -> Result<SuperType, Error> {
+    // joining an empty list keeps the type of the other side
+    if !lhs.is_atom() && lhs.count() == 0 && !rhs.is_atom() {
+        return Ok(rhs);
+    }
+    if !rhs.is_atom() && rhs.count() == 0 && !lhs.is_atom() {
+        return Ok(lhs);
+    }
+
+    let (lhs, rhs) = join_typed!(
+        lhs,
+        rhs,
+        Bool => Bools,
+        Byte => Bytes,
+        Int => Ints,
+        Float => Floats,
+        Char => Chars,
+        Symbol => Symbols,
+        Date => Dates
+    );
+
+    match (lhs, rhs) {
+        (SuperType::Table(l), SuperType::Table(r)) => {
+            if l.columns() != r.columns() {
+                return Err(Error::Type);
+            }
+            let (columns, left) = l.into_parts();
+            let (_, right) = r.into_parts();
+            let data = left
+                .into_iter()
+                .zip(right)
+                .map(|(l, r)| join(l, r))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(SuperType::Table(TypeTable::new(columns, data)?))
+        }
+        (SuperType::Dictionary(l), SuperType::Dictionary(r)) => upsert(l, r),
+        (lhs, rhs) => {
+            let mut items = lhs.into_items();
+            items.extend(rhs.into_items());
+            Ok(SuperType::from_items(items))
+        }
+    }
+}
+
+// d1,d2 replaces the values of the keys already in d1 and appends the others
+fn upsert(lhs: TypeDictionary, rhs: TypeDictionary) -> Result<SuperType, Error> {
+    let (mut keys, values) = lhs.into_parts();
+    let mut values = values.into_items();
+    let (new_keys, new_values) = rhs.into_parts();
+    for (key, value) in new_keys
+        .into_items()
+        .into_iter()
+        .zip(new_values.into_items())
+    {
+        match keys.position(&key) {
+            Some(i) => values[i] = value,
+            None => {
+                keys = join(keys, enlist(key)?)?;
+                values.push(value);
+            }
+        }
+    }
+    Ok(SuperType::Dictionary(TypeDictionary::new(
+        keys,
+        SuperType::from_items(values),
+    )?))
+}
+
+// Grows a typed list in place by a list or an atom of its type, handing back anything else
+macro_rules! append_typed {
+    ($lhs:expr, $rhs:expr, $($atom_variant:ident => $list_variant:ident),*) => {
+        match ($lhs, $rhs) {
+            $(
+                (SuperType::$list_variant(l), SuperType::$list_variant(r)) => {
+                    l.data_mut().extend(r.into_data());
+                    return Ok(());
+                }
+                (SuperType::$list_variant(l), SuperType::$atom_variant(r)) => {
+                    l.data_mut().push(r.into_data());
+                    return Ok(());
+                }
+            )*
+            (_, other) => other,
+        }
+    };
+}
+
+fn is_typed_list(value: &SuperType) -> bool {
+    with_list!(value, _list => true, other => matches!(other, SuperType::Bools(_)))
+}
+
+/// x,:y joins y to x in place. Lists that keep their type grow without their items being
+/// copied, so that appending to a variable takes the same time however long it is.
+pub fn append(lhs: &mut SuperType, rhs: SuperType) -> Result<(), Error> {
+    let rhs = append_typed!(
+        &mut *lhs,
+        rhs,
+        Byte => Bytes,
+        Int => Ints,
+        Float => Floats,
+        Char => Chars,
+        Symbol => Symbols,
+        Date => Dates
+    );
+    match (&mut *lhs, rhs) {
+        (SuperType::Bools(l), SuperType::Bool(r)) => l.push(r.into_data().0),
+        (SuperType::Bools(l), SuperType::Bools(r)) => r.iter().for_each(|bit| l.push(bit)),
+        // a typed list keeps its type, as it does when amended
+        (lhs, rhs) if is_typed_list(lhs) => {
+            if !matches!(&rhs, SuperType::MixedList(r) if r.0.is_empty()) {
+                return Err(Error::Type);
+            }
+        }
+        // a general list stays one, an empty one takes the type of what is joined to it
+        (SuperType::MixedList(l), rhs)
+            if !l.0.is_empty()
+                && !matches!(rhs, SuperType::Table(_) | SuperType::Dictionary(_)) =>
+        {
+            l.0.extend(rhs.into_items())
+        }
+        // join may fail half way, the variable keeps its value then
+        (lhs, rhs) => *lhs = join(lhs.clone(), rhs)?,
+    }
+    Ok(())
+}
+
+// Writes `values` at `positions` of a typed list once they all have its type
+macro_rules! amend_typed {
+    ($data:expr, $positions:expr, $values:expr, $($atom_variant:ident => $list_variant:ident),*) => {
+        match $data {
+            $(
+                SuperType::$list_variant(list) => {
+                    let items = $values
+                        .into_iter()
+                        .map(|value| match value {
+                            SuperType::$atom_variant(atom) => Ok(atom.into_data()),
+                            _ => Err(Error::Type),
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    let data = list.data_mut();
+                    for (&i, item) in $positions.iter().zip(items) {
+                        data[i] = item;
+                    }
+                }
+            )*
+            SuperType::Bools(bits) => {
+                let items = $values
+                    .into_iter()
+                    .map(|value| match value {
+                        SuperType::Bool(atom) => Ok(atom.into_data().0),
+                        _ => Err(Error::Type),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                for (&i, bit) in $positions.iter().zip(items) {
+                    bits.set(i, bit);
+                }
+            }
+            SuperType::MixedList(list) => {
+                for (&i, value) in $positions.iter().zip($values) {
+                    list.0[i] = value;
+                }
+            }
+            _ => return Err(Error::Type),
+        }
+    };
+}
+
+/// x[i]:y replaces the item of x at i by y, or the items at a list of positions by those of y,
+/// in place. A typed list keeps its type, other items raise 'type. On a dictionary it upserts.
+pub fn amend(data: &mut SuperType, index: SuperType, value: SuperType) -> Result<(), Error> {
+    if let SuperType::Dictionary(_) = data {
+        let entries = match index.is_atom() {
+            true => dict(enlist(index)?, enlist(value)?)?,
+            false => dict(index, value)?,
+        };
+        *data = join(data.clone(), entries)?;
+        return Ok(());
+    }
+    if data.is_atom() || matches!(data, SuperType::Table(_)) {
+        return Err(Error::Type);
+    }
+
+    let (positions, values) = match ints(&index) {
+        Some(super::comparison::Operand::Atom(i)) => (vec![i], vec![value]),
+        Some(super::comparison::Operand::List(positions)) => {
+            let values = match value.is_atom() {
+                true => vec![value; positions.len()],
+                false if value.count() == positions.len() => value.into_items(),
+                false => return Err(Error::Length),
+            };
+            (positions, values)
+        }
+        None => return Err(Error::Type),
+    };
+    let count = data.count();
+    let positions = positions
+        .into_iter()
+        .map(|i| usize::try_from(i).ok().filter(|&i| i < count))
+        .collect::<Option<Vec<_>>>()
+        .ok_or(Error::Length)?;
+
+    amend_typed!(
+        data,
+        positions,
+        values,
+        Byte => Bytes,
+        Int => Ints,
+        Float => Floats,
+        Char => Chars,
+        Symbol => Symbols,
+        Date => Dates
+    );
+    Ok(())
+}
+
+// n#x takes from the front when n is positive, from the back otherwise, and wraps around
+fn take_indices(n: i32, count: usize) -> Result<Vec<usize>, Error> {
+    let wanted = n.unsigned_abs() as usize;
+    if count == 0 {
+        return match wanted {
+            0 => Ok(vec![]),
+            _ => Err(Error::Length),
+        };
+    }
+    let start = match n >= 0 {
+        true => 0,
+        false => (count - wanted % count) % count,
+    };
+    Ok((0..wanted).map(|i| (start + i) % count).collect())
+}
+
+pub fn take(lhs: SuperType, rhs: SuperType) -> Result<SuperType, Error> {
+    // `s#x applies an attribute to a list, `#x removes it
+    if let SuperType::Symbol(name) = &lhs
+        && !matches!(rhs, SuperType::Table(_) | SuperType::Dictionary(_))
+    {
+        let attribute = Attribute::from_name(&name.data().0).ok_or(Error::Domain)?;
+        let mut rhs = rhs;
+        with_list!(&mut rhs, list => list.set_attribute(attribute)?, other => match other {
+            SuperType::Bools(bits) => bits.set_attribute(attribute)?,
+            _ => return Err(Error::Type),
+        });
+        return Ok(rhs);
+    }
+
+    if let Some(names) = symbol_names(&lhs) {
+        return match rhs {
+            SuperType::Table(table) => {
+                let data = names
+                    .iter()
+                    .map(|name| table.column(name).cloned().ok_or(Error::Name(name.clone())))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(SuperType::Table(TypeTable::new(names, data)?))
+            }
+            SuperType::Dictionary(d) => {
+                let values = names
+                    .iter()
+                    .map(|name| d.get(&SuperType::symbol(name.as_str())))
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or(Error::Type)?;
+                Ok(SuperType::Dictionary(TypeDictionary::new(
+                    SuperType::symbols(names),
+                    SuperType::from_items(values),
+                )?))
+            }
+            _ => Err(Error::Type),
+        };
+    }
+
+    let n = int_atom(&lhs).ok_or(Error::Type)?;
+    let rhs = match rhs.is_atom() {
+        true => rhs.broadcast(1)?,
+        false => rhs,
+    };
+    let indices = take_indices(n, rhs.count())?;
+    match rhs {
+        SuperType::Dictionary(d) => {
+            let (keys, values) = d.into_parts();
+            Ok(SuperType::Dictionary(TypeDictionary::new(
+                keys.select(&indices)?,
+                values.select(&indices)?,
+            )?))
+        }
+        other => other.select(&indices),
+    }
+}
+
+/// Dyadic `_`: drops n items from the front, or from the back when n is negative.
+pub fn drop(lhs: SuperType, rhs: SuperType) -> Result<SuperType, Error> {
+    let n = int_atom(&lhs).ok_or(Error::Type)?;
+    let count = rhs.count();
+    let dropped = (n.unsigned_abs() as usize).min(count);
+    let indices: Vec<usize> = match n >= 0 {
+        true => (dropped..count).collect(),
+        false => (0..count - dropped).collect(),
+    };
+    match rhs {
+        SuperType::Dictionary(d) => {
+            let (keys, values) = d.into_parts();
+            Ok(SuperType::Dictionary(TypeDictionary::new(
+                keys.select(&indices)?,
+                values.select(&indices)?,
+            )?))
+        }
+        other if other.is_atom() => Err(Error::Type),
+        other => other.select(&indices),
+    }
+}
+
+/// Dyadic `!`: builds a dictionary, or keys a table on its first n columns.
+pub fn dict(lhs: SuperType, rhs: SuperType) -> Result<SuperType, Error> {
+    match (int_atom(&lhs), rhs) {
+        (Some(n), SuperType::Table(table)) => key_table(n, table),
+        (Some(0), SuperType::Dictionary(d)) if d.is_keyed_table() => unkey(d).map(SuperType::Table),
+        (None, rhs) => {
+            let lhs = if lhs.is_atom() { enlist(lhs)? } else { lhs };
+            let rhs = if rhs.is_atom() { enlist(rhs)? } else { rhs };
+            Ok(SuperType::Dictionary(TypeDictionary::new(lhs, rhs)?))
+        }
+        _ => Err(Error::Type),
+    }
+}
+
+fn key_table(n: i32, table: TypeTable) -> Result<SuperType, Error> {
+    let n = usize::try_from(n).map_err(|_| Error::Domain)?;
+    if n == 0 {
+        return Ok(SuperType::Table(table));
+    }
+    if n >= table.columns().len() {
+        return Err(Error::Length);
+    }
+    let (mut columns, mut data) = table.into_parts();
+    let value_columns = columns.split_off(n);
+    let value_data = data.split_off(n);
+    Ok(SuperType::Dictionary(TypeDictionary::new(
+        SuperType::Table(TypeTable::new(columns, data)?),
+        SuperType::Table(TypeTable::new(value_columns, value_data)?),
+    )?))
+}
+
+/// Turns a keyed table back into a simple table, key columns first.
+pub fn unkey(keyed: TypeDictionary) -> Result<TypeTable, Error> {
+    match keyed.into_parts() {
+        (SuperType::Table(keys), SuperType::Table(values)) => {
+            let (mut columns, mut data) = keys.into_parts();
+            let (value_columns, value_data) = values.into_parts();
+            columns.extend(value_columns);
+            data.extend(value_data);
+            TypeTable::new(columns, data)
+        }
+        _ => Err(Error::Type),
+    }
+}
+
+/// Applying data to an index: items of a list, values of a dictionary, rows or columns of a table.
+pub fn index(data: &SuperType, index: SuperType) -> Result<SuperType, Error> {
+    match data {
+        SuperType::Dictionary(d) => {
+            if d.is_keyed_table()
+                && let SuperType::Table(keys) = d.keys()
+                && keys.columns().len() == 1
+                && !matches!(index, SuperType::Dictionary(_))
+            {
+                let position = keys.data()[0].position(&index).ok_or(Error::Length)?;
+                return d.values().item(position).ok_or(Error::Length);
+            }
+            if !index.is_atom() && !d.keys().is_atom() && index.type_id() == d.keys().type_id() {
+                let items = index
+                    .into_items()
+                    .into_iter()
+                    .map(|key| d.get(&key).ok_or(Error::Length))
+                    .collect::<Result<Vec<_>, _>>()?;
+                return Ok(SuperType::from_items(items));
+            }
+            d.get(&index).ok_or(Error::Length)
+        }
+        SuperType::Table(table) => match symbol_names(&index) {
+            Some(names) => {
+                let columns = names
+                    .iter()
+                    .map(|name| table.column(name).cloned().ok_or(Error::Name(name.clone())))
+                    .collect::<Result<Vec<_>, _>>()?;
+                match index.is_atom() {
+                    true => Ok(columns.into_iter().next().unwrap()),
+                    false => Ok(SuperType::MixedList(TypeMixedList(columns))),
+                }
+            }
+            None => index_positions(data, &index),
+        },
+        data if data.is_atom() => Err(Error::Type),
+        data => index_positions(data, &index),
+    }
+}
+
+fn index_positions(data: &SuperType, index: &SuperType) -> Result<SuperType, Error> {
+    match ints(index) {
+        Some(super::comparison::Operand::Atom(i)) => usize::try_from(i)
+            .ok()
+            .and_then(|i| data.item(i))
+            .ok_or(Error::Length),
+        Some(super::comparison::Operand::List(positions)) => {
+            let positions = positions
+                .into_iter()
+                .map(|i| usize::try_from(i).map_err(|_| Error::Length))
+                .collect::<Result<Vec<_>, _>>()?;
+            data.select(&positions)
+        }
+        None => Err(Error::Type),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::run;
+
+    #[test]
+    fn test_take_wraps_around() {
+        let list = SuperType::from(vec![1, 2, 3]);
+        assert_eq!(
+            take(SuperType::from(5), list.clone()),
+            Ok(SuperType::from(vec![1, 2, 3, 1, 2]))
+        );
+        assert_eq!(
+            take(SuperType::from(-2), list),
+            Ok(SuperType::from(vec![2, 3]))
+        );
+    }
+
+    #[test]
+    fn test_take_applies_attributes() {
+        let sorted = take(SuperType::symbol("s"), SuperType::from(vec![1, 2, 3])).unwrap();
+        assert_eq!(attributes(sorted.clone()), Ok(SuperType::symbol("s")));
+        let plain = take(SuperType::symbol(""), sorted).unwrap();
+        assert_eq!(attributes(plain), Ok(SuperType::symbol("")));
+        assert_eq!(
+            take(SuperType::symbol("s"), SuperType::from(vec![3, 1])),
+            Err(Error::AttributeFail("s"))
+        );
+        assert_eq!(
+            take(SuperType::symbol("u"), SuperType::from(1)),
+            Err(Error::Type)
+        );
+    }
+
+    #[test]
+    fn test_where_outside_and_inside_queries() {
+        assert_eq!(run("where 0101b"), Ok(SuperType::from(vec![1, 3])));
+        assert_eq!(
+            run("t:([] b:0101b; n:til 4); (exec where b from t; exec n from t where b)"),
+            run("(1 3; 1 3)")
+        );
+    }
+
+    #[test]
+    fn test_join_keeps_type() {
+        let joined = join(SuperType::from(vec![1, 2]), SuperType::from(3));
+        assert_eq!(joined, Ok(SuperType::from(vec![1, 2, 3])));
+
+        let joined = join(SuperType::from(1), SuperType::symbol("a"));
+        assert_eq!(joined.map(|j| j.type_id()), Ok(0));
+    }
+
+    #[test]
+    fn test_flip_dictionary_into_table() {
+        let d = dict(
+            SuperType::symbols(vec!["a".to_owned(), "b".to_owned()]),
+            SuperType::MixedList(TypeMixedList(vec![
+                SuperType::from(vec![1, 2]),
+                SuperType::from(0.5),
+            ])),
+        )
+        .unwrap();
+        let table = flip(d).unwrap();
+        assert_eq!(
+            index(&table, SuperType::symbol("b")),
+            Ok(SuperType::from(vec![0.5, 0.5]))
+        );
+    }
+}
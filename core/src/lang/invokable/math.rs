@@ -0,0 +1,281 @@
+use crate::{
+    Error,
+    lang::{
+        SuperType,
+        invokable::{
+            arithmetic::{atomic_dyad, is_float},
+            comparison::{Operand, floats, ints},
+        },
+        type_system::{Nullable, TypeDictionary, TypeInt, TypeTable},
+    },
+};
+
+pub(crate) const INT_NULL: i32 = TypeInt::NULL.0;
+
+// Applies a monadic function to the items of a mixed list, the values of a dictionary or the
+// columns of a table. None for atoms and typed lists.
+pub(crate) fn each(
+    value: &SuperType,
+    f: impl Fn(SuperType) -> Result<SuperType, Error>,
+) -> Option<Result<SuperType, Error>> {
+    let map = |items: &[SuperType]| items.iter().cloned().map(&f).collect::<Result<Vec<_>, _>>();
+    match value {
+        SuperType::MixedList(list) => Some(map(&list.0).map(SuperType::from_items)),
+        SuperType::Dictionary(dictionary) => Some(
+            f(dictionary.values().clone())
+                .and_then(|values| TypeDictionary::new(dictionary.keys().clone(), values))
+                .map(SuperType::Dictionary),
+        ),
+        SuperType::Table(table) => Some(
+            map(table.data())
+                .and_then(|data| TypeTable::new(table.columns().to_vec(), data))
+                .map(SuperType::Table),
+        ),
+        _ => None,
+    }
+}
+
+// Numbers as floats, int nulls becoming float nulls
+fn as_floats(value: &SuperType) -> Option<Operand<f64>> {
+    match is_float(value) {
+        true => floats(value),
+        false => ints(value).map(|i| {
+            i.map(|i| match i {
+                INT_NULL => f64::NAN,
+                i => i as f64,
+            })
+        }),
+    }
+}
+
+// Float nulls become int nulls and infinities the largest ints
+fn to_int(value: f64) -> i32 {
+    match value {
+        v if v.is_nan() => INT_NULL,
+        v if v >= i32::MAX as f64 => i32::MAX,
+        v if v <= -(i32::MAX as f64) => -i32::MAX,
+        v => v as i32,
+    }
+}
+
+macro_rules! impl_keep_type {
+    ($(#[$doc:meta])* $fn_name:ident, $int:expr, $float:expr) => {
+        $(#[$doc])*
+        pub fn $fn_name(value: SuperType) -> Result<SuperType, Error> {
+            if let Some(result) = each(&value, $fn_name) {
+                return result;
+            }
+            if is_float(&value) {
+                return Ok(floats(&value).ok_or(Error::Type)?.map($float).into());
+            }
+            Ok(ints(&value).ok_or(Error::Type)?.map($int).into())
+        }
+    };
+}
+
+impl_keep_type!(
+    /// neg x: -x, bools and bytes as ints.
+    neg,
+    i32::wrapping_neg,
+    |f: f64| -f
+);
+impl_keep_type!(
+    /// abs x: the magnitude of x.
+    abs,
+    i32::wrapping_abs,
+    f64::abs
+);
+impl_keep_type!(
+    /// floor x: the largest int at or below x.
+    floor,
+    |i: i32| i,
+    |f: f64| to_int(f.floor())
+);
+impl_keep_type!(
+    /// ceiling x: the smallest int at or above x.
+    ceiling,
+    |i: i32| i,
+    |f: f64| to_int(f.ceil())
+);
+impl_keep_type!(
+    /// signum x: -1, 0 or 1 as x is negative, zero or positive. Nulls are negative.
+    signum,
+    i32::signum,
+    |f: f64| match f {
+        f if f.is_nan() => -1,
+        0.0 => 0,
+        f => f.signum() as i32,
+    }
+);
+
+macro_rules! impl_float_result {
+    ($(#[$doc:meta])* $fn_name:ident, $float:expr) => {
+        $(#[$doc])*
+        pub fn $fn_name(value: SuperType) -> Result<SuperType, Error> {
+            if let Some(result) = each(&value, $fn_name) {
+                return result;
+            }
+            Ok(as_floats(&value).ok_or(Error::Type)?.map($float).into())
+        }
+    };
+}
+
+impl_float_result!(
+    /// sqrt x: the square root of x, null for negative x.
+    sqrt,
+    f64::sqrt
+);
+impl_float_result!(
+    /// exp x: e to the power x.
+    exp,
+    f64::exp
+);
+impl_float_result!(
+    /// log x: the natural logarithm of x.
+    log,
+    f64::ln
+);
+
+// Applies a dyadic function with the broadcasting of the arithmetic operators: as ints when
+// `int` is given and both sides are ints, as floats otherwise. Nulls give nulls.
+fn numeric_dyad(
+    lhs: SuperType,
+    rhs: SuperType,
+    this: fn(SuperType, SuperType) -> Result<SuperType, Error>,
+    int: Option<fn(i32, i32) -> i32>,
+    float: fn(f64, f64) -> f64,
+) -> Result<SuperType, Error> {
+    if let Some(result) = atomic_dyad(&lhs, &rhs, this) {
+        return result;
+    }
+    if let Some(int) = int
+        && !is_float(&lhs)
+        && !is_float(&rhs)
+        && let (Some(l), Some(r)) = (ints(&lhs), ints(&rhs))
+    {
+        let result = l.zip_with(r, |&l, &r| match l == INT_NULL || r == INT_NULL {
+            true => INT_NULL,
+            false => int(l, r),
+        })?;
+        return Ok(result.into());
+    }
+    match (as_floats(&lhs), as_floats(&rhs)) {
+        (Some(l), Some(r)) => Ok(l.zip_with(r, |&l, &r| float(l, r))?.into()),
+        _ => Err(Error::Type),
+    }
+}
+
+/// x mod y: the remainder of x divided by y, of the sign of y.
+pub fn modulo(lhs: SuperType, rhs: SuperType) -> Result<SuperType, Error> {
+    let int = |x: i32, y: i32| match y {
+        0 => INT_NULL,
+        y => {
+            let r = x.wrapping_rem(y);
+            match r != 0 && (r < 0) != (y < 0) {
+                true => r + y,
+                false => r,
+            }
+        }
+    };
+    numeric_dyad(lhs, rhs, modulo, Some(int), |x, y| x - y * (x / y).floor())
+}
+
+/// x div y: x divided by y, rounded down.
+pub fn div(lhs: SuperType, rhs: SuperType) -> Result<SuperType, Error> {
+    let int = |x: i32, y: i32| match y {
+        0 => INT_NULL,
+        y => {
+            let q = x.wrapping_div(y);
+            match x.wrapping_rem(y) != 0 && (x < 0) != (y < 0) {
+                true => q - 1,
+                false => q,
+            }
+        }
+    };
+    numeric_dyad(lhs, rhs, div, Some(int), |x, y| (x / y).floor())
+}
+
+/// x xexp y: x to the power y.
+pub fn xexp(lhs: SuperType, rhs: SuperType) -> Result<SuperType, Error> {
+    numeric_dyad(lhs, rhs, xexp, None, f64::powf)
+}
+
+/// x xlog y: the logarithm of y in base x.
+pub fn xlog(lhs: SuperType, rhs: SuperType) -> Result<SuperType, Error> {
+    numeric_dyad(lhs, rhs, xlog, None, |x, y| y.ln() / x.ln())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{core::execution_context::ExecutionContext, testing::run};
+
+    #[test]
+    fn test_minus_after_a_function_is_negative() {
+        assert_eq!(run("abs -3"), Ok(SuperType::from(3)));
+        assert_eq!(run("neg -1 2"), Ok(SuperType::from(vec![1, -2])));
+        assert_eq!(
+            run("signum -2 0 3 0N"),
+            Ok(SuperType::from(vec![-1, 0, 1, -1]))
+        );
+        assert_eq!(run("neg -0W"), run("0W"));
+        assert_eq!(run("count -2#1 2 3"), Ok(SuperType::from(2)));
+        assert_eq!(run("f:{x*2}; f -3"), Ok(SuperType::from(-6)));
+
+        // functions bound by earlier programs too, while nouns subtract
+        let mut context = ExecutionContext::default();
+        context.run("f:{x*2}; g:abs; x:5").unwrap();
+        assert_eq!(context.run("(f -3;g -4;x -1)"), run("-6 4 4"));
+    }
+
+    #[test]
+    fn test_monadic_result_types() {
+        assert_eq!(run("neg 1 -2").unwrap(), SuperType::from(vec![-1, 2]));
+        assert_eq!(run("abs (-1.5)").unwrap(), SuperType::from(1.5));
+        assert_eq!(run("sqrt 4 9").unwrap(), SuperType::from(vec![2.0, 3.0]));
+        assert_eq!(
+            run("floor (-1.5 2.5)").unwrap(),
+            SuperType::from(vec![-2, 2])
+        );
+        assert_eq!(run("ceiling 1.2").unwrap(), SuperType::from(2));
+        assert_eq!(
+            run("signum (-3 0 2)").unwrap(),
+            SuperType::from(vec![-1, 0, 1])
+        );
+        assert_eq!(run("exp 0").unwrap(), SuperType::from(1.0));
+        assert_eq!(
+            run("log 1 0").unwrap(),
+            SuperType::from(vec![0.0, f64::NEG_INFINITY])
+        );
+    }
+
+    #[test]
+    fn test_nulls_propagate() {
+        let null = SuperType::from(i32::MIN);
+        assert_eq!(neg(null.clone()), Ok(null.clone()));
+        assert_eq!(abs(null.clone()), Ok(null.clone()));
+        assert!(matches!(sqrt(null.clone()), Ok(SuperType::Float(f)) if f.data().0.is_nan()));
+        assert_eq!(floor(SuperType::from(f64::NAN)), Ok(null.clone()));
+        assert_eq!(modulo(null.clone(), SuperType::from(3)), Ok(null));
+        assert!(
+            matches!(sqrt(SuperType::from(-1)), Ok(SuperType::Float(f)) if f.data().0.is_nan())
+        );
+    }
+
+    #[test]
+    fn test_dyadic_broadcasting() {
+        assert_eq!(run("-7 7 mod 3").unwrap(), SuperType::from(vec![2, 1]));
+        assert_eq!(run("7 mod -3").unwrap(), SuperType::from(-2));
+        assert_eq!(run("-7 7 div 2").unwrap(), SuperType::from(vec![-4, 3]));
+        assert_eq!(run("7.5 div 2").unwrap(), SuperType::from(3.0));
+        assert_eq!(
+            run("2 xexp 3 0.5").unwrap(),
+            SuperType::from(vec![8.0, 2f64.sqrt()])
+        );
+        assert_eq!(run("2 xlog 8").unwrap(), SuperType::from(3.0));
+        assert_eq!(
+            run("(1;2.5) mod 2").unwrap(),
+            SuperType::from_items(vec![SuperType::from(1), SuperType::from(0.5)])
+        );
+    }
+}
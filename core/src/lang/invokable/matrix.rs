@@ -0,0 +1,224 @@
+use crate::{
+    Error,
+    lang::{SuperType, invokable::aggregate::to_floats, type_system::TypeMixedList},
+};
+
+// Rows of floats, all of the same length
+type Matrix = Vec<Vec<f64>>;
+
+// A mixed list of numeric rows as a matrix
+fn matrix(value: &SuperType) -> Result<Matrix, Error> {
+    let SuperType::MixedList(rows) = value else {
+        return Err(Error::Type);
+    };
+    let rows = rows
+        .0
+        .iter()
+        .map(|row| match row.is_atom() {
+            true => Err(Error::Type),
+            false => to_floats(row),
+        })
+        .collect::<Result<Matrix, _>>()?;
+    if rows.windows(2).any(|w| w[0].len() != w[1].len()) {
+        return Err(Error::Length);
+    }
+    Ok(rows)
+}
+
+fn columns(matrix: &Matrix) -> usize {
+    matrix.first().map_or(0, Vec::len)
+}
+
+fn to_value(matrix: Matrix) -> SuperType {
+    SuperType::MixedList(TypeMixedList(
+        matrix.into_iter().map(SuperType::from).collect(),
+    ))
+}
+
+fn transpose(matrix: &Matrix) -> Matrix {
+    (0..columns(matrix))
+        .map(|j| matrix.iter().map(|row| row[j]).collect())
+        .collect()
+}
+
+fn multiply(lhs: &Matrix, rhs: &Matrix) -> Result<Matrix, Error> {
+    if columns(lhs) != rhs.len() {
+        return Err(Error::Length);
+    }
+    let rhs = transpose(rhs);
+    Ok(lhs
+        .iter()
+        .map(|row| {
+            rhs.iter()
+                .map(|column| row.iter().zip(column).map(|(a, b)| a * b).sum())
+                .collect()
+        })
+        .collect())
+}
+
+// Solves a x = b by Gauss-Jordan elimination with partial pivoting, None when a is singular
+fn solve(mut a: Matrix, mut b: Matrix) -> Option<Matrix> {
+    let n = a.len();
+    for column in 0..n {
+        let pivot =
+            (column..n).max_by(|&i, &j| a[i][column].abs().total_cmp(&a[j][column].abs()))?;
+        if a[pivot][column].abs() < f64::EPSILON * n as f64 {
+            return None;
+        }
+        a.swap(column, pivot);
+        b.swap(column, pivot);
+        let (pivot_a, pivot_b) = (a[column].clone(), b[column].clone());
+        for (row, (a, b)) in a.iter_mut().zip(b.iter_mut()).enumerate() {
+            if row == column {
+                continue;
+            }
+            let factor = a[column] / pivot_a[column];
+            a.iter_mut()
+                .zip(&pivot_a)
+                .for_each(|(v, p)| *v -= factor * p);
+            b.iter_mut()
+                .zip(&pivot_b)
+                .for_each(|(v, p)| *v -= factor * p);
+        }
+    }
+    for (row, b) in b.iter_mut().enumerate() {
+        b.iter_mut().for_each(|v| *v /= a[row][row]);
+    }
+    Some(b)
+}
+
+fn identity(n: usize) -> Matrix {
+    (0..n)
+        .map(|i| (0..n).map(|j| if i == j { 1.0 } else { 0.0 }).collect())
+        .collect()
+}
+
+/// x mmu y: the matrix product of x and y, a vector on either side taken as a row or a column.
+pub fn mmu(lhs: SuperType, rhs: SuperType) -> Result<SuperType, Error> {
+    if lhs.is_atom() || rhs.is_atom() {
+        return Err(Error::Type);
+    }
+    match (&lhs, &rhs) {
+        (SuperType::MixedList(_), SuperType::MixedList(_)) => {
+            Ok(to_value(multiply(&matrix(&lhs)?, &matrix(&rhs)?)?))
+        }
+        (SuperType::MixedList(_), vector) => {
+            let column = to_floats(vector)?.into_iter().map(|v| vec![v]).collect();
+            let product = multiply(&matrix(&lhs)?, &column)?;
+            Ok(SuperType::from(product.concat()))
+        }
+        (vector, SuperType::MixedList(_)) => {
+            let row = vec![to_floats(vector)?];
+            let product = multiply(&row, &matrix(&rhs)?)?;
+            Ok(SuperType::from(product.concat()))
+        }
+        (l, r) => {
+            let (l, r) = (to_floats(l)?, to_floats(r)?);
+            if l.len() != r.len() {
+                return Err(Error::Length);
+            }
+            Ok(SuperType::from(
+                l.iter().zip(&r).map(|(a, b)| a * b).sum::<f64>(),
+            ))
+        }
+    }
+}
+
+/// inv x: the inverse of the square matrix x, nulls when x is singular.
+pub fn inv(value: SuperType) -> Result<SuperType, Error> {
+    let matrix = matrix(&value)?;
+    let n = matrix.len();
+    if columns(&matrix) != n {
+        return Err(Error::Length);
+    }
+    let inverse = solve(matrix, identity(n)).unwrap_or_else(|| vec![vec![f64::NAN; n]; n]);
+    Ok(to_value(inverse))
+}
+
+/// x lsq y: the least squares solution a of x = a mmu y, a vector x giving a vector.
+pub fn lsq(lhs: SuperType, rhs: SuperType) -> Result<SuperType, Error> {
+    let y = matrix(&rhs)?;
+    let x = match lhs {
+        SuperType::MixedList(_) => matrix(&lhs)?,
+        ref vector if !vector.is_atom() => vec![to_floats(vector)?],
+        _ => return Err(Error::Type),
+    };
+    if columns(&x) != columns(&y) {
+        return Err(Error::Length);
+    }
+    // a (y y') = x y', y y' being symmetric
+    let transposed = transpose(&y);
+    let gram = multiply(&y, &transposed)?;
+    let projection = transpose(&multiply(&x, &transposed)?);
+    let solution =
+        solve(gram, projection).unwrap_or_else(|| vec![vec![f64::NAN; x.len()]; y.len()]);
+    let a = transpose(&solution);
+    Ok(match lhs {
+        SuperType::MixedList(_) => to_value(a),
+        _ => SuperType::from(a.concat()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::run;
+
+    fn rows(rows: Matrix) -> SuperType {
+        to_value(rows)
+    }
+
+    fn assert_close(value: Result<SuperType, Error>, expected: Matrix) {
+        let actual = matrix(&value.unwrap()).unwrap();
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.concat().iter().zip(expected.concat()) {
+            assert!((a - e).abs() < 1e-9, "{a} is not {e}");
+        }
+    }
+
+    #[test]
+    fn test_mmu() {
+        assert_eq!(
+            run("(1 2;3 4) mmu (5 6;7 8)"),
+            Ok(rows(vec![vec![19.0, 22.0], vec![43.0, 50.0]]))
+        );
+        assert_eq!(run("(1 2;3 4) $ 1 1"), Ok(SuperType::from(vec![3.0, 7.0])));
+        assert_eq!(
+            run("1 1 mmu (1 2;3 4)"),
+            Ok(SuperType::from(vec![4.0, 6.0]))
+        );
+        assert_eq!(run("1 2 3 mmu 4 5 6"), Ok(SuperType::from(32.0)));
+        assert_eq!(run("(1 2;3 4) mmu (1 2 3;4 5 6;7 8 9)"), Err(Error::Length));
+        assert_eq!(run("(1 2;3 4 5) mmu (1 2;3 4)"), Err(Error::Length));
+    }
+
+    #[test]
+    fn test_inv() {
+        assert_eq!(
+            run("inv (2 0;0 4.0)"),
+            Ok(rows(vec![vec![0.5, 0.0], vec![0.0, 0.25]]))
+        );
+        assert_close(
+            run("m:(4 7;2 6); m mmu inv m"),
+            vec![vec![1.0, 0.0], vec![0.0, 1.0]],
+        );
+        let singular = matrix(&run("inv (1 2;2 4)").unwrap()).unwrap();
+        assert!(singular.concat().iter().all(|v| v.is_nan()));
+        assert_eq!(run("inv (1 2 3;4 5 6)"), Err(Error::Length));
+    }
+
+    #[test]
+    fn test_lsq_fits_a_line() {
+        // y = 1 + 2 t over t = 0 1 2 3
+        let fitted = run("1 3 5 7 lsq (1 1 1 1;0 1 2 3)").unwrap();
+        assert_close(
+            Ok(to_value(vec![to_floats(&fitted).unwrap()])),
+            vec![vec![1.0, 2.0]],
+        );
+        assert_close(
+            run("(1 3 5 7;2 2 2 2) lsq (1 1 1 1;0 1 2 3)"),
+            vec![vec![1.0, 2.0], vec![2.0, 0.0]],
+        );
+        assert_eq!(run("1 2 lsq (1 1 1;0 1 2)"), Err(Error::Length));
+    }
+}
@@ -1,7 +1,70 @@
+mod aggregate;
+mod arithmetic;
+mod cast;
+mod comparison;
+pub mod function;
+pub(crate) mod join;
+pub mod kernel;
+pub(crate) mod list;
+mod math;
+mod matrix;
 pub mod operator;
+pub(crate) mod parallel;
+mod propagation;
+mod regex;
+mod search;
+mod set;
+mod sort;
+mod stats;
+mod string;
 pub mod unary;
 
-use crate::lang::SuperType;
+use crate::lang::{
+    SuperType,
+    type_system::{TypeFunction, TypeOperator, TypeUnary},
+};
+
+use self::{function::Function, operator::Operator, unary::Unary};
+
+/// Resolves the name of a builtin function to its value.
+pub fn builtin(name: &str) -> Option<SuperType> {
+    Unary::from_name(name)
+        .map(|unary| SuperType::UnaryOperation(TypeUnary(unary)))
+        .or_else(|| Function::from_name(name).map(|f| SuperType::Function(TypeFunction(f))))
+}
+
+/// Resolves the symbol of an operator, e.g. `+`, to its value.
+pub fn operator(symbol: &str) -> Option<SuperType> {
+    Operator::from_symbol(symbol).map(|op| SuperType::BinaryOperation(TypeOperator(op)))
+}
+
+/// Invokes a function with as many arguments as given, `f[]` passes a single Nothing.
+pub fn invoke(function: &SuperType, arguments: Vec<SuperType>) -> Result<SuperType, crate::Error> {
+    let count = arguments.len();
+    let mut arguments = arguments.into_iter();
+    let mut next = || arguments.next().unwrap();
+    match count {
+        0 => function.invoke_1(SuperType::Nothing),
+        1 => function.invoke_1(next()),
+        2 => function.invoke_2(next(), next()),
+        3 => function.invoke_3(next(), next(), next()),
+        4 => function.invoke_4(next(), next(), next(), next()),
+        5 => function.invoke_5(next(), next(), next(), next(), next()),
+        6 => function.invoke_6(next(), next(), next(), next(), next(), next()),
+        7 => function.invoke_7(next(), next(), next(), next(), next(), next(), next()),
+        8 => function.invoke_8(
+            next(),
+            next(),
+            next(),
+            next(),
+            next(),
+            next(),
+            next(),
+            next(),
+        ),
+        _ => Err(crate::Error::Rank),
+    }
+}
 
 pub trait Invokable {
     fn invoke_1(&self, _arg1: SuperType) -> Result<SuperType, crate::Error> {
@@ -54,6 +117,7 @@ pub trait Invokable {
         Err(crate::Error::Rank)
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn invoke_7(
         &self,
         _arg1: SuperType,
@@ -67,6 +131,7 @@ pub trait Invokable {
         Err(crate::Error::Rank)
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn invoke_8(
         &self,
         _arg1: SuperType,
@@ -81,3 +146,54 @@ pub trait Invokable {
         Err(crate::Error::Rank)
     }
 }
+
+// Applying data indexes it, e.g. x[1] or d`key
+impl Invokable for SuperType {
+    fn invoke_1(&self, arg1: SuperType) -> Result<SuperType, crate::Error> {
+        match self {
+            SuperType::UnaryOperation(unary) => unary.0.invoke_1(arg1),
+            SuperType::BinaryOperation(op) => op.0.invoke_1(arg1),
+            SuperType::Function(function) => function.0.invoke_1(arg1),
+            // lambdas need a context to run in, see ExecutionContext::apply
+            SuperType::Lambda(_) => Err(crate::Error::NotYetImplemented),
+            data => list::index(data, arg1),
+        }
+    }
+
+    fn invoke_2(&self, arg1: SuperType, arg2: SuperType) -> Result<SuperType, crate::Error> {
+        match self {
+            SuperType::BinaryOperation(op) => op.0.invoke_2(arg1, arg2),
+            SuperType::Function(function) => function.0.invoke_2(arg1, arg2),
+            SuperType::UnaryOperation(_) => Err(crate::Error::Rank),
+            // lambdas need a context to run in, see ExecutionContext::apply
+            SuperType::Lambda(_) => Err(crate::Error::NotYetImplemented),
+            // index at depth, e.g. m[i;j]
+            data => list::index(&list::index(data, arg1)?, arg2),
+        }
+    }
+
+    fn invoke_3(
+        &self,
+        arg1: SuperType,
+        arg2: SuperType,
+        arg3: SuperType,
+    ) -> Result<SuperType, crate::Error> {
+        match self {
+            SuperType::Function(function) => function.0.invoke_3(arg1, arg2, arg3),
+            _ => Err(crate::Error::Rank),
+        }
+    }
+
+    fn invoke_4(
+        &self,
+        arg1: SuperType,
+        arg2: SuperType,
+        arg3: SuperType,
+        arg4: SuperType,
+    ) -> Result<SuperType, crate::Error> {
+        match self {
+            SuperType::Function(function) => function.0.invoke_4(arg1, arg2, arg3, arg4),
+            _ => Err(crate::Error::Rank),
+        }
+    }
+}
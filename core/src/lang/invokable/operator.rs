@@ -1,4 +1,16 @@
-use crate::Error;
+use std::cell::Cell;
+
+use crate::{
+    Error,
+    lang::{
+        SuperType,
+        invokable::{
+            Invokable, arithmetic, cast, comparison, list, math,
+            propagation::{self, Builtin},
+            search,
+        },
+    },
+};
 
 // Every operator takes 2 SuperType and returns a SuperType
 #[derive(Clone, Debug, Copy, PartialEq, Eq)]
@@ -24,15 +36,128 @@ pub enum Operator {
     Fill, // ^
     Take, // #
     Cut,  // _
+    Find, // ?
+
+    // Dictionary operators
+    Dict, // !
+
+    // Type operators
+    Cast, // $
 
     // Assignement operators
-    Assign, // :
+    Assign,       // :
+    GlobalAssign, // ::
 
     // Logical operators
     And, // &
     Or,  // |
 }
 
+impl Operator {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Operator::Add => "+",
+            Operator::Subtract => "-",
+            Operator::Multiply => "*",
+            Operator::Divide => "%",
+            Operator::Equals => "=",
+            Operator::NotEquals => "<>",
+            Operator::Match => "~",
+            Operator::LessThan => "<",
+            Operator::UpTo => "<=",
+            Operator::AtLeast => ">=",
+            Operator::GreaterThan => ">",
+            Operator::Join => ",",
+            Operator::Fill => "^",
+            Operator::Take => "#",
+            Operator::Cut => "_",
+            Operator::Find => "?",
+            Operator::Dict => "!",
+            Operator::Cast => "$",
+            Operator::Assign => ":",
+            Operator::GlobalAssign => "::",
+            Operator::And => "&",
+            Operator::Or => "|",
+        }
+    }
+
+    pub fn from_symbol(symbol: &str) -> Option<Operator> {
+        let op = match symbol {
+            "+" => Operator::Add,
+            "-" => Operator::Subtract,
+            "*" => Operator::Multiply,
+            "%" => Operator::Divide,
+            "=" => Operator::Equals,
+            "<>" => Operator::NotEquals,
+            "~" => Operator::Match,
+            "<" => Operator::LessThan,
+            "<=" => Operator::UpTo,
+            ">=" => Operator::AtLeast,
+            ">" => Operator::GreaterThan,
+            "," => Operator::Join,
+            "^" => Operator::Fill,
+            "#" => Operator::Take,
+            "_" => Operator::Cut,
+            "?" => Operator::Find,
+            "!" => Operator::Dict,
+            "$" => Operator::Cast,
+            ":" => Operator::Assign,
+            "::" => Operator::GlobalAssign,
+            "&" => Operator::And,
+            "|" => Operator::Or,
+            _ => return None,
+        };
+        Some(op)
+    }
+}
+
+impl Invokable for Operator {
+    // the monadic form of an operator is a different function, e.g. -x negates x
+    fn invoke_1(&self, arg1: SuperType) -> Result<SuperType, Error> {
+        match self {
+            Operator::Subtract => math::neg(arg1),
+            Operator::Take => Ok(SuperType::from(arg1.count() as i32)),
+            Operator::Join => list::enlist(arg1),
+            Operator::Match => comparison::not(arg1),
+            Operator::Or => list::reverse(arg1),
+            Operator::And => list::where_(arg1),
+            Operator::Multiply => list::first(arg1),
+            Operator::Dict => list::til_or_key(arg1),
+            Operator::Add => list::flip(arg1),
+            _ => Err(Error::NotYetImplemented),
+        }
+    }
+
+    fn invoke_2(&self, arg1: SuperType, arg2: SuperType) -> Result<SuperType, Error> {
+        let sorted = propagation::keeps_sorted(Builtin::Operator(*self), &[&arg1, &arg2]);
+        let result = match self {
+            Operator::Add => arithmetic::add(arg1, arg2),
+            Operator::Subtract => arithmetic::sub(arg1, arg2),
+            Operator::Multiply => arithmetic::mul(arg1, arg2),
+            Operator::Divide => arithmetic::div(arg1, arg2),
+            Operator::Equals => comparison::equals(arg1, arg2),
+            Operator::NotEquals => comparison::not_equals(arg1, arg2),
+            Operator::Match => Ok(SuperType::from(arg1 == arg2)),
+            Operator::LessThan => comparison::less_than(arg1, arg2),
+            Operator::UpTo => comparison::up_to(arg1, arg2),
+            Operator::AtLeast => comparison::at_least(arg1, arg2),
+            Operator::GreaterThan => comparison::greater_than(arg1, arg2),
+            Operator::Join => list::join(arg1, arg2),
+            Operator::Take => list::take(arg1, arg2),
+            Operator::Cut => list::drop(arg1, arg2),
+            Operator::Dict => list::dict(arg1, arg2),
+            Operator::Cast => cast::cast(arg1, arg2),
+            Operator::And => comparison::min(arg1, arg2),
+            Operator::Or => comparison::max(arg1, arg2),
+            Operator::Find => search::find(arg1, arg2),
+            Operator::Fill | Operator::Assign | Operator::GlobalAssign => {
+                Err(Error::NotYetImplemented)
+            }
+        }?;
+        Ok(propagation::apply(sorted, result))
+    }
+}
+
 pub trait OperatorAdd<Tin, Tout> {
     fn add(_lhs: Tin, _rhs: Tin) -> Result<Tout, Error> {
         Err(Error::Type)
@@ -60,3 +185,63 @@ pub trait OperatorDivide<Tin, Tout> {
 pub trait OperatorEquals<Tin> {
     fn equals(lhs: Tin, rhs: Tin) -> bool;
 }
+
+/// What int arithmetic does when a result does not fit: wrap around as q does, the way into the
+/// null and the infinities, or raise Error::Overflow. q has no switch for it, so neither does the
+/// language: raising is chosen from Rust, with `ExecutionContext::set_overflow`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Overflow {
+    #[default]
+    Wrap,
+    Raise,
+}
+
+thread_local! {
+    static OVERFLOW: Cell<Overflow> = const { Cell::new(Overflow::Wrap) };
+    static OVERFLOWED: Cell<bool> = const { Cell::new(false) };
+}
+
+// Puts the previous mode back when dropped, also when unwinding
+struct OverflowGuard(Overflow);
+
+impl Drop for OverflowGuard {
+    fn drop(&mut self) {
+        OVERFLOW.set(self.0);
+    }
+}
+
+/// Runs `f` with int arithmetic overflowing as `overflow` says.
+pub(crate) fn with_overflow<T>(overflow: Overflow, f: impl FnOnce() -> T) -> T {
+    let _guard = OverflowGuard(OVERFLOW.replace(overflow));
+    f()
+}
+
+/// How int arithmetic overflows at the moment, for threads working on behalf of this one.
+pub(crate) fn overflow() -> Overflow {
+    OVERFLOW.get()
+}
+
+/// The result of an int operation: `checked` when it fits, else `wrapped` or Error::Overflow.
+pub fn overflowing<T>(checked: Option<T>, wrapped: T) -> Result<T, Error> {
+    match (checked, OVERFLOW.get()) {
+        (Some(value), _) => Ok(value),
+        (None, Overflow::Wrap) => Ok(wrapped),
+        (None, Overflow::Raise) => Err(Error::Overflow),
+    }
+}
+
+/// Records an overflow in a kernel that cannot stop on it, see `checking_overflow`.
+pub(crate) fn note_overflow() {
+    OVERFLOWED.set(true);
+}
+
+/// Runs the kernel `f`, then Error::Overflow when overflows raise and `f` noted one. The note is
+/// cleared before `f` runs, so that one left behind by an error elsewhere is not taken for its.
+pub(crate) fn checking_overflow<T>(f: impl FnOnce() -> T) -> Result<T, Error> {
+    OVERFLOWED.set(false);
+    let result = f();
+    match OVERFLOWED.replace(false) && OVERFLOW.get() == Overflow::Raise {
+        true => Err(Error::Overflow),
+        false => Ok(result),
+    }
+}
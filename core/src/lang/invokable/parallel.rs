@@ -0,0 +1,112 @@
+//! Splits the kernels over long lists between secondary threads, as q does when started with
+//! `-s`. A list is cut into one run of items for each thread and what the threads give back is
+//! put together in the order of the runs, so that results do not depend on the number of threads.
+
+use std::{cell::RefCell, ops::Range, sync::Arc};
+
+use rayon::{ThreadPool, ThreadPoolBuilder, prelude::*};
+
+use crate::Error;
+
+/// Lists shorter than this stay on the calling thread, handing them out costing more than it
+/// saves.
+pub const THRESHOLD: usize = 1 << 16;
+
+thread_local! {
+    static POOL: RefCell<Option<Arc<ThreadPool>>> = const { RefCell::new(None) };
+}
+
+/// A pool of `threads` secondary threads, None for 0 or 1 as a single one would only wait on
+/// the calling thread.
+pub(crate) fn pool(threads: usize) -> Result<Option<Arc<ThreadPool>>, Error> {
+    if threads < 2 {
+        return Ok(None);
+    }
+    let pool = ThreadPoolBuilder::new().num_threads(threads).build();
+    pool.map(|pool| Some(Arc::new(pool)))
+        .map_err(|e| Error::MalformedProgram(e.to_string()))
+}
+
+// Puts the previous pool back when dropped, also when unwinding
+struct PoolGuard(Option<Arc<ThreadPool>>);
+
+impl Drop for PoolGuard {
+    fn drop(&mut self) {
+        POOL.set(self.0.take());
+    }
+}
+
+/// Runs `f` with the kernels splitting long lists over `pool`, or not at all when None.
+pub(crate) fn with_pool<T>(pool: Option<Arc<ThreadPool>>, f: impl FnOnce() -> T) -> T {
+    let _guard = PoolGuard(POOL.replace(pool));
+    f()
+}
+
+/// The secondary threads of the context running on this thread, if it has any.
+pub(crate) fn current() -> Option<Arc<ThreadPool>> {
+    POOL.with_borrow(|pool| pool.clone())
+}
+
+/// Runs `f` on the threads of `pool` over consecutive runs of `len` items, one for each thread
+/// and each starting at a multiple of `align`, and gives back what it returns in the order of
+/// the runs.
+pub(crate) fn split<T: Send>(
+    pool: &ThreadPool,
+    len: usize,
+    align: usize,
+    f: impl Fn(Range<usize>) -> T + Sync,
+) -> Vec<T> {
+    let size = len.div_ceil(pool.current_num_threads()).max(1);
+    let size = size.next_multiple_of(align);
+    pool.install(|| {
+        let starts = (0..len).step_by(size).collect::<Vec<_>>();
+        starts
+            .into_par_iter()
+            .map(|start| f(start..len.min(start + size)))
+            .collect()
+    })
+}
+
+/// As split, for the kernels: short lists, or no pool, make a single run on the calling thread.
+pub(crate) fn runs<T: Send>(
+    len: usize,
+    align: usize,
+    f: impl Fn(Range<usize>) -> T + Sync,
+) -> Vec<T> {
+    match current() {
+        Some(pool) if len >= THRESHOLD => split(&pool, len, align, f),
+        _ => vec![f(0..len)],
+    }
+}
+
+/// The items of runs one after the other, the single run of a short list as it is.
+pub(crate) fn concat<T: Copy>(mut runs: Vec<Vec<T>>) -> Vec<T> {
+    match runs.len() {
+        1 => runs.pop().unwrap(),
+        _ => runs.concat(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_runs_cover_the_items_in_order() {
+        let len = THRESHOLD * 3 + 5;
+        let serial = runs(len, 64, |run| run);
+        assert_eq!((serial.len(), serial[0].clone()), (1, 0..len));
+
+        let split = with_pool(pool(4).unwrap(), || runs(len, 64, |run| run));
+        assert_eq!(split.len(), 4);
+        assert!(split.iter().all(|run| run.start % 64 == 0));
+        assert_eq!(split.first().unwrap().start, 0);
+        assert_eq!(split.last().unwrap().end, len);
+        assert!(split.windows(2).all(|w| w[0].end == w[1].start));
+
+        // short lists stay whole, and the pool goes away with the call
+        let short = with_pool(pool(4).unwrap(), || runs(10, 1, |run| run));
+        assert_eq!((short.len(), short[0].clone()), (1, 0..10));
+        assert_eq!(runs(len, 1, |run| run).len(), 1);
+    }
+}
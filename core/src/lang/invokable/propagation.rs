@@ -0,0 +1,132 @@
+use crate::lang::{
+    SuperType,
+    invokable::{comparison, operator::Operator, unary::Unary},
+    type_system::{Attribute, TypeTrait, with_list},
+};
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Builtin {
+    Unary(Unary),
+    Operator(Operator),
+}
+
+// When the result of a builtin is sorted
+#[derive(Clone, Copy)]
+enum Rule {
+    Always,
+    // n#x with x sorted, as long as it does not wrap around
+    TakeOfSorted,
+    // x+y with one sorted and the other a positive atom, as long as no int wraps around
+    ShiftOfSorted,
+}
+
+// Builtins missing from the table give results without attributes
+const RULES: [(Builtin, Rule); 4] = [
+    (Builtin::Unary(Unary::Til), Rule::Always),
+    (Builtin::Unary(Unary::Asc), Rule::Always),
+    (Builtin::Operator(Operator::Take), Rule::TakeOfSorted),
+    (Builtin::Operator(Operator::Add), Rule::ShiftOfSorted),
+];
+
+fn is_sorted(value: &SuperType) -> bool {
+    with_list!(value, list => Attribute::has_sorted(list.get_attributes()), other => match other {
+        SuperType::Bools(bits) => Attribute::has_sorted(bits.get_attributes()),
+        _ => false,
+    })
+}
+
+fn is_positive(value: &SuperType) -> bool {
+    value.is_atom()
+        && matches!(comparison::floats(value), Some(comparison::Operand::Atom(v)) if v > 0.0)
+}
+
+// Whether adding the positive atom `shift` to the sorted `list` keeps every item below the
+// int wrap around, past which the largest items would land on the null and the negatives
+fn shifts_in_range(list: &SuperType, shift: &SuperType) -> bool {
+    let is_float = |value: &SuperType| matches!(value, SuperType::Float(_) | SuperType::Floats(_));
+    if is_float(list) || is_float(shift) {
+        return true;
+    }
+    let last = list.count().checked_sub(1).and_then(|last| list.item(last));
+    match (
+        last.as_ref().and_then(comparison::floats),
+        comparison::floats(shift),
+    ) {
+        (Some(comparison::Operand::Atom(last)), Some(comparison::Operand::Atom(shift))) => {
+            last + shift <= i32::MAX as f64
+        }
+        _ => last.is_none(),
+    }
+}
+
+/// Works out, before `builtin` runs, whether its result will be sorted.
+pub(crate) fn keeps_sorted(builtin: Builtin, arguments: &[&SuperType]) -> bool {
+    let Some((_, rule)) = RULES.iter().find(|(b, _)| *b == builtin) else {
+        return false;
+    };
+    match (rule, arguments) {
+        (Rule::Always, _) => true,
+        (Rule::TakeOfSorted, [SuperType::Int(n), list]) => {
+            is_sorted(list) && n.data().0.unsigned_abs() as usize <= list.count()
+        }
+        (Rule::ShiftOfSorted, [lhs, rhs]) => {
+            let shifts =
+                |list, shift| is_sorted(list) && is_positive(shift) && shifts_in_range(list, shift);
+            shifts(lhs, rhs) || shifts(rhs, lhs)
+        }
+        _ => false,
+    }
+}
+
+/// Marks a result sorted when `keeps_sorted` said it would be.
+pub(crate) fn apply(sorted: bool, mut result: SuperType) -> SuperType {
+    if sorted {
+        with_list!(&mut result, list => list.assume_sorted(), other => {
+            if let SuperType::Bools(bits) = other {
+                bits.assume_sorted()
+            }
+        });
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{core::execution_context::ExecutionContext, lang::SuperType};
+
+    fn attr(program: &str) -> SuperType {
+        ExecutionContext::default()
+            .run(&format!("attr ({program})"))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_sorted_results() {
+        assert_eq!(attr("til 5"), SuperType::symbol("s"));
+        assert_eq!(attr("2#til 5"), SuperType::symbol("s"));
+        assert_eq!(attr("-2#til 5"), SuperType::symbol("s"));
+        assert_eq!(attr("3+til 5"), SuperType::symbol("s"));
+        assert_eq!(attr("(til 5)+0.5"), SuperType::symbol("s"));
+    }
+
+    #[test]
+    fn test_other_results_drop_attributes() {
+        assert_eq!(attr("7#til 5"), SuperType::symbol(""));
+        assert_eq!(attr("-3+til 5"), SuperType::symbol(""));
+        assert_eq!(attr("reverse til 5"), SuperType::symbol(""));
+        assert_eq!(attr("(til 5)*2"), SuperType::symbol(""));
+        assert_eq!(attr("2#`u#3 1 2"), SuperType::symbol(""));
+        // wrapping around leaves the shifted items out of order
+        assert_eq!(attr("(`s#0 2147483640)+10"), SuperType::symbol(""));
+        assert_eq!(attr("1+`s#1 0W"), SuperType::symbol(""));
+        assert_eq!(attr("(`s#1 2)+0.5"), SuperType::symbol("s"));
+    }
+
+    #[test]
+    fn test_sorting_a_wrapped_shift() {
+        assert_eq!(
+            ExecutionContext::default().run("asc (`s#0 2147483640)+10"),
+            Ok(SuperType::from(vec![-2147483646, 10]))
+        );
+    }
+}
@@ -0,0 +1,170 @@
+use regex::bytes::{Captures, Regex};
+
+use crate::{
+    Error,
+    lang::{
+        SuperType,
+        invokable::{
+            Invokable,
+            math::each,
+            string::{bytes, string},
+        },
+        type_system::{TypeMixedList, TypeRegex},
+    },
+};
+
+// A compiled regex as it is, a string compiled
+fn compiled(pattern: &SuperType) -> Result<Regex, Error> {
+    match pattern {
+        SuperType::Regex(regex) => Ok(regex.0.clone()),
+        pattern => {
+            let source = bytes(pattern).ok_or(Error::Type)?;
+            let source = String::from_utf8(source).map_err(|e| Error::Parse(e.to_string()))?;
+            Regex::new(&source).map_err(|e| Error::Parse(e.to_string()))
+        }
+    }
+}
+
+// Applies `f` to a string, or to each string of a list of strings
+fn strings(
+    value: &SuperType,
+    f: &impl Fn(&[u8]) -> Result<SuperType, Error>,
+) -> Result<SuperType, Error> {
+    match bytes(value) {
+        Some(text) => f(&text),
+        None => each(value, |item| strings(&item, f)).unwrap_or(Err(Error::Type)),
+    }
+}
+
+fn list(items: Vec<SuperType>) -> SuperType {
+    SuperType::MixedList(TypeMixedList(items))
+}
+
+/// regex p: the string p compiled into a regular expression, to be kept in a variable and
+/// given to rmatch, rss, rfind, rssr and rvs in place of the string.
+pub fn regex(pattern: SuperType) -> Result<SuperType, Error> {
+    Ok(SuperType::Regex(TypeRegex(compiled(&pattern)?)))
+}
+
+/// s rmatch p: whether the regex p matches somewhere in the string s.
+pub fn rmatch(value: SuperType, pattern: SuperType) -> Result<SuperType, Error> {
+    let regex = compiled(&pattern)?;
+    strings(&value, &|text| Ok(SuperType::from(regex.is_match(text))))
+}
+
+/// s rss p: the positions where the matches of the regex p start in the string s.
+pub fn rss(value: SuperType, pattern: SuperType) -> Result<SuperType, Error> {
+    let regex = compiled(&pattern)?;
+    strings(&value, &|text| {
+        let starts = regex.find_iter(text).map(|m| m.start() as i32);
+        Ok(SuperType::from(starts.collect::<Vec<_>>()))
+    })
+}
+
+// The whole match, followed by the groups when the regex has any
+fn captured(regex: &Regex, captures: Captures) -> SuperType {
+    let group = |i| string(captures.get(i).map_or(&[][..], |m| m.as_bytes()));
+    match regex.captures_len() {
+        1 => group(0),
+        groups => list((0..groups).map(group).collect()),
+    }
+}
+
+/// s rfind p: the matches of the regex p in the string s. When p has groups, each match is the
+/// matched string followed by what each group captured.
+pub fn rfind(value: SuperType, pattern: SuperType) -> Result<SuperType, Error> {
+    let regex = compiled(&pattern)?;
+    strings(&value, &|text| {
+        let matches = regex.captures_iter(text).map(|c| captured(&regex, c));
+        Ok(list(matches.collect()))
+    })
+}
+
+/// rssr[s;p;r]: s with each match of the regex p replaced by the string r, where $1 stands for
+/// the first group, or by r applied to the matched string when r is a function.
+pub fn rssr(
+    value: SuperType,
+    pattern: SuperType,
+    replacement: SuperType,
+) -> Result<SuperType, Error> {
+    let regex = compiled(&pattern)?;
+    strings(&value, &|text| {
+        let mut result = Vec::new();
+        let mut copied = 0;
+        for captures in regex.captures_iter(text) {
+            let found = captures.get(0).unwrap();
+            result.extend_from_slice(&text[copied..found.start()]);
+            match bytes(&replacement) {
+                Some(template) => captures.expand(&template, &mut result),
+                None => {
+                    let replaced = replacement.invoke_1(string(found.as_bytes()))?;
+                    result.extend(bytes(&replaced).ok_or(Error::Type)?);
+                }
+            }
+            copied = found.end();
+        }
+        result.extend_from_slice(&text[copied..]);
+        Ok(string(&result))
+    })
+}
+
+/// p rvs s: the string s split at each match of the regex p.
+pub fn rvs(pattern: SuperType, value: SuperType) -> Result<SuperType, Error> {
+    let regex = compiled(&pattern)?;
+    strings(&value, &|text| {
+        Ok(list(regex.split(text).map(string).collect()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{run, show};
+
+    #[test]
+    fn test_match_and_positions() {
+        assert_eq!(run("\"ab12\" rmatch \"[0-9]+\""), Ok(SuperType::from(true)));
+        assert_eq!(show("(\"a1\";\"bc\";\"22\") rmatch \"\\\\d\""), "101b");
+        assert_eq!(show("\"a1bb22\" rss \"[0-9]+\""), "1 4");
+        // positions count bytes, as chars are bytes
+        assert_eq!(show("\"é1\" rss \"1\""), ",2");
+        assert!(matches!(run("\"a\" rmatch \"(\""), Err(Error::Parse(_))));
+    }
+
+    #[test]
+    fn test_find_all_with_captures() {
+        assert_eq!(show("\"a1bb22\" rfind \"[0-9]+\""), "(,\"1\";\"22\")");
+        assert_eq!(
+            show("\"k=v x=yy\" rfind \"(\\\\w)=(\\\\w+)\""),
+            "((\"k=v\";,\"k\";,\"v\");(\"x=yy\";,\"x\";\"yy\"))"
+        );
+        assert_eq!(show("\"abc\" rfind \"[0-9]\""), "()");
+    }
+
+    #[test]
+    fn test_replace_and_split() {
+        assert_eq!(
+            show("rssr[\"2024-01-31\";\"(\\\\d+)-(\\\\d+)-(\\\\d+)\";\"$3.$2.$1\"]"),
+            "\"31.01.2024\""
+        );
+        assert_eq!(show("rssr[\"a1b2\";\"[a-z]\";upper]"), "\"A1B2\"");
+        assert_eq!(show("\",\\\\s*\" rvs \"a, b,c\""), "(,\"a\";,\"b\";,\"c\")");
+        assert_eq!(
+            show("\" +\" rvs (\"a  b\";\"c\")"),
+            "((,\"a\";,\"b\");,,\"c\")"
+        );
+    }
+
+    #[test]
+    fn test_compiled_pattern_in_a_variable() {
+        assert_eq!(show("p:regex \"^ERR\"; p"), "regex \"^ERR\"");
+        assert_eq!(
+            show(
+                "p:regex \"^ERR\"; t:([] msg:(\"ERR disk\";\"ok\";\"ERR net\")); \
+                 exec msg from select from t where msg rmatch p"
+            ),
+            "(\"ERR disk\";\"ERR net\")"
+        );
+        assert_eq!(run("regex 1"), Err(Error::Type));
+    }
+}
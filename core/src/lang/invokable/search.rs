@@ -0,0 +1,431 @@
+use std::cmp::Ordering;
+
+use crate::{
+    Error,
+    lang::{
+        SuperType,
+        invokable::comparison::{Operand, floats},
+        type_system::{
+            Attribute, Key, List, ListItem, TypeDictionary, TypeFloat, TypeMixedList, TypeTrait,
+        },
+    },
+};
+
+// Runs `$body` with `$list` bound to the typed list `$value` and `$items` to the items of `$other`
+// as a slice, when `$other` is an atom or a list of the same type. Runs `$fallback` otherwise.
+macro_rules! with_same_type {
+    ($value:expr, $other:expr, |$list:ident, $items:ident| $body:expr, $fallback:expr) => {
+        with_same_type!(
+            @arms $value, $other, $list, $items, $body, $fallback,
+            Byte => Bytes, Int => Ints, Float => Floats, Char => Chars, Symbol => Symbols,
+            Date => Dates
+        )
+    };
+    (@arms $value:expr, $other:expr, $list:ident, $items:ident, $body:expr, $fallback:expr,
+        $($atom:ident => $variant:ident),*) => {
+        match ($value, $other) {
+            // packed bools are searched unpacked
+            (SuperType::Bools(bits), SuperType::Bool(atom)) => {
+                let $list = &bits.to_list();
+                let $items = std::slice::from_ref(atom.data());
+                $body
+            }
+            (SuperType::Bools(bits), SuperType::Bools(items)) => {
+                let $list = &bits.to_list();
+                let items = items.data();
+                let $items = items.as_slice();
+                $body
+            }
+            $(
+                (SuperType::$variant($list), SuperType::$atom(atom)) => {
+                    let $items = std::slice::from_ref(atom.data());
+                    $body
+                }
+                (SuperType::$variant($list), SuperType::$variant(items)) => {
+                    let $items = items.data().as_slice();
+                    $body
+                }
+            )*
+            _ => $fallback,
+        }
+    };
+}
+
+// The numbers of an atom or a list, to search lists of different numeric types
+fn numbers(value: &SuperType) -> Option<Vec<f64>> {
+    match floats(value)? {
+        Operand::Atom(number) => Some(vec![number]),
+        Operand::List(numbers) => Some(numbers),
+    }
+}
+
+// Ints for each item of `rhs`, a single int when it is an atom
+fn shaped(rhs: &SuperType, values: Vec<i32>) -> SuperType {
+    match rhs.is_atom() {
+        true => SuperType::from(values[0]),
+        false => SuperType::from(values),
+    }
+}
+
+// The span of the items equal to `item` in a sorted list
+fn equal_range<T: ListItem>(data: &[T], item: &T) -> std::ops::Range<usize> {
+    let start = data.partition_point(|x| x.order(item) == Ordering::Less);
+    let end = data.partition_point(|x| x.order(item) != Ordering::Greater);
+    start..end.max(start)
+}
+
+fn is_plain<T: ListItem>(list: &List<T>) -> bool {
+    list.get_attributes() == Attribute::None as u8
+}
+
+// The first position of `item`, looked up through the attributes of the list when it has any
+fn position<T: ListItem>(list: &List<T>, item: &T) -> Option<usize> {
+    let data = list.data();
+    if let Some(index) = list.index() {
+        return index.first(&item.key());
+    }
+    if Attribute::has_sorted(list.get_attributes()) {
+        let range = equal_range(data, item);
+        return (!range.is_empty()).then_some(range.start);
+    }
+    data.iter().position(|x| x.order(item) == Ordering::Equal)
+}
+
+/// x?y: the position of the first y in x, the count of x when there is none.
+/// On a dictionary, the key of the first value y.
+pub fn find(lhs: SuperType, rhs: SuperType) -> Result<SuperType, Error> {
+    if let SuperType::Dictionary(dictionary) = &lhs {
+        let keys = dictionary.keys();
+        let key = |position: i32| {
+            keys.item(position as usize)
+                .unwrap_or_else(|| keys.null_item())
+        };
+        return Ok(match find(dictionary.values().clone(), rhs)? {
+            SuperType::Int(position) => key(position.data().0),
+            positions => {
+                let positions = positions.into_items();
+                SuperType::from_items(
+                    positions
+                        .iter()
+                        .map(|p| match p {
+                            SuperType::Int(p) => key(p.data().0),
+                            _ => keys.null_item(),
+                        })
+                        .collect(),
+                )
+            }
+        });
+    }
+    if lhs.is_atom() {
+        return Err(Error::Type);
+    }
+
+    // looking several items up in a list without attributes is cheaper through a hash
+    let hashed = |wanted: Vec<Key>| {
+        let grouping = lhs.grouping();
+        wanted.iter().map(|key| grouping.first(key)).collect()
+    };
+    let positions: Vec<Option<usize>> = with_same_type!(
+        &lhs,
+        &rhs,
+        |list, items| match items.len() > 1 && is_plain(list) {
+            true => hashed(items.iter().map(ListItem::key).collect()),
+            false => items.iter().map(|item| position(list, item)).collect(),
+        },
+        match rhs.is_atom() {
+            true => hashed(vec![rhs.key()]),
+            false => hashed(rhs.item_keys()),
+        }
+    );
+    let count = lhs.count();
+    let positions = positions
+        .into_iter()
+        .map(|p| p.unwrap_or(count) as i32)
+        .collect();
+    Ok(shaped(&rhs, positions))
+}
+
+/// x in y: whether each item of x is an item of y.
+pub fn in_(lhs: SuperType, rhs: SuperType) -> Result<SuperType, Error> {
+    let rhs = match rhs.is_atom() {
+        true => rhs.broadcast(1)?,
+        false => rhs,
+    };
+    let count = rhs.count() as i32;
+    Ok(match find(rhs, lhs)? {
+        SuperType::Int(position) => SuperType::from(position.data().0 < count),
+        positions => {
+            let positions = positions.into_items();
+            SuperType::from_items(
+                positions
+                    .into_iter()
+                    .map(|p| SuperType::from(p != SuperType::from(count)))
+                    .collect(),
+            )
+        }
+    })
+}
+
+// Which items are between `lo` and `hi`, a single span of a sorted list
+fn within_typed<T: ListItem>(list: &List<T>, lo: &T, hi: &T) -> Vec<bool> {
+    let data = list.data();
+    if Attribute::has_sorted(list.get_attributes()) {
+        let start = data.partition_point(|x| x.order(lo) == Ordering::Less);
+        let end = data.partition_point(|x| x.order(hi) != Ordering::Greater);
+        let mut mask = vec![false; data.len()];
+        mask[start..end.max(start)].fill(true);
+        return mask;
+    }
+    data.iter()
+        .map(|x| x.order(lo) != Ordering::Less && x.order(hi) != Ordering::Greater)
+        .collect()
+}
+
+/// x within (lo;hi): whether each item of x is at least lo and at most hi.
+pub fn within(lhs: SuperType, rhs: SuperType) -> Result<SuperType, Error> {
+    let (lo, hi) = match rhs.into_items().as_slice() {
+        [lo, hi] => (lo.clone(), hi.clone()),
+        _ => return Err(Error::Length),
+    };
+    if lhs.is_atom() {
+        let bounds = SuperType::MixedList(vec![lo, hi].into());
+        return within(lhs.broadcast(1)?, bounds)?
+            .item(0)
+            .ok_or(Error::Length);
+    }
+
+    let bounds = SuperType::from_items(vec![lo.clone(), hi.clone()]);
+    let mask: Vec<bool> = with_same_type!(
+        &lhs,
+        &bounds,
+        |list, items| within_typed(list, &items[0], &items[1]),
+        {
+            let number = |value: &SuperType| match floats(value) {
+                Some(Operand::Atom(number)) => Ok(TypeFloat(number)),
+                _ => Err(Error::Type),
+            };
+            let (lo, hi) = (number(&lo)?, number(&hi)?);
+            let list = match numbers(&lhs) {
+                Some(values) => List::from(values),
+                None => return Err(Error::Type),
+            };
+            within_typed(&list, &lo, &hi)
+        }
+    );
+    Ok(SuperType::from(mask))
+}
+
+// The position of the last item of the sorted `data` at or below `item`, -1 when there is none
+fn last_at_most<T: ListItem>(data: &[T], item: &T) -> i32 {
+    data.partition_point(|x| x.order(item) != Ordering::Greater) as i32 - 1
+}
+
+/// x bin y: for each y, the position of the last item of the sorted x at or below it.
+pub fn bin(lhs: SuperType, rhs: SuperType) -> Result<SuperType, Error> {
+    if lhs.is_atom() {
+        return Err(Error::Type);
+    }
+    let positions: Vec<i32> = with_same_type!(
+        &lhs,
+        &rhs,
+        |list, items| items
+            .iter()
+            .map(|item| last_at_most(list.data(), item))
+            .collect(),
+        match (numbers(&lhs), numbers(&rhs)) {
+            (Some(data), Some(items)) => {
+                let data: Vec<TypeFloat> = data.into_iter().map(TypeFloat).collect();
+                items
+                    .into_iter()
+                    .map(|item| last_at_most(&data, &TypeFloat(item)))
+                    .collect()
+            }
+            _ => return Err(Error::Type),
+        }
+    );
+    Ok(shaped(&rhs, positions))
+}
+
+// The position of the first item of the sorted `data` at or above `item`, the count when none
+fn first_at_least<T: ListItem>(data: &[T], item: &T) -> i32 {
+    data.partition_point(|x| x.order(item) == Ordering::Less) as i32
+}
+
+/// x binr y: for each y, the position of the first item of the sorted x at or above it.
+pub fn binr(lhs: SuperType, rhs: SuperType) -> Result<SuperType, Error> {
+    if lhs.is_atom() {
+        return Err(Error::Type);
+    }
+    let positions: Vec<i32> = with_same_type!(
+        &lhs,
+        &rhs,
+        |list, items| items
+            .iter()
+            .map(|item| first_at_least(list.data(), item))
+            .collect(),
+        match (numbers(&lhs), numbers(&rhs)) {
+            (Some(data), Some(items)) => {
+                let data: Vec<TypeFloat> = data.into_iter().map(TypeFloat).collect();
+                items
+                    .into_iter()
+                    .map(|item| first_at_least(&data, &TypeFloat(item)))
+                    .collect()
+            }
+            _ => return Err(Error::Type),
+        }
+    );
+    Ok(shaped(&rhs, positions))
+}
+
+/// distinct x: the items of x, or rows of a table, without repeats, in order of first appearance.
+pub fn distinct(value: SuperType) -> Result<SuperType, Error> {
+    if value.is_atom() {
+        return Err(Error::Type);
+    }
+    value.select(&value.grouping().firsts())
+}
+
+/// group x: a dictionary from each distinct item of x to the positions it appears at.
+pub fn group(value: SuperType) -> Result<SuperType, Error> {
+    if value.is_atom() {
+        return Err(Error::Type);
+    }
+    let grouping = value.grouping();
+    let keys = value.select(&grouping.firsts())?;
+    let positions = grouping
+        .into_positions()
+        .into_iter()
+        .map(|positions| {
+            SuperType::from(positions.into_iter().map(|p| p as i32).collect::<Vec<_>>())
+        })
+        .collect();
+    Ok(SuperType::Dictionary(TypeDictionary::new(
+        keys,
+        SuperType::MixedList(TypeMixedList(positions)),
+    )?))
+}
+
+/// The items of an attributed list equal to an atom, found through the attributes.
+/// None when the list has no attribute to look the atom up with.
+pub(crate) fn equal_mask(list: &SuperType, atom: &SuperType) -> Option<SuperType> {
+    if !atom.is_atom() {
+        return None;
+    }
+    with_same_type!(
+        list,
+        atom,
+        |list, items| {
+            let item = &items[0];
+            let positions = match list.index() {
+                Some(index) => index.positions(&item.key()),
+                None if Attribute::has_sorted(list.get_attributes()) => {
+                    equal_range(list.data(), item).collect()
+                }
+                None => return None,
+            };
+            let mut mask = vec![false; list.data().len()];
+            for position in positions {
+                mask[position] = true;
+            }
+            Some(SuperType::from(mask))
+        },
+        None
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::run;
+
+    #[test]
+    fn test_find_with_and_without_attributes() {
+        for prefix in ["", "`s#", "`u#", "`g#", "`p#"] {
+            assert_eq!(
+                run(&format!("({prefix}1 3 5 7)?5 4 1")).unwrap(),
+                SuperType::from(vec![2, 4, 0]),
+                "{prefix}"
+            );
+        }
+        assert_eq!(run("(`g#`a`b`a)?`a").unwrap(), SuperType::from(0));
+        assert_eq!(run("(`a`b!1 2)?2").unwrap(), SuperType::symbol("b"));
+    }
+
+    #[test]
+    fn test_in_and_within() {
+        assert_eq!(
+            run("2 4 in `s#1 2 3").unwrap(),
+            SuperType::from(vec![true, false])
+        );
+        assert_eq!(
+            run("(`s#1 2 3 4 5) within 2 4").unwrap(),
+            SuperType::from(vec![false, true, true, true, false])
+        );
+        assert_eq!(
+            run("1 2 3 within (1.5;3)").unwrap(),
+            SuperType::from(vec![false, true, true])
+        );
+        assert_eq!(run("3 within 1 5").unwrap(), SuperType::from(true));
+    }
+
+    #[test]
+    fn test_bin() {
+        assert_eq!(
+            run("1 3 5 bin 0 1 4 9").unwrap(),
+            SuperType::from(vec![-1, 0, 1, 2])
+        );
+        assert_eq!(run("1 3 5 bin 3.5").unwrap(), SuperType::from(1));
+    }
+
+    #[test]
+    fn test_find_in_mixed_lists() {
+        assert_eq!(
+            run("(1;`a;2.5;`a)?(`a;2.5;3)").unwrap(),
+            SuperType::from(vec![1, 2, 4])
+        );
+        assert_eq!(run("`a`b`c?`c`d").unwrap(), SuperType::from(vec![2, 3]));
+    }
+
+    #[test]
+    fn test_distinct_and_group() {
+        assert_eq!(
+            run("distinct 3 1 3 2 1").unwrap(),
+            SuperType::from(vec![3, 1, 2])
+        );
+        assert_eq!(
+            run("count distinct (1;`a;1;`a;2)").unwrap(),
+            SuperType::from(3)
+        );
+        assert_eq!(
+            run("count distinct ([] a:1 1 2; b:`x`x`y)").unwrap(),
+            SuperType::from(2)
+        );
+        assert_eq!(
+            run("(group `a`b`a)`a").unwrap(),
+            SuperType::from(vec![0, 2])
+        );
+        assert_eq!(run("!group 2 2 1").unwrap(), SuperType::from(vec![2, 1]));
+    }
+
+    #[test]
+    fn test_binr() {
+        assert_eq!(
+            run("1 3 5 binr 0 1 4 9").unwrap(),
+            SuperType::from(vec![0, 0, 2, 3])
+        );
+    }
+
+    #[test]
+    fn test_equality_uses_attributes() {
+        for prefix in ["`s#", "`g#", "`p#"] {
+            assert_eq!(
+                run(&format!("({prefix}1 1 2 3)=1")).unwrap(),
+                SuperType::from(vec![true, true, false, false])
+            );
+        }
+        assert_eq!(
+            run("1=`u#3 1 2").unwrap(),
+            SuperType::from(vec![false, true, false])
+        );
+    }
+}
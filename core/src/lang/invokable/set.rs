@@ -0,0 +1,143 @@
+use std::collections::HashSet;
+
+use crate::{
+    Error,
+    lang::{
+        SuperType,
+        invokable::{list, search},
+        type_system::{Key, TypeMixedList, TypeTable},
+    },
+};
+
+// Atoms take part in set operations as single item lists
+fn as_list(value: SuperType) -> Result<SuperType, Error> {
+    match value.is_atom() {
+        true => value.broadcast(1),
+        false => Ok(value),
+    }
+}
+
+// The items of `lhs` that are, or are not, items of `rhs`, repeats included
+fn keep(lhs: SuperType, rhs: SuperType, member: bool) -> Result<SuperType, Error> {
+    let (lhs, rhs) = (as_list(lhs)?, as_list(rhs)?);
+    let members: HashSet<Key> = rhs.item_keys().into_iter().collect();
+    let rows: Vec<usize> = lhs
+        .item_keys()
+        .iter()
+        .enumerate()
+        .filter(|(_, key)| members.contains(key) == member)
+        .map(|(i, _)| i)
+        .collect();
+    lhs.select(&rows)
+}
+
+/// x union y: the distinct items of x then those of y.
+pub fn union(lhs: SuperType, rhs: SuperType) -> Result<SuperType, Error> {
+    search::distinct(list::join(as_list(lhs)?, as_list(rhs)?)?)
+}
+
+/// x inter y: the items of x that are also items of y.
+pub fn inter(lhs: SuperType, rhs: SuperType) -> Result<SuperType, Error> {
+    keep(lhs, rhs, true)
+}
+
+/// x except y: the items of x that are not items of y.
+pub fn except(lhs: SuperType, rhs: SuperType) -> Result<SuperType, Error> {
+    keep(lhs, rhs, false)
+}
+
+/// x cross y: every item of x paired with every item of y, the rows of both for tables.
+pub fn cross(lhs: SuperType, rhs: SuperType) -> Result<SuperType, Error> {
+    let (lhs, rhs) = (as_list(lhs)?, as_list(rhs)?);
+    let (left, right) = (lhs.count(), rhs.count());
+    let left_rows: Vec<usize> = (0..left * right).map(|i| i / right).collect();
+    let right_rows: Vec<usize> = (0..left * right).map(|i| i % right).collect();
+
+    if let (SuperType::Table(l), SuperType::Table(r)) = (&lhs, &rhs) {
+        let (mut columns, mut data) = l.select_rows(&left_rows)?.into_parts();
+        let (right_columns, right_data) = r.select_rows(&right_rows)?.into_parts();
+        columns.extend(right_columns);
+        data.extend(right_data);
+        return Ok(SuperType::Table(TypeTable::new(columns, data)?));
+    }
+    if matches!(lhs, SuperType::Table(_) | SuperType::Dictionary(_))
+        || matches!(rhs, SuperType::Table(_) | SuperType::Dictionary(_))
+    {
+        return Err(Error::Type);
+    }
+
+    let pairs = left_rows
+        .into_iter()
+        .zip(right_rows)
+        .map(|(l, r)| {
+            let pair = vec![
+                lhs.item(l).ok_or(Error::Length)?,
+                rhs.item(r).ok_or(Error::Length)?,
+            ];
+            Ok(SuperType::from_items(pair))
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    Ok(SuperType::MixedList(TypeMixedList(pairs)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::run;
+
+    #[test]
+    fn test_union_inter_except_keep_type() {
+        assert_eq!(
+            run("1 2 2 3 union 3 4").unwrap(),
+            SuperType::from(vec![1, 2, 3, 4])
+        );
+        assert_eq!(
+            run("1 2 2 3 inter 2 3 5").unwrap(),
+            SuperType::from(vec![2, 2, 3])
+        );
+        assert_eq!(
+            run("1 2 2 3 except 2").unwrap(),
+            SuperType::from(vec![1, 3])
+        );
+        assert_eq!(
+            run("`a`b except `b").unwrap(),
+            SuperType::symbols(vec!["a".to_owned()])
+        );
+        // 2 and 2.0 are different items
+        assert_eq!(
+            run("(1;`a;2) inter (`a;2.0)").unwrap(),
+            SuperType::MixedList(TypeMixedList(vec![SuperType::symbol("a")]))
+        );
+    }
+
+    #[test]
+    fn test_set_operations_on_tables() {
+        let program = "t:([] a:1 2 3); s:([] a:3 4)";
+        assert_eq!(
+            run(&format!("{program}; exec a from t union s")).unwrap(),
+            SuperType::from(vec![1, 2, 3, 4])
+        );
+        assert_eq!(
+            run(&format!("{program}; exec a from t except s")).unwrap(),
+            SuperType::from(vec![1, 2])
+        );
+    }
+
+    #[test]
+    fn test_cross() {
+        assert_eq!(run("count 1 2 3 cross `a`b").unwrap(), SuperType::from(6));
+        assert_eq!(
+            run("(1 2 cross 3 4) 1").unwrap(),
+            SuperType::from(vec![1, 4])
+        );
+        assert_eq!(
+            run("exec b from ([] a:1 2) cross ([] b:`x`y)").unwrap(),
+            SuperType::symbols(vec![
+                "x".to_owned(),
+                "y".to_owned(),
+                "x".to_owned(),
+                "y".to_owned()
+            ])
+        );
+    }
+}
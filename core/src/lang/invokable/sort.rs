@@ -0,0 +1,259 @@
+use std::cmp::Ordering;
+
+use crate::{
+    Error,
+    lang::{
+        SuperType,
+        invokable::list,
+        type_system::{Attribute, ListItem, TypeDictionary, TypeTable, TypeTrait, with_list},
+    },
+};
+
+/// q's ordering of any two items: by type first, then by value, lists item by item.
+pub(crate) fn compare(lhs: &SuperType, rhs: &SuperType) -> Ordering {
+    lhs.type_id()
+        .cmp(&rhs.type_id())
+        .then_with(|| match (lhs, rhs) {
+            (SuperType::Bool(l), SuperType::Bool(r)) => l.data().order(r.data()),
+            (SuperType::Byte(l), SuperType::Byte(r)) => l.data().order(r.data()),
+            (SuperType::Int(l), SuperType::Int(r)) => l.data().order(r.data()),
+            (SuperType::Float(l), SuperType::Float(r)) => l.data().order(r.data()),
+            (SuperType::Char(l), SuperType::Char(r)) => l.data().order(r.data()),
+            (SuperType::Symbol(l), SuperType::Symbol(r)) => l.data().order(r.data()),
+            (SuperType::Date(l), SuperType::Date(r)) => l.data().order(r.data()),
+            (l, r) if !l.is_atom() => {
+                let ordering = (0..l.count().min(r.count()))
+                    .map(|i| compare(&l.item(i).unwrap(), &r.item(i).unwrap()))
+                    .find(|o| o.is_ne());
+                ordering.unwrap_or_else(|| l.count().cmp(&r.count()))
+            }
+            _ => Ordering::Equal,
+        })
+}
+
+// Compares two items of a column given their positions
+type Comparator<'a> = Box<dyn Fn(usize, usize) -> Ordering + 'a>;
+
+fn comparator(column: &SuperType) -> Comparator<'_> {
+    with_list!(column, list => {
+        let data = list.data();
+        Box::new(move |a, b| data[a].order(&data[b]))
+    }, other => match other {
+        SuperType::Bools(bits) => Box::new(move |a, b| bits.get(a).cmp(&bits.get(b))),
+        SuperType::MixedList(list) => Box::new(move |a, b| compare(&list.0[a], &list.0[b])),
+        other => Box::new(move |a, b| compare(&other.item(a).unwrap(), &other.item(b).unwrap())),
+    })
+}
+
+// The positions of the rows in order of the columns, the first column first.
+// Equal rows keep their order, also when descending.
+fn grade(columns: &[&SuperType], count: usize, descending: bool) -> Vec<usize> {
+    let comparators: Vec<Comparator> = columns.iter().map(|c| comparator(c)).collect();
+    let mut rows: Vec<usize> = (0..count).collect();
+    rows.sort_by(|&a, &b| {
+        let ordering = comparators
+            .iter()
+            .map(|compare| compare(a, b))
+            .find(|o| o.is_ne())
+            .unwrap_or(Ordering::Equal);
+        match descending {
+            true => ordering.reverse(),
+            false => ordering,
+        }
+    });
+    rows
+}
+
+fn is_sorted(value: &SuperType) -> bool {
+    with_list!(value, list => Attribute::has_sorted(list.get_attributes()), other => match other {
+        SuperType::Bools(bits) => Attribute::has_sorted(bits.get_attributes()),
+        _ => false,
+    })
+}
+
+// The positions of the items of a list or the rows of a table in order
+fn grade_of(value: &SuperType, descending: bool) -> Result<Vec<usize>, Error> {
+    match value {
+        SuperType::Table(table) => {
+            let columns: Vec<&SuperType> = table.data().iter().collect();
+            Ok(grade(&columns, table.row_count(), descending))
+        }
+        SuperType::Dictionary(dictionary) => grade_of(dictionary.values(), descending),
+        atom if atom.is_atom() => Err(Error::Type),
+        list if is_sorted(list) && !descending => Ok((0..list.count()).collect()),
+        list => Ok(grade(&[list], list.count(), descending)),
+    }
+}
+
+fn positions(rows: Vec<usize>) -> SuperType {
+    SuperType::from(rows.into_iter().map(|i| i as i32).collect::<Vec<_>>())
+}
+
+/// iasc x: the positions of the items of x in ascending order, the keys for a dictionary.
+pub fn iasc(value: SuperType) -> Result<SuperType, Error> {
+    let rows = grade_of(&value, false)?;
+    match value {
+        SuperType::Dictionary(dictionary) => dictionary.keys().select(&rows),
+        _ => Ok(positions(rows)),
+    }
+}
+
+/// idesc x: the positions of the items of x in descending order, the keys for a dictionary.
+pub fn idesc(value: SuperType) -> Result<SuperType, Error> {
+    let rows = grade_of(&value, true)?;
+    match value {
+        SuperType::Dictionary(dictionary) => dictionary.keys().select(&rows),
+        _ => Ok(positions(rows)),
+    }
+}
+
+/// rank x: the position each item of x would have once sorted.
+pub fn rank(value: SuperType) -> Result<SuperType, Error> {
+    let rows = grade_of(&value, false)?;
+    let mut ranks = vec![0; rows.len()];
+    for (rank, row) in rows.into_iter().enumerate() {
+        ranks[row] = rank as i32;
+    }
+    Ok(SuperType::from(ranks))
+}
+
+fn sort(value: SuperType, descending: bool) -> Result<SuperType, Error> {
+    if is_sorted(&value) && !descending {
+        return Ok(value);
+    }
+    let rows = grade_of(&value, descending)?;
+    match value {
+        SuperType::Dictionary(dictionary) => {
+            let (keys, values) = dictionary.into_parts();
+            Ok(SuperType::Dictionary(TypeDictionary::new(
+                keys.select(&rows)?,
+                values.select(&rows)?,
+            )?))
+        }
+        value => value.select(&rows),
+    }
+}
+
+/// asc x: the items of x in ascending order, a dictionary is sorted on its values.
+pub fn asc(value: SuperType) -> Result<SuperType, Error> {
+    sort(value, false)
+}
+
+/// desc x: the items of x in descending order, a dictionary is sorted on its values.
+pub fn desc(value: SuperType) -> Result<SuperType, Error> {
+    sort(value, true)
+}
+
+fn sort_table(names: SuperType, table: SuperType, descending: bool) -> Result<SuperType, Error> {
+    let names: Vec<String> = match names {
+        SuperType::Symbol(name) => vec![name.data().0.clone()],
+        SuperType::Symbols(names) => names.data().iter().map(|n| n.0.clone()).collect(),
+        _ => return Err(Error::Type),
+    };
+    let (table, keys) = match table {
+        SuperType::Table(table) => (table, 0),
+        SuperType::Dictionary(keyed) if keyed.is_keyed_table() => {
+            let keys = match keyed.keys() {
+                SuperType::Table(keys) => keys.columns().len(),
+                _ => unreachable!(),
+            };
+            (list::unkey(keyed)?, keys)
+        }
+        _ => return Err(Error::Type),
+    };
+
+    let columns = names
+        .iter()
+        .map(|name| table.column(name).ok_or(Error::Name(name.clone())))
+        .collect::<Result<Vec<_>, _>>()?;
+    let rows = grade(&columns, table.row_count(), descending);
+    let (columns, mut data) = table.select_rows(&rows)?.into_parts();
+
+    // the rows are now sorted on the first column
+    if !descending && let Some(first) = columns.iter().position(|c| *c == names[0]) {
+        with_list!(&mut data[first], list => list.assume_sorted(), other => {
+            if let SuperType::Bools(bits) = other {
+                bits.assume_sorted()
+            }
+        });
+    }
+    let table = TypeTable::new(columns, data)?;
+    match keys {
+        0 => Ok(SuperType::Table(table)),
+        n => list::dict(SuperType::from(n as i32), SuperType::Table(table)),
+    }
+}
+
+/// c xasc t: the rows of t in ascending order of the columns c, the first column first.
+pub fn xasc(names: SuperType, table: SuperType) -> Result<SuperType, Error> {
+    sort_table(names, table, false)
+}
+
+/// c xdesc t: the rows of t in descending order of the columns c, the first column first.
+pub fn xdesc(names: SuperType, table: SuperType) -> Result<SuperType, Error> {
+    sort_table(names, table, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::run;
+
+    #[test]
+    fn test_grades_are_stable() {
+        assert_eq!(
+            run("iasc 3 1 2 1").unwrap(),
+            SuperType::from(vec![1, 3, 2, 0])
+        );
+        assert_eq!(
+            run("idesc 3 1 2 1").unwrap(),
+            SuperType::from(vec![0, 2, 1, 3])
+        );
+        assert_eq!(
+            run("rank 30 10 20").unwrap(),
+            SuperType::from(vec![2, 0, 1])
+        );
+        assert_eq!(
+            run("asc `c`a`b").unwrap(),
+            SuperType::symbols(vec!["a".to_owned(), "b".to_owned(), "c".to_owned()])
+        );
+        assert_eq!(run("attr asc 3 1 2").unwrap(), SuperType::symbol("s"));
+        assert_eq!(
+            run("desc 1.5 0.5 3").unwrap(),
+            SuperType::from(vec![3.0, 1.5, 0.5])
+        );
+    }
+
+    #[test]
+    fn test_mixed_lists_sort_by_type_then_value() {
+        assert_eq!(
+            run("asc (2;`b;1;`a)").unwrap(),
+            SuperType::MixedList(
+                vec![
+                    SuperType::symbol("a"),
+                    SuperType::symbol("b"),
+                    SuperType::from(1),
+                    SuperType::from(2),
+                ]
+                .into()
+            )
+        );
+    }
+
+    #[test]
+    fn test_xasc_sorts_on_several_columns() {
+        let program = "t:([] sym:`b`a`b`a; px:1 2 0 2; n:til 4)";
+        assert_eq!(
+            run(&format!("{program}; exec n from `sym`px xasc t")).unwrap(),
+            SuperType::from(vec![1, 3, 2, 0])
+        );
+        assert_eq!(
+            run(&format!("{program}; exec n from `px xdesc t")).unwrap(),
+            SuperType::from(vec![1, 3, 0, 2])
+        );
+        assert_eq!(
+            run(&format!("{program}; s:`sym xasc t; attr s`sym")).unwrap(),
+            SuperType::symbol("s")
+        );
+    }
+}
@@ -0,0 +1,203 @@
+use crate::{
+    Error,
+    lang::{
+        SuperType,
+        invokable::aggregate::{self, Monadic, to_floats},
+    },
+};
+
+// The items of x that are not null, as floats. Mixed lists have no single numeric type.
+fn present(value: &SuperType) -> Result<Vec<f64>, Error> {
+    if let SuperType::MixedList(_) = value {
+        return Err(Error::Type);
+    }
+    Ok(to_floats(value)?
+        .into_iter()
+        .filter(|v| !v.is_nan())
+        .collect())
+}
+
+// The items of x and y side by side where neither is null, an atom going with every item
+fn pairs(lhs: &SuperType, rhs: &SuperType) -> Result<Vec<(f64, f64)>, Error> {
+    if matches!(lhs, SuperType::MixedList(_)) || matches!(rhs, SuperType::MixedList(_)) {
+        return Err(Error::Type);
+    }
+    let (mut l, mut r) = (to_floats(lhs)?, to_floats(rhs)?);
+    if lhs.is_atom() {
+        l = vec![l[0]; r.len()];
+    }
+    if rhs.is_atom() {
+        r = vec![r[0]; l.len()];
+    }
+    if l.len() != r.len() {
+        return Err(Error::Length);
+    }
+    Ok(l.into_iter()
+        .zip(r)
+        .filter(|(l, r)| !l.is_nan() && !r.is_nan())
+        .collect())
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+// The sum of squared deviations from the mean over n, or n-1 for a sample
+fn variance(values: &[f64], sample: bool) -> f64 {
+    let mean = mean(values);
+    let squares: f64 = values.iter().map(|v| (v - mean) * (v - mean)).sum();
+    match sample {
+        true => squares / (values.len() as f64 - 1.0),
+        false => squares / values.len() as f64,
+    }
+}
+
+fn statistic(value: SuperType, this: Monadic, f: fn(&[f64]) -> f64) -> Result<SuperType, Error> {
+    if let Some(result) = aggregate::columns(&value, this) {
+        return result;
+    }
+    Ok(SuperType::from(f(&present(&value)?)))
+}
+
+/// var x: the population variance of x, nulls left out.
+pub fn var(value: SuperType) -> Result<SuperType, Error> {
+    statistic(value, var, |v| variance(v, false))
+}
+
+/// dev x: the population standard deviation of x, nulls left out.
+pub fn dev(value: SuperType) -> Result<SuperType, Error> {
+    statistic(value, dev, |v| variance(v, false).sqrt())
+}
+
+/// svar x: the sample variance of x, nulls left out.
+pub fn svar(value: SuperType) -> Result<SuperType, Error> {
+    statistic(value, svar, |v| variance(v, true))
+}
+
+/// sdev x: the sample standard deviation of x, nulls left out.
+pub fn sdev(value: SuperType) -> Result<SuperType, Error> {
+    statistic(value, sdev, |v| variance(v, true).sqrt())
+}
+
+fn covariance(pairs: &[(f64, f64)]) -> f64 {
+    let n = pairs.len() as f64;
+    let (mean_l, mean_r) = (
+        pairs.iter().map(|p| p.0).sum::<f64>() / n,
+        pairs.iter().map(|p| p.1).sum::<f64>() / n,
+    );
+    pairs
+        .iter()
+        .map(|(l, r)| (l - mean_l) * (r - mean_r))
+        .sum::<f64>()
+        / n
+}
+
+/// x cov y: the population covariance of x and y, pairs with a null left out.
+pub fn cov(lhs: SuperType, rhs: SuperType) -> Result<SuperType, Error> {
+    Ok(SuperType::from(covariance(&pairs(&lhs, &rhs)?)))
+}
+
+/// x cor y: the correlation of x and y, pairs with a null left out.
+pub fn cor(lhs: SuperType, rhs: SuperType) -> Result<SuperType, Error> {
+    let pairs = pairs(&lhs, &rhs)?;
+    let l: Vec<f64> = pairs.iter().map(|p| p.0).collect();
+    let r: Vec<f64> = pairs.iter().map(|p| p.1).collect();
+    let deviations = (variance(&l, false) * variance(&r, false)).sqrt();
+    Ok(SuperType::from(covariance(&pairs) / deviations))
+}
+
+/// w wsum x: the sum of x weighted by w, pairs with a null left out.
+pub fn wsum(weights: SuperType, value: SuperType) -> Result<SuperType, Error> {
+    let pairs = pairs(&weights, &value)?;
+    Ok(SuperType::from(
+        pairs.iter().map(|(w, v)| w * v).sum::<f64>(),
+    ))
+}
+
+/// w wavg x: the mean of x weighted by w, pairs with a null left out.
+pub fn wavg(weights: SuperType, value: SuperType) -> Result<SuperType, Error> {
+    let pairs = pairs(&weights, &value)?;
+    let total: f64 = pairs.iter().map(|(w, v)| w * v).sum();
+    let weight: f64 = pairs.iter().map(|(w, _)| w).sum();
+    Ok(SuperType::from(total / weight))
+}
+
+/// p percentile x: the p-th quantiles of x for p between 0 and 1, interpolating linearly
+/// between the items either side, nulls left out.
+pub fn percentile(p: SuperType, value: SuperType) -> Result<SuperType, Error> {
+    let mut values = present(&value)?;
+    values.sort_by(f64::total_cmp);
+    let quantile = |p: f64| -> Result<f64, Error> {
+        if !(0.0..=1.0).contains(&p) {
+            return Err(Error::Domain);
+        }
+        if values.is_empty() {
+            return Ok(f64::NAN);
+        }
+        let rank = p * (values.len() - 1) as f64;
+        let (below, above) = (rank.floor() as usize, rank.ceil() as usize);
+        Ok(values[below] + (rank - rank.floor()) * (values[above] - values[below]))
+    };
+    let quantiles = to_floats(&p)?
+        .into_iter()
+        .map(quantile)
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(match p.is_atom() {
+        true => SuperType::from(quantiles[0]),
+        false => SuperType::from(quantiles),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{lang::invokable::math::INT_NULL, testing::run};
+
+    #[test]
+    fn test_variances_promote_and_skip_nulls() {
+        let ints = SuperType::from(vec![2, 4, INT_NULL, 4, 4, 5, 5, 7, 9]);
+        assert_eq!(var(ints.clone()), Ok(SuperType::from(4.0)));
+        assert_eq!(dev(ints.clone()), Ok(SuperType::from(2.0)));
+        assert_eq!(svar(ints), Ok(SuperType::from(32.0 / 7.0)));
+        assert_eq!(run("sdev 1 3").unwrap(), SuperType::from(2f64.sqrt()));
+        assert_eq!(run("var 1b").unwrap(), SuperType::from(0.0));
+    }
+
+    #[test]
+    fn test_pairwise_statistics() {
+        assert_eq!(run("1 2 3 cov 2 4 6").unwrap(), SuperType::from(4.0 / 3.0));
+        assert_eq!(run("1 2 3 cor 3 2 1").unwrap(), SuperType::from(-1.0));
+        assert_eq!(run("1 2 wsum 3 4").unwrap(), SuperType::from(11.0));
+        assert_eq!(run("1 3 wavg 1 5").unwrap(), SuperType::from(4.0));
+        assert_eq!(run("1 2 cov 1 2 3"), Err(Error::Length));
+    }
+
+    #[test]
+    fn test_percentiles() {
+        assert_eq!(run("0.5 percentile 4 1 3 2").unwrap(), SuperType::from(2.5));
+        assert_eq!(
+            run("0 0.25 1 percentile 1 2 3 4 5").unwrap(),
+            SuperType::from(vec![1.0, 2.0, 5.0])
+        );
+        assert_eq!(run("2 percentile 1 2"), Err(Error::Domain));
+    }
+
+    #[test]
+    fn test_statistics_in_select_by() {
+        let table = "t:([] sym:`a`b`a`b; px:1 2 3 6; size:1 1 3 1)";
+        assert_eq!(
+            run(&format!(
+                "{table}; exec v from select v:var px by sym from t"
+            ))
+            .unwrap(),
+            SuperType::from(vec![1.0, 4.0])
+        );
+        assert_eq!(
+            run(&format!(
+                "{table}; exec w from select w:size wavg px by sym from t"
+            ))
+            .unwrap(),
+            SuperType::from(vec![2.5, 4.0])
+        );
+    }
+}
@@ -0,0 +1,505 @@
+use crate::{
+    Error,
+    lang::{
+        SuperType,
+        invokable::{
+            Invokable,
+            comparison::{Operand, ints},
+            math::{INT_NULL, each},
+            operator,
+        },
+        type_system::{Atom, List, TypeChar, TypeMixedList, TypeSymbol},
+    },
+};
+
+// The bytes of a string or a char
+pub(crate) fn bytes(value: &SuperType) -> Option<Vec<u8>> {
+    match value {
+        SuperType::Char(c) => Some(vec![c.data().0]),
+        SuperType::Chars(c) => Some(c.bytes()),
+        _ => None,
+    }
+}
+
+pub(crate) fn string(bytes: &[u8]) -> SuperType {
+    SuperType::Chars(List::new(bytes.iter().copied().map(TypeChar).collect()))
+}
+
+// Applies `f` to chars, strings and symbols, keeping their shape. Text that is UTF-8 goes
+// through `text` so that letters outside ASCII change too, other bytes through `byte`.
+fn map_chars(
+    value: SuperType,
+    this: fn(SuperType) -> Result<SuperType, Error>,
+    byte: fn(&u8) -> u8,
+    text: fn(&str) -> String,
+) -> Result<SuperType, Error> {
+    if let Some(result) = each(&value, this) {
+        return result;
+    }
+    let symbol = |s: &TypeSymbol| TypeSymbol(text(&s.0));
+    Ok(match value {
+        SuperType::Char(c) => SuperType::char(byte(&c.data().0)),
+        SuperType::Chars(c) => match std::str::from_utf8(&c.bytes()) {
+            Ok(utf8) => string(text(utf8).as_bytes()),
+            Err(_) => string(&c.bytes().iter().map(byte).collect::<Vec<_>>()),
+        },
+        SuperType::Symbol(s) => SuperType::from(Atom::new(symbol(s.data()))),
+        SuperType::Symbols(s) => SuperType::from(List::new(s.data().iter().map(symbol).collect())),
+        _ => return Err(Error::Type),
+    })
+}
+
+/// upper x: x in uppercase, for chars, strings and symbols.
+pub fn upper(value: SuperType) -> Result<SuperType, Error> {
+    map_chars(value, upper, u8::to_ascii_uppercase, str::to_uppercase)
+}
+
+/// lower x: x in lowercase, for chars, strings and symbols.
+pub fn lower(value: SuperType) -> Result<SuperType, Error> {
+    map_chars(value, lower, u8::to_ascii_lowercase, str::to_lowercase)
+}
+
+// Applies `f` to strings, chars being left as they are
+fn map_strings(
+    value: SuperType,
+    this: fn(SuperType) -> Result<SuperType, Error>,
+    f: fn(&[u8]) -> &[u8],
+) -> Result<SuperType, Error> {
+    if let Some(result) = each(&value, this) {
+        return result;
+    }
+    match value {
+        SuperType::Chars(c) => Ok(string(f(&c.bytes()))),
+        char @ SuperType::Char(_) => Ok(char),
+        _ => Err(Error::Type),
+    }
+}
+
+/// trim x: x without leading or trailing whitespace.
+pub fn trim(value: SuperType) -> Result<SuperType, Error> {
+    map_strings(value, trim, <[u8]>::trim_ascii)
+}
+
+/// ltrim x: x without leading whitespace.
+pub fn ltrim(value: SuperType) -> Result<SuperType, Error> {
+    map_strings(value, ltrim, <[u8]>::trim_ascii_start)
+}
+
+/// rtrim x: x without trailing whitespace.
+pub fn rtrim(value: SuperType) -> Result<SuperType, Error> {
+    map_strings(value, rtrim, <[u8]>::trim_ascii_end)
+}
+
+// An element of a pattern: `*` any run of chars, `?` any char, `[..]` a char of a set
+#[derive(Debug, PartialEq)]
+enum Glob {
+    Any,
+    One,
+    Set(bool, Vec<(u8, u8)>), // negated, inclusive ranges
+    Char(u8),
+}
+
+impl Glob {
+    fn matches(&self, c: u8) -> bool {
+        match self {
+            Glob::Any | Glob::One => true,
+            Glob::Set(negated, ranges) => {
+                ranges.iter().any(|(low, high)| (*low..=*high).contains(&c)) != *negated
+            }
+            Glob::Char(expected) => *expected == c,
+        }
+    }
+}
+
+// An unclosed `[` stands for itself
+fn glob(pattern: &[u8]) -> Vec<Glob> {
+    let mut globs = Vec::new();
+    let mut i = 0;
+    while i < pattern.len() {
+        let glob = match pattern[i] {
+            b'*' => Glob::Any,
+            b'?' => Glob::One,
+            b'[' if let Some(end) = pattern[i + 1..].iter().skip(1).position(|&c| c == b']') => {
+                // a `]` right after the `[` is part of the set
+                let set = &pattern[i + 1..i + 2 + end];
+                i += end + 2;
+                let (negated, set) = match set {
+                    [b'^', rest @ ..] if !rest.is_empty() => (true, rest),
+                    set => (false, set),
+                };
+                let mut ranges = Vec::new();
+                let mut j = 0;
+                while j < set.len() {
+                    match set.get(j + 1..j + 3) {
+                        Some([b'-', high]) => {
+                            ranges.push((set[j], *high));
+                            j += 3;
+                        }
+                        _ => {
+                            ranges.push((set[j], set[j]));
+                            j += 1;
+                        }
+                    }
+                }
+                Glob::Set(negated, ranges)
+            }
+            c => Glob::Char(c),
+        };
+        globs.push(glob);
+        i += 1;
+    }
+    globs
+}
+
+// Whether the whole of `text` matches the pattern
+fn glob_match(globs: &[Glob], text: &[u8]) -> bool {
+    // matched[j]: whether the globs so far match the first j chars
+    let mut matched = vec![false; text.len() + 1];
+    matched[0] = true;
+    for glob in globs {
+        match glob {
+            Glob::Any => {
+                for j in 1..=text.len() {
+                    matched[j] |= matched[j - 1];
+                }
+            }
+            glob => {
+                for j in (1..=text.len()).rev() {
+                    matched[j] = matched[j - 1] && glob.matches(text[j - 1]);
+                }
+                matched[0] = false;
+            }
+        }
+    }
+    matched[text.len()]
+}
+
+/// x like p: whether the strings or symbols x match the pattern p, where `*` matches any run of
+/// chars, `?` any char and `[..]` any char of a set, e.g. `[a-z]` or `[^0-9]`.
+pub fn like(value: SuperType, pattern: SuperType) -> Result<SuperType, Error> {
+    let globs = glob(&bytes(&pattern).ok_or(Error::Type)?);
+    let matches = |s: &[u8]| glob_match(&globs, s);
+    match &value {
+        SuperType::Char(_) | SuperType::Chars(_) => {
+            Ok(SuperType::from(matches(&bytes(&value).unwrap_or_default())))
+        }
+        SuperType::Symbol(s) => Ok(SuperType::from(matches(s.data().0.as_bytes()))),
+        SuperType::Symbols(s) => Ok(SuperType::from(
+            s.data()
+                .iter()
+                .map(|s| matches(s.0.as_bytes()))
+                .collect::<Vec<_>>(),
+        )),
+        _ => each(&value, |item| like(item, pattern.clone())).unwrap_or(Err(Error::Type)),
+    }
+}
+
+// The start of each occurrence of the pattern in `text`, occurrences not overlapping. The
+// pattern matches a fixed number of chars, `*` standing for itself.
+fn occurrences(text: &[u8], pattern: &[u8]) -> (Vec<usize>, usize) {
+    let globs: Vec<Glob> = glob(pattern)
+        .into_iter()
+        .map(|glob| match glob {
+            Glob::Any => Glob::Char(b'*'),
+            glob => glob,
+        })
+        .collect();
+    let mut starts = Vec::new();
+    let mut i = 0;
+    while !globs.is_empty() && i + globs.len() <= text.len() {
+        match globs.iter().zip(&text[i..]).all(|(g, &c)| g.matches(c)) {
+            true => {
+                starts.push(i);
+                i += globs.len();
+            }
+            false => i += 1,
+        }
+    }
+    (starts, globs.len())
+}
+
+/// s ss p: the positions of the occurrences of p in the string s, `?` and `[..]` matching as
+/// they do in like.
+pub fn ss(value: SuperType, pattern: SuperType) -> Result<SuperType, Error> {
+    if let Some(result) = each(&value, |item| ss(item, pattern.clone())) {
+        return result;
+    }
+    let pattern = bytes(&pattern).ok_or(Error::Type)?;
+    let text = bytes(&value).ok_or(Error::Type)?;
+    let (starts, _) = occurrences(&text, &pattern);
+    Ok(SuperType::from(
+        starts.into_iter().map(|i| i as i32).collect::<Vec<_>>(),
+    ))
+}
+
+/// ssr[s;p;r]: s with each occurrence of p replaced by the string r, or by r applied to the
+/// occurrence when r is a function.
+pub fn ssr(
+    value: SuperType,
+    pattern: SuperType,
+    replacement: SuperType,
+) -> Result<SuperType, Error> {
+    if let Some(result) = each(&value, |item| {
+        ssr(item, pattern.clone(), replacement.clone())
+    }) {
+        return result;
+    }
+    let pattern = bytes(&pattern).ok_or(Error::Type)?;
+    let text = bytes(&value).ok_or(Error::Type)?;
+    let (starts, length) = occurrences(&text, &pattern);
+    let mut result = Vec::new();
+    let mut copied = 0;
+    for start in starts {
+        result.extend_from_slice(&text[copied..start]);
+        let replaced = match &replacement {
+            SuperType::Char(_) | SuperType::Chars(_) => replacement.clone(),
+            function => function.invoke_1(string(&text[start..start + length]))?,
+        };
+        result.extend(bytes(&replaced).ok_or(Error::Type)?);
+        copied = start + length;
+    }
+    result.extend_from_slice(&text[copied..]);
+    Ok(string(&result))
+}
+
+// The digits of `value` in the mixed radix `bases`, the most significant first
+fn encode(bases: &[i32], mut value: i32) -> Vec<i32> {
+    let mut digits: Vec<i32> = bases
+        .iter()
+        .rev()
+        .map(|&base| {
+            let digit = value.rem_euclid(base);
+            value = value.div_euclid(base);
+            digit
+        })
+        .collect();
+    digits.reverse();
+    digits
+}
+
+// The digits of `value` in `base`, as many as it takes
+fn encode_all(base: i32, value: i32) -> Result<Vec<i32>, Error> {
+    if value < 0 {
+        return Err(Error::Domain);
+    }
+    let mut digits = Vec::new();
+    let mut value = value;
+    while value > 0 || digits.is_empty() {
+        digits.push(value % base);
+        value /= base;
+    }
+    digits.reverse();
+    Ok(digits)
+}
+
+// The rows of a matrix of ints, a vector giving a single row
+fn rows(value: &SuperType) -> Option<Vec<Vec<i32>>> {
+    match value {
+        SuperType::MixedList(rows) => rows
+            .0
+            .iter()
+            .map(|row| match ints(row)? {
+                Operand::List(row) => Some(row),
+                Operand::Atom(_) => None,
+            })
+            .collect(),
+        _ => None,
+    }
+}
+
+fn columns(rows: Vec<Vec<i32>>) -> SuperType {
+    SuperType::from_items(rows.into_iter().map(SuperType::from).collect())
+}
+
+/// d vs s: the string s split at each occurrence of the char or string d. With ints, b vs x
+/// gives the digits of x in base b, or in the mixed radix b when b is a list.
+pub fn vs(delimiter: SuperType, value: SuperType) -> Result<SuperType, Error> {
+    if let (Some(delimiter), Some(text)) = (bytes(&delimiter), bytes(&value)) {
+        if delimiter.is_empty() {
+            return Err(Error::Domain);
+        }
+        let mut pieces = Vec::new();
+        let mut start = 0;
+        let mut i = 0;
+        while i + delimiter.len() <= text.len() {
+            match text[i..].starts_with(&delimiter) {
+                true => {
+                    pieces.push(string(&text[start..i]));
+                    i += delimiter.len();
+                    start = i;
+                }
+                false => i += 1,
+            }
+        }
+        pieces.push(string(&text[start..]));
+        return Ok(SuperType::MixedList(TypeMixedList(pieces)));
+    }
+    let (Some(bases), Some(values)) = (ints(&delimiter), ints(&value)) else {
+        return Err(Error::Type);
+    };
+    let digits: Vec<Vec<i32>> = match bases {
+        Operand::Atom(base) if base < 2 => return Err(Error::Domain),
+        Operand::Atom(base) => {
+            let values = match values {
+                Operand::Atom(value) => return Ok(SuperType::from(encode_all(base, value)?)),
+                Operand::List(values) => values,
+            };
+            let digits = values
+                .into_iter()
+                .map(|v| encode_all(base, v))
+                .collect::<Result<Vec<_>, _>>()?;
+            let width = digits.iter().map(Vec::len).max().unwrap_or(0);
+            digits
+                .into_iter()
+                .map(|d| [vec![0; width - d.len()], d].concat())
+                .collect()
+        }
+        Operand::List(bases) if bases.iter().any(|&b| b < 1) => return Err(Error::Domain),
+        Operand::List(bases) => match values {
+            Operand::Atom(value) => return Ok(SuperType::from(encode(&bases, value))),
+            Operand::List(values) => values.into_iter().map(|v| encode(&bases, v)).collect(),
+        },
+    };
+    // a row per digit, a column per value
+    let width = digits.first().map_or(0, Vec::len);
+    Ok(columns(
+        (0..width)
+            .map(|i| digits.iter().map(|d| d[i]).collect())
+            .collect(),
+    ))
+}
+
+// The value of `digits` in the mixed radix `bases`
+fn decode(bases: impl Iterator<Item = i32>, digits: &[i32]) -> Result<i32, Error> {
+    bases.zip(digits).try_fold(0i32, |value, (base, &digit)| {
+        if digit == INT_NULL {
+            return Ok(INT_NULL);
+        }
+        let checked = value.checked_mul(base).and_then(|v| v.checked_add(digit));
+        operator::overflowing(checked, value.wrapping_mul(base).wrapping_add(digit))
+    })
+}
+
+/// d sv x: the strings x joined with the char or string d in between. With ints, b sv x gives
+/// the number with the digits x in base b, or in the mixed radix b when b is a list.
+pub fn sv(delimiter: SuperType, value: SuperType) -> Result<SuperType, Error> {
+    if let Some(delimiter) = bytes(&delimiter) {
+        let SuperType::MixedList(items) = &value else {
+            return Err(Error::Type);
+        };
+        let pieces = items
+            .0
+            .iter()
+            .map(|item| bytes(item).ok_or(Error::Type))
+            .collect::<Result<Vec<_>, _>>()?;
+        return Ok(string(&pieces.join(delimiter.as_slice())));
+    }
+    let bases = ints(&delimiter).ok_or(Error::Type)?;
+    let rows = match rows(&value) {
+        Some(rows) => rows,
+        None => match ints(&value).ok_or(Error::Type)? {
+            Operand::List(digits) => digits.into_iter().map(|d| vec![d]).collect(),
+            Operand::Atom(_) => return Err(Error::Type),
+        },
+    };
+    if let Operand::List(bases) = &bases
+        && bases.len() != rows.len()
+    {
+        return Err(Error::Length);
+    }
+    let base = |i: usize| match &bases {
+        Operand::Atom(base) => *base,
+        Operand::List(bases) => bases[i],
+    };
+    // a number per column of the digit rows
+    let width = rows.first().map_or(0, Vec::len);
+    if rows.iter().any(|row| row.len() != width) {
+        return Err(Error::Length);
+    }
+    let numbers = (0..width)
+        .map(|j| {
+            let digits: Vec<i32> = rows.iter().map(|row| row[j]).collect();
+            decode((0..rows.len()).map(base), &digits)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(match value {
+        SuperType::MixedList(_) => SuperType::from(numbers),
+        _ => SuperType::from(numbers[0]),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{run, show};
+
+    #[test]
+    fn test_case_and_whitespace() {
+        assert_eq!(show("upper \"abc\""), "\"ABC\"");
+        assert_eq!(show("lower `AbC`D"), "`abc`d");
+        assert_eq!(show("upper (\"ab\";\"c\")"), "(\"AB\";\"C\")");
+        assert_eq!(show("trim \"  a b \""), "\"a b\"");
+        assert_eq!(show("ltrim \" a \""), "\"a \"");
+        assert_eq!(show("rtrim (\" a \";\"bc \")"), "(\" a\";\"bc\")");
+        assert_eq!(run("upper 1"), Err(Error::Type));
+    }
+
+    #[test]
+    fn test_strings_are_utf8_bytes() {
+        assert_eq!(run("count \"é\""), Ok(SuperType::from(2)));
+        assert_eq!(run("`int$\"é\""), Ok(SuperType::from(vec![195, 169])));
+        assert_eq!(show("\"aé\"[0 1 2]"), "\"aé\"");
+        assert_eq!(show("upper \"é!\""), "\"É!\"");
+        assert_eq!(show("\"é\" vs \"aébéc\""), "(,\"a\";,\"b\";,\"c\")");
+        assert_eq!(show("`$\"né\""), "`né");
+    }
+
+    #[test]
+    fn test_like() {
+        assert_eq!(run("\"hello\" like \"h*o\""), Ok(SuperType::from(true)));
+        assert_eq!(run("\"hello\" like \"h?llo\""), Ok(SuperType::from(true)));
+        assert_eq!(run("\"hello\" like \"h?lo\""), Ok(SuperType::from(false)));
+        assert_eq!(show("`ab`cb`b like \"[a-b]b\""), "100b");
+        assert_eq!(show("(\"x1\";\"xy\") like \"x[^0-9]\""), "01b");
+        assert_eq!(
+            show("(\"ERROR: disk\";\"INFO: ok\") like \"ERROR*\""),
+            "10b"
+        );
+        assert_eq!(run("\"a[\" like \"a[\""), Ok(SuperType::from(true)));
+    }
+
+    #[test]
+    fn test_ss_and_ssr() {
+        assert_eq!(run("\"abcabc\" ss \"bc\""), Ok(SuperType::from(vec![1, 4])));
+        assert_eq!(run("\"aaaa\" ss \"aa\""), Ok(SuperType::from(vec![0, 2])));
+        assert_eq!(show("(\"ab\";\"bb\") ss \"b\""), "(,1;0 1)");
+        assert_eq!(
+            run("\"a1b2\" ss \"[0-9]\""),
+            Ok(SuperType::from(vec![1, 3]))
+        );
+        assert_eq!(show("ssr[\"hello world\";\"o\";\"0\"]"), "\"hell0 w0rld\"");
+        assert_eq!(show("ssr[\"a1b2\";\"[0-9]\";\"#\"]"), "\"a#b#\"");
+        assert_eq!(show("ssr[\"ab\";\"b\";upper]"), "\"aB\"");
+    }
+
+    #[test]
+    fn test_vs_and_sv_on_strings() {
+        assert_eq!(show("\",\" vs \"ab,c,,d\""), "(\"ab\";,\"c\";\"\";,\"d\")");
+        assert_eq!(show("\"::\" vs \"k::vv\""), "(,\"k\";\"vv\")");
+        assert_eq!(show("\",\" sv (\"ab\";\"c\";\"\")"), "\"ab,c,\"");
+        assert_eq!(show("\", \" sv \",\" vs \"a,b\""), "\"a, b\"");
+    }
+
+    #[test]
+    fn test_vs_and_sv_encode_numbers() {
+        assert_eq!(run("2 vs 5"), Ok(SuperType::from(vec![1, 0, 1])));
+        assert_eq!(run("10 vs 0"), Ok(SuperType::from(vec![0])));
+        assert_eq!(run("24 60 60 vs 3723"), Ok(SuperType::from(vec![1, 2, 3])));
+        assert_eq!(show("10 vs 12 5"), "(1 0;2 5)");
+        assert_eq!(run("2 sv 1 0 1"), Ok(SuperType::from(5)));
+        assert_eq!(run("24 60 60 sv 1 2 3"), Ok(SuperType::from(3723)));
+        assert_eq!(run("10 sv 10 vs 12 5"), Ok(SuperType::from(vec![12, 5])));
+        assert_eq!(run("1 vs 5"), Err(Error::Domain));
+        assert_eq!(run("24 60 sv 1 2 3"), Err(Error::Length));
+    }
+}
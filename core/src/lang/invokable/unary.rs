@@ -1,16 +1,79 @@
+use crate::{
+    Error,
+    lang::{
+        SuperType,
+        invokable::{
+            Invokable, aggregate, comparison, list, math, matrix,
+            propagation::{self, Builtin},
+            regex, search, sort, stats, string,
+        },
+        parse_tree,
+    },
+};
+
 // Every unary operator takes 1 SuperType and returns a SuperType
 #[derive(Clone, Debug, Copy, PartialEq, Eq)]
 pub enum Unary {
     // List manipulation
     Count,
     Cut,
+    Distinct,
     Enlist,
     First,
+    Group,
     In,
     Last,
     Raze,
     Reverse,
     Til,
+    Where,
+
+    // Sorting
+    Asc,
+    Desc,
+    Iasc,
+    Idesc,
+    Rank,
+
+    // Aggregations
+    Sum,
+    Prd,
+    Min,
+    Max,
+    Avg,
+    Med,
+    Sums,
+    Prds,
+    Mins,
+    Maxs,
+    Avgs,
+    Deltas,
+    Ratios,
+
+    // Statistics
+    Var,
+    Dev,
+    Svar,
+    Sdev,
+
+    // Math
+    Neg,
+    Abs,
+    Sqrt,
+    Exp,
+    Log,
+    Floor,
+    Ceiling,
+    Signum,
+    Inv,
+
+    // Strings
+    Upper,
+    Lower,
+    Trim,
+    Ltrim,
+    Rtrim,
+    Regex,
 
     // IO
     Get,
@@ -24,7 +87,216 @@ pub enum Unary {
     // Meta
     Null,
     Attributes,
+    Parse,
+    Eval,
 
     // Assignement
     AssignThrough, // .: @: $: !: ?: +: -: *: %: =: ~: <: >: |: &: #: _: ^: ,:
 }
+
+impl Unary {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Unary::Count => "count",
+            Unary::Cut => "cut",
+            Unary::Distinct => "distinct",
+            Unary::Enlist => "enlist",
+            Unary::First => "first",
+            Unary::Group => "group",
+            Unary::In => "in",
+            Unary::Last => "last",
+            Unary::Raze => "raze",
+            Unary::Reverse => "reverse",
+            Unary::Til => "til",
+            Unary::Where => "where",
+            Unary::Asc => "asc",
+            Unary::Desc => "desc",
+            Unary::Iasc => "iasc",
+            Unary::Idesc => "idesc",
+            Unary::Rank => "rank",
+            Unary::Sum => "sum",
+            Unary::Prd => "prd",
+            Unary::Min => "min",
+            Unary::Max => "max",
+            Unary::Avg => "avg",
+            Unary::Med => "med",
+            Unary::Sums => "sums",
+            Unary::Prds => "prds",
+            Unary::Mins => "mins",
+            Unary::Maxs => "maxs",
+            Unary::Avgs => "avgs",
+            Unary::Deltas => "deltas",
+            Unary::Ratios => "ratios",
+            Unary::Var => "var",
+            Unary::Dev => "dev",
+            Unary::Svar => "svar",
+            Unary::Sdev => "sdev",
+            Unary::Neg => "neg",
+            Unary::Abs => "abs",
+            Unary::Sqrt => "sqrt",
+            Unary::Exp => "exp",
+            Unary::Log => "log",
+            Unary::Floor => "floor",
+            Unary::Ceiling => "ceiling",
+            Unary::Signum => "signum",
+            Unary::Inv => "inv",
+            Unary::Upper => "upper",
+            Unary::Lower => "lower",
+            Unary::Trim => "trim",
+            Unary::Ltrim => "ltrim",
+            Unary::Rtrim => "rtrim",
+            Unary::Regex => "regex",
+            Unary::Get => "get",
+            Unary::Set => "set",
+            Unary::All => "all",
+            Unary::Any => "any",
+            Unary::Not => "not",
+            Unary::Null => "null",
+            Unary::Attributes => "attr",
+            Unary::Parse => "parse",
+            Unary::Eval => "eval",
+            Unary::AssignThrough => ":",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Unary> {
+        let unary = match name {
+            "count" => Unary::Count,
+            "enlist" => Unary::Enlist,
+            "distinct" => Unary::Distinct,
+            "first" => Unary::First,
+            "group" => Unary::Group,
+            "last" => Unary::Last,
+            "raze" => Unary::Raze,
+            "reverse" => Unary::Reverse,
+            "til" => Unary::Til,
+            "where" => Unary::Where,
+            "asc" => Unary::Asc,
+            "desc" => Unary::Desc,
+            "iasc" => Unary::Iasc,
+            "idesc" => Unary::Idesc,
+            "rank" => Unary::Rank,
+            "sum" => Unary::Sum,
+            "prd" => Unary::Prd,
+            "min" => Unary::Min,
+            "max" => Unary::Max,
+            "avg" => Unary::Avg,
+            "med" => Unary::Med,
+            "sums" => Unary::Sums,
+            "prds" => Unary::Prds,
+            "mins" => Unary::Mins,
+            "maxs" => Unary::Maxs,
+            "avgs" => Unary::Avgs,
+            "deltas" => Unary::Deltas,
+            "ratios" => Unary::Ratios,
+            "var" => Unary::Var,
+            "dev" => Unary::Dev,
+            "svar" => Unary::Svar,
+            "sdev" => Unary::Sdev,
+            "neg" => Unary::Neg,
+            "abs" => Unary::Abs,
+            "sqrt" => Unary::Sqrt,
+            "exp" => Unary::Exp,
+            "log" => Unary::Log,
+            "floor" => Unary::Floor,
+            "ceiling" => Unary::Ceiling,
+            "signum" => Unary::Signum,
+            "inv" => Unary::Inv,
+            "upper" => Unary::Upper,
+            "lower" => Unary::Lower,
+            "trim" => Unary::Trim,
+            "ltrim" => Unary::Ltrim,
+            "rtrim" => Unary::Rtrim,
+            "regex" => Unary::Regex,
+            "all" => Unary::All,
+            "any" => Unary::Any,
+            "not" => Unary::Not,
+            "null" => Unary::Null,
+            "attr" => Unary::Attributes,
+            "parse" => Unary::Parse,
+            "eval" => Unary::Eval,
+            _ => return None,
+        };
+        Some(unary)
+    }
+}
+
+impl Invokable for Unary {
+    fn invoke_1(&self, arg1: SuperType) -> Result<SuperType, Error> {
+        let sorted = propagation::keeps_sorted(Builtin::Unary(*self), &[&arg1]);
+        let result = match self {
+            Unary::Count => Ok(SuperType::from(arg1.count() as i32)),
+            Unary::Enlist => list::enlist(arg1),
+            Unary::Distinct => search::distinct(arg1),
+            Unary::First => list::first(arg1),
+            Unary::Group => search::group(arg1),
+            Unary::Last => list::last(arg1),
+            Unary::Raze => list::raze(arg1),
+            Unary::Reverse => list::reverse(arg1),
+            Unary::Til => list::til(arg1),
+            Unary::Where => list::where_(arg1),
+            Unary::Asc => sort::asc(arg1),
+            Unary::Desc => sort::desc(arg1),
+            Unary::Iasc => sort::iasc(arg1),
+            Unary::Idesc => sort::idesc(arg1),
+            Unary::Rank => sort::rank(arg1),
+            Unary::Sum => aggregate::sum(arg1),
+            Unary::Prd => aggregate::prd(arg1),
+            Unary::Min => aggregate::min(arg1),
+            Unary::Max => aggregate::max(arg1),
+            Unary::Avg => aggregate::avg(arg1),
+            Unary::Med => aggregate::med(arg1),
+            Unary::Sums => aggregate::sums(arg1),
+            Unary::Prds => aggregate::prds(arg1),
+            Unary::Mins => aggregate::mins(arg1),
+            Unary::Maxs => aggregate::maxs(arg1),
+            Unary::Avgs => aggregate::avgs(arg1),
+            Unary::Deltas => aggregate::deltas(arg1),
+            Unary::Ratios => aggregate::ratios(arg1),
+            Unary::Var => stats::var(arg1),
+            Unary::Dev => stats::dev(arg1),
+            Unary::Svar => stats::svar(arg1),
+            Unary::Sdev => stats::sdev(arg1),
+            Unary::Neg => math::neg(arg1),
+            Unary::Abs => math::abs(arg1),
+            Unary::Sqrt => math::sqrt(arg1),
+            Unary::Exp => math::exp(arg1),
+            Unary::Log => math::log(arg1),
+            Unary::Floor => math::floor(arg1),
+            Unary::Ceiling => math::ceiling(arg1),
+            Unary::Signum => math::signum(arg1),
+            Unary::Inv => matrix::inv(arg1),
+            Unary::Upper => string::upper(arg1),
+            Unary::Lower => string::lower(arg1),
+            Unary::Trim => string::trim(arg1),
+            Unary::Ltrim => string::ltrim(arg1),
+            Unary::Rtrim => string::rtrim(arg1),
+            Unary::Regex => regex::regex(arg1),
+            Unary::All => comparison::all(arg1),
+            Unary::Any => comparison::any(arg1),
+            Unary::Not => comparison::not(arg1),
+            Unary::Null => comparison::null(arg1),
+            Unary::Attributes => list::attributes(arg1),
+            Unary::Parse => parse(arg1),
+            Unary::Cut | Unary::In | Unary::Get | Unary::Set | Unary::AssignThrough => {
+                Err(Error::NotYetImplemented)
+            }
+            // eval needs a context to run in, the execution context catches it first
+            Unary::Eval => Err(Error::NotYetImplemented),
+        }?;
+        Ok(propagation::apply(sorted, result))
+    }
+}
+
+// parse "x+1" returns the parse tree of the expression as data
+fn parse(source: SuperType) -> Result<SuperType, Error> {
+    let source: String = match source {
+        SuperType::Chars(chars) => chars.text(),
+        SuperType::Char(c) => (c.data().0 as char).to_string(),
+        _ => return Err(Error::Type),
+    };
+    match parse_tree::parse(&source)?.as_slice() {
+        [statement] => Ok(SuperType::from(statement)),
+        _ => Err(Error::NotYetImplemented),
+    }
+}
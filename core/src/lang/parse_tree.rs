@@ -1,14 +1,659 @@
-use crate::lang::SuperType;
+use cupid_parser::{Expr, Query as QueryExpr};
+
+pub use cupid_parser::QueryKind;
+
+use crate::{
+    Error,
+    core::instruction::Instruction,
+    lang::{
+        SuperType,
+        invokable::{self, list, operator::Operator, unary::Unary},
+        type_system::{
+            Atom, List, TypeDate, TypeDictionary, TypeLambda, TypeMixedList, TypeOperator,
+            TypeUnary,
+        },
+    },
+};
 
 /// defines an expression but isn't evaluated.
 /// This is the upmost structure representing code before being compiled into instructions.
+#[derive(Clone, Debug, PartialEq)]
 pub struct ParseTree {
-    //TODO: invokable: Invokable,
+    invokable: Box<ParseTreeNode>,
     arguments: Vec<ParseTreeNode>,
 }
 
+#[derive(Clone, Debug, PartialEq)]
 pub enum ParseTreeNode {
     Literal(SuperType),   // just a value
     Variable(String),     // variable name, needs to be resolved
     ParseTree(ParseTree), // nested parse tree, e.g. for function calls
+    Assign {
+        name: String,
+        value: Box<ParseTreeNode>,
+    },
+    AssignGlobal {
+        name: String,
+        value: Box<ParseTreeNode>,
+    },
+    // x op: y, x[i]: y and x[i] op: y, changing x where it is defined
+    Amend {
+        name: String,
+        index: Option<Box<ParseTreeNode>>,
+        op: Option<Operator>,
+        value: Box<ParseTreeNode>,
+    },
+    List(Vec<ParseTreeNode>), // (a;b;c), each item is evaluated
+    Query(Box<Query>),
+}
+
+/// A qSQL query whose phrases are still unevaluated.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Query {
+    pub kind: QueryKind,
+    pub columns: Vec<(Option<String>, ParseTreeNode)>,
+    pub by: Vec<(Option<String>, ParseTreeNode)>,
+    pub from: ParseTreeNode,
+    pub constraints: Vec<ParseTreeNode>,
+}
+
+impl ParseTree {
+    pub fn new(invokable: ParseTreeNode, arguments: Vec<ParseTreeNode>) -> Self {
+        ParseTree {
+            invokable: Box::new(invokable),
+            arguments,
+        }
+    }
+
+    pub fn invokable(&self) -> &ParseTreeNode {
+        &self.invokable
+    }
+
+    pub fn arguments(&self) -> &[ParseTreeNode] {
+        &self.arguments
+    }
+}
+
+impl ParseTreeNode {
+    /// Compiles the node into instructions, in the order they must run.
+    pub fn compile(&self, instructions: &mut Vec<Instruction>) {
+        match self {
+            ParseTreeNode::Literal(value) => instructions.push(Instruction::Literal(value.clone())),
+            ParseTreeNode::Variable(name) => {
+                instructions.push(Instruction::GetVariable { name: name.clone() })
+            }
+            ParseTreeNode::ParseTree(tree) => {
+                // q evaluates right to left
+                for argument in tree.arguments.iter().rev() {
+                    argument.compile(instructions);
+                }
+                tree.invokable.compile(instructions);
+                instructions.push(Instruction::Invoke {
+                    arity: tree.arguments.len(),
+                });
+            }
+            ParseTreeNode::Assign { name, value } => {
+                value.compile(instructions);
+                instructions.push(Instruction::Assign { name: name.clone() });
+            }
+            ParseTreeNode::AssignGlobal { name, value } => {
+                value.compile(instructions);
+                instructions.push(Instruction::AssignGlobal { name: name.clone() });
+            }
+            ParseTreeNode::Amend {
+                name,
+                index,
+                op,
+                value,
+            } => {
+                value.compile(instructions);
+                if let Some(index) = index {
+                    index.compile(instructions);
+                }
+                instructions.push(Instruction::Amend {
+                    name: name.clone(),
+                    op: *op,
+                    indexed: index.is_some(),
+                });
+            }
+            ParseTreeNode::List(items) => {
+                for item in items.iter().rev() {
+                    item.compile(instructions);
+                }
+                instructions.push(Instruction::EnList { count: items.len() });
+            }
+            ParseTreeNode::Query(query) => instructions.push(Instruction::Query(query.clone())),
+        }
+    }
+
+    /// The name a query gives to a column computed by this expression when none is given.
+    pub fn default_name(&self) -> Option<&str> {
+        match self {
+            ParseTreeNode::Variable(name) => Some(name),
+            ParseTreeNode::Assign { name, .. }
+            | ParseTreeNode::AssignGlobal { name, .. }
+            | ParseTreeNode::Amend { name, .. } => Some(name),
+            ParseTreeNode::ParseTree(tree) => std::iter::once(tree.invokable.as_ref())
+                .chain(tree.arguments.iter())
+                .find_map(ParseTreeNode::default_name),
+            _ => None,
+        }
+    }
+}
+
+/// The name of a column computed by `node`: its given name, else the first variable it uses.
+pub(crate) fn column_name(name: &Option<String>, node: &ParseTreeNode) -> String {
+    name.clone()
+        .or_else(|| node.default_name().map(str::to_owned))
+        .unwrap_or_else(|| "x".to_owned())
+}
+
+/// Lexes and parses a program into one node per statement.
+pub fn parse(source: &str) -> Result<Vec<ParseTreeNode>, Error> {
+    parse_with(source, |_| false)
+}
+
+/// As parse, `is_function` telling which variables hold functions: a minus after them starts a
+/// negative literal, as it does after the builtins.
+pub fn parse_with(
+    source: &str,
+    is_function: impl Fn(&str) -> bool,
+) -> Result<Vec<ParseTreeNode>, Error> {
+    let is_verb = |name: &str| invokable::builtin(name).is_some() || is_function(name);
+    let tokens = cupid_parser::lex_with(source, is_verb).map_err(Error::Parse)?;
+    cupid_parser::parse(&tokens)
+        .map_err(Error::Parse)?
+        .iter()
+        .map(ParseTreeNode::try_from)
+        .collect()
+}
+
+fn literal(value: impl Into<SuperType>) -> ParseTreeNode {
+    ParseTreeNode::Literal(value.into())
+}
+
+fn int(value: i64) -> Result<i32, Error> {
+    i32::try_from(value).map_err(|_| Error::Parse(format!("{value} does not fit in an int")))
+}
+
+fn apply(function: SuperType, arguments: Vec<ParseTreeNode>) -> ParseTreeNode {
+    ParseTreeNode::ParseTree(ParseTree::new(ParseTreeNode::Literal(function), arguments))
+}
+
+fn operator(symbol: &str) -> Result<SuperType, Error> {
+    invokable::operator(symbol).ok_or(Error::NotYetImplemented)
+}
+
+// ([k:..] c:..) is built as k!flip `k`c!(..;..)
+fn table(keys: &[(String, Expr)], columns: &[(String, Expr)]) -> Result<ParseTreeNode, Error> {
+    let (names, values): (Vec<String>, Vec<&Expr>) = keys
+        .iter()
+        .chain(columns.iter())
+        .map(|(name, value)| (name.clone(), value))
+        .unzip();
+    let values = values
+        .into_iter()
+        .map(ParseTreeNode::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let dictionary = apply(
+        operator("!")?,
+        vec![
+            literal(SuperType::symbols(names)),
+            ParseTreeNode::List(values),
+        ],
+    );
+    let table = apply(operator("+")?, vec![dictionary]);
+    Ok(match keys.len() {
+        0 => table,
+        n => apply(operator("!")?, vec![literal(int(n as i64)?), table]),
+    })
+}
+
+fn named(
+    columns: &[(Option<String>, Expr)],
+) -> Result<Vec<(Option<String>, ParseTreeNode)>, Error> {
+    columns
+        .iter()
+        .map(|(name, expr)| Ok((name.clone(), ParseTreeNode::try_from(expr)?)))
+        .collect()
+}
+
+impl TryFrom<&QueryExpr> for Query {
+    type Error = Error;
+
+    fn try_from(query: &QueryExpr) -> Result<Self, Self::Error> {
+        Ok(Query {
+            kind: query.kind,
+            columns: named(&query.columns)?,
+            by: named(&query.by)?,
+            from: ParseTreeNode::try_from(&query.from)?,
+            constraints: query
+                .constraints
+                .iter()
+                .map(ParseTreeNode::try_from)
+                .collect::<Result<Vec<_>, _>>()?,
+        })
+    }
+}
+
+impl TryFrom<&Expr> for ParseTreeNode {
+    type Error = Error;
+
+    fn try_from(expr: &Expr) -> Result<Self, Self::Error> {
+        let node = match expr {
+            Expr::Integer(i) => literal(int(*i)?),
+            Expr::Integers(i) => literal(i.iter().map(|&i| int(i)).collect::<Result<Vec<_>, _>>()?),
+            Expr::Decimal(f) => literal(*f),
+            Expr::Decimals(f) => literal(f.clone()),
+            Expr::Date(d) => literal(Atom::new(TypeDate(int(*d)?))),
+            Expr::Dates(d) => literal(List::new(
+                d.iter()
+                    .map(|&d| int(d).map(TypeDate))
+                    .collect::<Result<Vec<_>, _>>()?,
+            )),
+            Expr::Bool(b) => literal(*b),
+            Expr::Bools(b) => literal(b.clone()),
+            // chars are bytes, a char that takes more is a string of its UTF-8 encoding
+            Expr::Char(c) if c.is_ascii() => literal(SuperType::char(*c as u8)),
+            Expr::Char(c) => literal(SuperType::from(c.to_string().as_str())),
+            Expr::Str(s) => literal(SuperType::from(s.as_str())),
+            Expr::Symbol(s) => literal(SuperType::symbol(s.as_str())),
+            Expr::Symbols(s) => literal(SuperType::symbols(s.clone())),
+            // builtins are reserved words, they can't be shadowed by variables
+            Expr::Identifier(name) => match invokable::builtin(name) {
+                Some(function) => ParseTreeNode::Literal(function),
+                None => ParseTreeNode::Variable(name.clone()),
+            },
+            Expr::Operator(symbol) => ParseTreeNode::Literal(operator(symbol)?),
+            Expr::Apply(function, arguments) => ParseTreeNode::ParseTree(ParseTree::new(
+                ParseTreeNode::try_from(function.as_ref())?,
+                arguments
+                    .iter()
+                    .map(ParseTreeNode::try_from)
+                    .collect::<Result<Vec<_>, _>>()?,
+            )),
+            Expr::Assign(name, value) => ParseTreeNode::Assign {
+                name: name.clone(),
+                value: Box::new(ParseTreeNode::try_from(value.as_ref())?),
+            },
+            Expr::AssignGlobal(name, value) => ParseTreeNode::AssignGlobal {
+                name: name.clone(),
+                value: Box::new(ParseTreeNode::try_from(value.as_ref())?),
+            },
+            Expr::Amend {
+                name,
+                index,
+                op,
+                value,
+            } => ParseTreeNode::Amend {
+                name: name.clone(),
+                index: match index {
+                    Some(index) => Some(Box::new(ParseTreeNode::try_from(index.as_ref())?)),
+                    None => None,
+                },
+                op: match op {
+                    Some(op) => Some(Operator::from_symbol(op).ok_or(Error::NotYetImplemented)?),
+                    None => None,
+                },
+                value: Box::new(ParseTreeNode::try_from(value.as_ref())?),
+            },
+            Expr::Lambda { params, body } => {
+                ParseTreeNode::Literal(SuperType::Lambda(TypeLambda {
+                    params: params.clone(),
+                    body: body
+                        .iter()
+                        .map(ParseTreeNode::try_from)
+                        .collect::<Result<Vec<_>, _>>()?,
+                }))
+            }
+            Expr::List(items) => ParseTreeNode::List(
+                items
+                    .iter()
+                    .map(ParseTreeNode::try_from)
+                    .collect::<Result<Vec<_>, _>>()?,
+            ),
+            Expr::Table { keys, columns } => table(keys, columns)?,
+            Expr::Query(query) => ParseTreeNode::Query(Box::new(Query::try_from(query.as_ref())?)),
+        };
+        Ok(node)
+    }
+}
+
+// Parse trees as data, the way q represents them:
+// - a function applied to its arguments is the list (f;x;y)
+// - a variable is its name as a symbol, so literal symbols and lists are enlisted
+// - a query is (?;t;c;b;a) for select and exec, (!;t;c;b;a) for update and delete
+
+fn mixed(items: Vec<SuperType>) -> SuperType {
+    SuperType::MixedList(TypeMixedList(items))
+}
+
+fn is_empty_list(value: &SuperType) -> bool {
+    !value.is_atom() && value.count() == 0
+}
+
+// `a`b!(x;y) for named columns
+fn named_columns(columns: &[(Option<String>, ParseTreeNode)]) -> SuperType {
+    let names = columns
+        .iter()
+        .map(|(name, node)| column_name(name, node))
+        .collect();
+    let values = columns.iter().map(|(_, node)| node.into()).collect();
+    SuperType::Dictionary(
+        TypeDictionary::new(SuperType::symbols(names), mixed(values))
+            .expect("as many names as columns"),
+    )
+}
+
+fn unnamed_columns(
+    dictionary: &TypeDictionary,
+) -> Result<Vec<(Option<String>, ParseTreeNode)>, Error> {
+    let names = match dictionary.keys() {
+        SuperType::Symbols(names) => names.data().iter().map(|name| name.0.clone()),
+        _ => return Err(Error::Type),
+    };
+    names
+        .zip(dictionary.values().clone().into_items())
+        .map(|(name, value)| Ok((Some(name), ParseTreeNode::try_from(&value)?)))
+        .collect()
+}
+
+fn is_single_unnamed(columns: &[(Option<String>, ParseTreeNode)]) -> bool {
+    matches!(columns, [(None, _)])
+}
+
+impl From<&Query> for SuperType {
+    fn from(query: &Query) -> Self {
+        let function = match query.kind {
+            QueryKind::Select | QueryKind::Exec => Operator::Find,
+            QueryKind::Update | QueryKind::Delete => Operator::Dict,
+        };
+        let constraints = mixed(query.constraints.iter().map(SuperType::from).collect());
+        let by = match query.kind {
+            QueryKind::Exec if query.by.is_empty() => mixed(vec![]),
+            QueryKind::Exec if is_single_unnamed(&query.by) => (&query.by[0].1).into(),
+            _ if query.by.is_empty() => SuperType::from(false),
+            _ => named_columns(&query.by),
+        };
+        let columns = match query.kind {
+            QueryKind::Delete => SuperType::symbols(
+                query
+                    .columns
+                    .iter()
+                    .map(|(name, node)| column_name(name, node))
+                    .collect(),
+            ),
+            QueryKind::Exec if is_single_unnamed(&query.columns) => (&query.columns[0].1).into(),
+            _ if query.columns.is_empty() => mixed(vec![]),
+            _ => named_columns(&query.columns),
+        };
+        mixed(vec![
+            SuperType::BinaryOperation(TypeOperator(function)),
+            (&query.from).into(),
+            constraints,
+            by,
+            columns,
+        ])
+    }
+}
+
+impl From<&ParseTreeNode> for SuperType {
+    fn from(node: &ParseTreeNode) -> Self {
+        match node {
+            ParseTreeNode::Literal(
+                value @ (SuperType::Symbol(_) | SuperType::Symbols(_) | SuperType::MixedList(_)),
+            ) => list::enlist(value.clone()).expect("enlisting never fails on lists"),
+            ParseTreeNode::Literal(value) => value.clone(),
+            ParseTreeNode::Variable(name) => SuperType::symbol(name.as_str()),
+            ParseTreeNode::ParseTree(tree) => mixed(
+                std::iter::once(tree.invokable())
+                    .chain(tree.arguments())
+                    .map(SuperType::from)
+                    .collect(),
+            ),
+            ParseTreeNode::Assign { name, value } => mixed(vec![
+                SuperType::BinaryOperation(TypeOperator(Operator::Assign)),
+                SuperType::symbol(name.as_str()),
+                value.as_ref().into(),
+            ]),
+            ParseTreeNode::AssignGlobal { name, value } => mixed(vec![
+                SuperType::BinaryOperation(TypeOperator(Operator::GlobalAssign)),
+                SuperType::symbol(name.as_str()),
+                value.as_ref().into(),
+            ]),
+            // as data x op: y is x: x op y, and x[i] is the application of x to i
+            ParseTreeNode::Amend {
+                name,
+                index,
+                op,
+                value,
+            } => {
+                let target = match index {
+                    Some(index) => mixed(vec![
+                        SuperType::symbol(name.as_str()),
+                        index.as_ref().into(),
+                    ]),
+                    None => SuperType::symbol(name.as_str()),
+                };
+                let value = match op {
+                    Some(op) => mixed(vec![
+                        SuperType::BinaryOperation(TypeOperator(*op)),
+                        target.clone(),
+                        value.as_ref().into(),
+                    ]),
+                    None => value.as_ref().into(),
+                };
+                mixed(vec![
+                    SuperType::BinaryOperation(TypeOperator(Operator::Assign)),
+                    target,
+                    value,
+                ])
+            }
+            ParseTreeNode::List(items) => mixed(
+                std::iter::once(SuperType::UnaryOperation(TypeUnary(Unary::Enlist)))
+                    .chain(items.iter().map(SuperType::from))
+                    .collect(),
+            ),
+            ParseTreeNode::Query(query) => query.as_ref().into(),
+        }
+    }
+}
+
+impl Query {
+    /// Builds the query of a functional form, ?[t;c;b;a] or ![t;c;b;a].
+    /// Returns None when `function` is not `?` or `!` applied to 4 arguments.
+    pub fn functional(
+        function: &SuperType,
+        arguments: &[SuperType],
+    ) -> Result<Option<Query>, Error> {
+        let ([from, constraints, by, columns], SuperType::BinaryOperation(op)) =
+            (arguments, function)
+        else {
+            return Ok(None);
+        };
+        if !matches!(op.0, Operator::Find | Operator::Dict) {
+            return Ok(None);
+        }
+
+        if constraints.is_atom() {
+            return Err(Error::Type);
+        }
+        let constraints = constraints
+            .clone()
+            .into_items()
+            .iter()
+            .map(ParseTreeNode::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // 0b or a dictionary groups for select, () or a single expression for exec
+        let (by, exec) = match by {
+            SuperType::Bool(b) if !b.data().0 => (vec![], false),
+            SuperType::Dictionary(d) => (unnamed_columns(d)?, false),
+            by if is_empty_list(by) => (vec![], true),
+            by => (vec![(None, ParseTreeNode::try_from(by)?)], true),
+        };
+
+        let (kind, columns) = match (op.0, columns) {
+            (Operator::Find, SuperType::Dictionary(d)) => (QueryKind::Select, unnamed_columns(d)?),
+            (Operator::Find, columns) if is_empty_list(columns) => (QueryKind::Select, vec![]),
+            (Operator::Find, columns) => (
+                QueryKind::Exec,
+                vec![(None, ParseTreeNode::try_from(columns)?)],
+            ),
+            (_, SuperType::Dictionary(d)) => (QueryKind::Update, unnamed_columns(d)?),
+            (_, columns @ (SuperType::Symbol(_) | SuperType::Symbols(_))) => {
+                let names = columns
+                    .clone()
+                    .into_items()
+                    .into_iter()
+                    .map(|name| match name {
+                        SuperType::Symbol(name) => name.data().0.clone(),
+                        _ => unreachable!(),
+                    });
+                let columns = names
+                    .map(|name| (Some(name.clone()), ParseTreeNode::Variable(name)))
+                    .collect();
+                (QueryKind::Delete, columns)
+            }
+            (_, columns) if is_empty_list(columns) => (QueryKind::Delete, vec![]),
+            _ => return Err(Error::Type),
+        };
+        let kind = match (kind, exec) {
+            (QueryKind::Select, true) => QueryKind::Exec,
+            (QueryKind::Update | QueryKind::Delete, true) => return Err(Error::Type),
+            (kind, _) => kind,
+        };
+
+        Ok(Some(Query {
+            kind,
+            columns,
+            by,
+            from: ParseTreeNode::try_from(from)?,
+            constraints,
+        }))
+    }
+}
+
+impl ParseTreeNode {
+    /// Invocations that run parse trees given as data: eval and the functional queries.
+    pub fn from_invocation(
+        function: &SuperType,
+        arguments: &[SuperType],
+    ) -> Result<Option<ParseTreeNode>, Error> {
+        if let (SuperType::UnaryOperation(unary), [tree]) = (function, arguments)
+            && unary.0 == Unary::Eval
+        {
+            return ParseTreeNode::try_from(tree).map(Some);
+        }
+        // the arguments are values already, a symbol names the table rather than a variable
+        Ok(Query::functional(function, arguments)?.map(|mut query| {
+            query.from = ParseTreeNode::Literal(arguments[0].clone());
+            ParseTreeNode::Query(Box::new(query))
+        }))
+    }
+}
+
+impl TryFrom<&SuperType> for ParseTreeNode {
+    type Error = Error;
+
+    fn try_from(value: &SuperType) -> Result<Self, Self::Error> {
+        let node = match value {
+            SuperType::Symbol(name) => ParseTreeNode::Variable(name.data().0.clone()),
+            SuperType::Symbols(symbols) if symbols.data().len() == 1 => {
+                ParseTreeNode::Literal(SuperType::symbol(symbols.data()[0].0.as_str()))
+            }
+            SuperType::MixedList(list) => match list.0.as_slice() {
+                [] => ParseTreeNode::Literal(value.clone()),
+                [item] => ParseTreeNode::Literal(item.clone()),
+                [function, arguments @ ..] => {
+                    if let Some(query) = Query::functional(function, arguments)? {
+                        return Ok(ParseTreeNode::Query(Box::new(query)));
+                    }
+                    match (function, arguments) {
+                        (SuperType::UnaryOperation(unary), items) if unary.0 == Unary::Enlist => {
+                            ParseTreeNode::List(
+                                items
+                                    .iter()
+                                    .map(ParseTreeNode::try_from)
+                                    .collect::<Result<Vec<_>, _>>()?,
+                            )
+                        }
+                        (SuperType::BinaryOperation(op), [SuperType::Symbol(name), value])
+                            if op.0 == Operator::Assign =>
+                        {
+                            ParseTreeNode::Assign {
+                                name: name.data().0.clone(),
+                                value: Box::new(ParseTreeNode::try_from(value)?),
+                            }
+                        }
+                        (SuperType::BinaryOperation(op), [SuperType::MixedList(target), value])
+                            if op.0 == Operator::Assign
+                                && matches!(target.0.as_slice(), [SuperType::Symbol(_), _]) =>
+                        {
+                            let [SuperType::Symbol(name), index] = target.0.as_slice() else {
+                                unreachable!()
+                            };
+                            ParseTreeNode::Amend {
+                                name: name.data().0.clone(),
+                                index: Some(Box::new(ParseTreeNode::try_from(index)?)),
+                                op: None,
+                                value: Box::new(ParseTreeNode::try_from(value)?),
+                            }
+                        }
+                        (SuperType::BinaryOperation(op), [SuperType::Symbol(name), value])
+                            if op.0 == Operator::GlobalAssign =>
+                        {
+                            ParseTreeNode::AssignGlobal {
+                                name: name.data().0.clone(),
+                                value: Box::new(ParseTreeNode::try_from(value)?),
+                            }
+                        }
+                        (function, arguments) => ParseTreeNode::ParseTree(ParseTree::new(
+                            ParseTreeNode::try_from(function)?,
+                            arguments
+                                .iter()
+                                .map(ParseTreeNode::try_from)
+                                .collect::<Result<Vec<_>, _>>()?,
+                        )),
+                    }
+                }
+            },
+            other => ParseTreeNode::Literal(other.clone()),
+        };
+        Ok(node)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // column names are filled in on the way to data, so compare the data
+    fn round_trip(source: &str) {
+        let data = SuperType::from(&parse(source).unwrap().remove(0));
+        let node = ParseTreeNode::try_from(&data).unwrap();
+        assert_eq!(SuperType::from(&node), data, "{source}");
+    }
+
+    #[test]
+    fn test_parse_tree_as_data() {
+        let node = parse("x+`a").unwrap().remove(0);
+        assert_eq!(SuperType::from(&node).to_string(), "(+;`x;,`a)");
+    }
+
+    #[test]
+    fn test_parse_tree_round_trips_through_data() {
+        round_trip("x: 1 2 3 * y");
+        round_trip("x:: {y} 1");
+        round_trip("x,: 1 2");
+        round_trip("x[0]: 3");
+        round_trip("x[i]+: y");
+        round_trip("(a;`b`c;\"de\")");
+        round_trip("select px, n:count qty by sym from t where px>1, qty<10");
+        round_trip("exec px from t");
+        round_trip("update px:0 from t where sym=`a");
+        round_trip("delete qty from t");
+        round_trip("delete from t where px>1");
+    }
 }
@@ -21,11 +21,22 @@ impl<T: InnerTypeTrait> Atom<T> {
         &self.data
     }
 
+    pub fn into_data(self) -> T {
+        self.data
+    }
+
     pub fn enlist(self) -> List<T> {
         List::new(vec![self.data])
     }
 }
 
+impl<T: InnerTypeTrait + Clone> Atom<T> {
+    /// Returns a list holding `count` copies of this atom.
+    pub fn repeat(&self, count: usize) -> List<T> {
+        List::new(vec![self.data.clone(); count])
+    }
+}
+
 impl<T: InnerTypeTrait> TypeTrait for Atom<T> {
     fn count(&self) -> usize {
         1
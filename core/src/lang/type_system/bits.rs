@@ -0,0 +1,246 @@
+use crate::{
+    Error,
+    lang::type_system::{InnerTypeTrait, List, TypeBool, TypeTrait, list::attribute_index},
+};
+
+use super::Attribute;
+
+const WORD: usize = u64::BITS as usize;
+
+/// A list of booleans packed 64 to a word, so that masks over many rows combine a word at a
+/// time. The bits past the last item are always clear.
+#[derive(Debug, Clone)]
+pub struct Bits {
+    words: Vec<u64>,
+    len: usize,
+    attributes: u8,
+}
+
+impl Bits {
+    pub fn new(data: Vec<TypeBool>) -> Self {
+        data.into_iter().map(|b| b.0).collect()
+    }
+
+    /// Bits out of packed words, ignoring what they hold past `len` items.
+    pub(crate) fn from_words(words: Vec<u64>, len: usize) -> Self {
+        let mut bits = Bits {
+            words,
+            len,
+            attributes: Attribute::None as u8,
+        };
+        bits.clear_tail();
+        bits
+    }
+
+    // Clears the bits past the last item, which word-at-a-time operations may have set
+    fn clear_tail(&mut self) {
+        if let Some(last) = self.words.last_mut()
+            && !self.len.is_multiple_of(WORD)
+        {
+            *last &= (1 << (self.len % WORD)) - 1;
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The packed words, the first item in the lowest bit of the first word.
+    pub fn words(&self) -> &[u64] {
+        &self.words
+    }
+
+    pub fn get(&self, index: usize) -> Option<bool> {
+        (index < self.len).then(|| self.words[index / WORD] >> (index % WORD) & 1 == 1)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = bool> + '_ {
+        (0..self.len).map(|i| self.words[i / WORD] >> (i % WORD) & 1 == 1)
+    }
+
+    /// The items unpacked, for the operations that work on any typed list.
+    pub fn data(&self) -> Vec<TypeBool> {
+        self.iter().map(TypeBool).collect()
+    }
+
+    pub fn into_data(self) -> Vec<TypeBool> {
+        self.data()
+    }
+
+    pub fn push(&mut self, bit: bool) {
+        if self.len.is_multiple_of(WORD) {
+            self.words.push(0);
+        }
+        self.words[self.len / WORD] |= (bit as u64) << (self.len % WORD);
+        self.len += 1;
+        self.attributes = Attribute::None as u8;
+    }
+
+    /// Replaces the item at `index`, which must be in range.
+    pub fn set(&mut self, index: usize, bit: bool) {
+        assert!(index < self.len, "bit {index} out of {}", self.len);
+        let mask = 1 << (index % WORD);
+        let word = &mut self.words[index / WORD];
+        *word = (*word & !mask) | (bit as u64) << (index % WORD);
+        self.attributes = Attribute::None as u8;
+    }
+
+    /// The items at `indices`, None when one is out of range.
+    pub fn select(&self, indices: &[usize]) -> Option<Bits> {
+        indices.iter().map(|&i| self.get(i)).collect()
+    }
+
+    fn zip_words(&self, other: &Bits, f: impl Fn(u64, u64) -> u64) -> Result<Bits, Error> {
+        if self.len != other.len {
+            return Err(Error::Length);
+        }
+        let words = self.words.iter().zip(&other.words).map(|(l, r)| f(*l, *r));
+        Ok(Bits::from_words(words.collect(), self.len))
+    }
+
+    pub fn and(&self, other: &Bits) -> Result<Bits, Error> {
+        self.zip_words(other, |l, r| l & r)
+    }
+
+    pub fn or(&self, other: &Bits) -> Result<Bits, Error> {
+        self.zip_words(other, |l, r| l | r)
+    }
+
+    pub fn not(&self) -> Bits {
+        Bits::from_words(self.words.iter().map(|w| !w).collect(), self.len)
+    }
+
+    /// The number of items that are set.
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// The positions of the items that are set, in order.
+    pub fn ones(&self) -> Vec<usize> {
+        let mut positions = Vec::with_capacity(self.count_ones());
+        for (i, &word) in self.words.iter().enumerate() {
+            let mut word = word;
+            while word != 0 {
+                positions.push(i * WORD + word.trailing_zeros() as usize);
+                word &= word - 1;
+            }
+        }
+        positions
+    }
+
+    /// Marks the list sorted without checking, for results that are sorted by construction.
+    pub(crate) fn assume_sorted(&mut self) {
+        self.attributes = Attribute::Sorted as u8;
+    }
+
+    /// The items unpacked into a list, for the operations that work on any typed list. It stays
+    /// sorted, the other attributes index at most two distinct items and are not worth keeping.
+    pub(crate) fn to_list(&self) -> List<TypeBool> {
+        let mut list = List::new(self.data());
+        if Attribute::has_sorted(self.attributes) {
+            list.assume_sorted();
+        }
+        list
+    }
+}
+
+// As for lists, attributes do not take part in equality
+impl PartialEq for Bits {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.words == other.words
+    }
+}
+
+impl TypeTrait for Bits {
+    fn count(&self) -> usize {
+        self.len
+    }
+
+    fn get_type() -> i16 {
+        TypeBool::get_type().abs()
+    }
+
+    fn get_attributes(&self) -> u8 {
+        self.attributes
+    }
+
+    fn set_attribute(&mut self, attribute: Attribute) -> Result<(), Error> {
+        attribute_index(&self.data(), &attribute)?;
+        self.attributes = attribute as u8;
+        Ok(())
+    }
+}
+
+impl FromIterator<bool> for Bits {
+    fn from_iter<I: IntoIterator<Item = bool>>(iter: I) -> Self {
+        let mut bits = Bits::from_words(Vec::new(), 0);
+        for bit in iter {
+            bits.push(bit);
+        }
+        bits
+    }
+}
+
+impl From<Vec<bool>> for Bits {
+    fn from(values: Vec<bool>) -> Self {
+        values.into_iter().collect()
+    }
+}
+
+impl From<List<TypeBool>> for Bits {
+    fn from(list: List<TypeBool>) -> Self {
+        Bits::new(list.into_data())
+    }
+}
+
+impl IntoIterator for Bits {
+    type Item = TypeBool;
+    type IntoIter = std::vec::IntoIter<TypeBool>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.into_data().into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bits(pattern: &str) -> Bits {
+        pattern.chars().map(|c| c == '1').collect()
+    }
+
+    #[test]
+    fn test_packs_across_words() {
+        let mask: Bits = (0..130).map(|i| i % 3 == 0).collect();
+        assert_eq!(mask.words().len(), 3);
+        assert_eq!(mask.get(129), Some(true));
+        assert_eq!(mask.get(130), None);
+        assert_eq!(mask.count_ones(), 44);
+        assert_eq!(mask.ones()[..3], [0, 3, 6]);
+        assert_eq!(mask.ones().last(), Some(&129));
+
+        let mut mask = mask;
+        mask.set(129, false);
+        mask.set(64, true);
+        assert_eq!((mask.get(129), mask.get(64)), (Some(false), Some(true)));
+        assert_eq!(mask.count_ones(), 44);
+    }
+
+    #[test]
+    fn test_word_operations() {
+        assert_eq!(bits("1100").and(&bits("1010")), Ok(bits("1000")));
+        assert_eq!(bits("1100").or(&bits("1010")), Ok(bits("1110")));
+        assert_eq!(bits("1100").and(&bits("1")), Err(Error::Length));
+
+        // negating keeps the bits past the end clear
+        let negated = bits("101").not();
+        assert_eq!(negated, bits("010"));
+        assert_eq!(negated.words(), [0b010]);
+        assert_eq!(negated.count_ones(), 1);
+    }
+}
@@ -0,0 +1,279 @@
+use std::fmt::{Display, Formatter, Result};
+
+use crate::lang::type_system::{
+    Nullable, SuperType, TypeDate, TypeDictionary, TypeFloat, TypeInt, TypeTable,
+};
+
+// Floats print their shortest representation, whole values get a trailing f when nothing else
+// tells them apart from ints
+fn format_floats(values: &[f64]) -> String {
+    let formatted = values
+        .iter()
+        .map(|&v| match TypeFloat(v) {
+            v if v.is_null() => "0n".to_owned(),
+            v if Some(v) == TypeFloat::INFINITY => "0w".to_owned(),
+            v if Some(v) == TypeFloat::NEGATIVE_INFINITY => "-0w".to_owned(),
+            v => v.0.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    match values.iter().all(|v| v.fract() == 0.0 && v.is_finite()) {
+        true => format!("{formatted}f"),
+        false => formatted,
+    }
+}
+
+fn format_int(value: i32) -> String {
+    match TypeInt(value) {
+        v if v.is_null() => "0N".to_owned(),
+        v if Some(v) == TypeInt::INFINITY => "0W".to_owned(),
+        v if Some(v) == TypeInt::NEGATIVE_INFINITY => "-0W".to_owned(),
+        v => v.0.to_string(),
+    }
+}
+
+// Dates print as yyyy.mm.dd, a list of nulls and infinities only gets a trailing d
+fn format_dates(values: &[TypeDate]) -> String {
+    let formatted = values
+        .iter()
+        .map(|&v| match v {
+            v if v.is_null() => "0N".to_owned(),
+            v if Some(v) == TypeDate::INFINITY => "0W".to_owned(),
+            v if Some(v) == TypeDate::NEGATIVE_INFINITY => "-0W".to_owned(),
+            v => {
+                let (year, month, day) = v.ymd();
+                format!("{year:04}.{month:02}.{day:02}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    match values.iter().all(|v| v.0 <= -i32::MAX || v.0 == i32::MAX) {
+        true => format!("{formatted}d"),
+        false => formatted,
+    }
+}
+
+impl SuperType {
+    /// Short name of the type, as used by casts and to print empty lists.
+    pub fn type_name(&self) -> &'static str {
+        match self.type_id().abs() {
+            0 => "",
+            1 => "boolean",
+            4 => "byte",
+            6 => "int",
+            9 => "float",
+            10 => "char",
+            11 => "symbol",
+            14 => "date",
+            98 => "table",
+            99 => "dictionary",
+            _ => "function",
+        }
+    }
+
+    // How an item shows inside a table or a dictionary: without its type decorations
+    fn fmt_cell(&self) -> String {
+        match self {
+            SuperType::Bool(b) => (b.data().0 as u8).to_string(),
+            // nulls show as blanks
+            SuperType::Int(i) if i.data().is_null() => String::new(),
+            SuperType::Int(i) => format_int(i.data().0),
+            SuperType::Float(f) if f.data().is_null() => String::new(),
+            SuperType::Float(f) if f.data().0.is_infinite() => format_floats(&[f.data().0]),
+            SuperType::Float(f) => f.data().0.to_string(),
+            SuperType::Char(c) => String::from_utf8_lossy(&[c.data().0]).into_owned(),
+            SuperType::Chars(chars) => chars.text(),
+            SuperType::Symbol(s) => s.data().0.clone(),
+            SuperType::Date(d) if d.data().is_null() => String::new(),
+            SuperType::Date(d) => format_dates(&[*d.data()]),
+            other => other.to_string(),
+        }
+    }
+}
+
+fn fmt_table(f: &mut Formatter<'_>, keys: Option<&TypeTable>, table: &TypeTable) -> Result {
+    let columns: Vec<&TypeTable> = keys.into_iter().chain(std::iter::once(table)).collect();
+    let mut headers = Vec::new();
+    let mut cells = Vec::new();
+    for t in &columns {
+        for (name, data) in t.columns().iter().zip(t.data()) {
+            let column: Vec<String> = (0..data.count())
+                .map(|i| data.item(i).map(|item| item.fmt_cell()).unwrap_or_default())
+                .collect();
+            let width = column
+                .iter()
+                .map(|c| c.chars().count())
+                .fold(name.len(), usize::max);
+            headers.push((name.clone(), width));
+            cells.push(column);
+        }
+    }
+
+    let key_count = keys.map_or(0, |k| k.columns().len());
+    let write_line = |f: &mut Formatter<'_>, line: Vec<String>| -> Result {
+        let mut out = String::new();
+        for (i, cell) in line.iter().enumerate() {
+            if i > 0 {
+                out.push_str(if i == key_count { "| " } else { " " });
+            }
+            out.push_str(&format!("{:width$}", cell, width = headers[i].1));
+        }
+        writeln!(f, "{}", out.trim_end())
+    };
+
+    write_line(f, headers.iter().map(|(name, _)| name.clone()).collect())?;
+    let separator = headers
+        .iter()
+        .enumerate()
+        .map(|(i, (_, width))| match i {
+            0 => "-".repeat(*width),
+            i if i == key_count => format!("| {}", "-".repeat(*width)),
+            _ => "-".repeat(width + 1),
+        })
+        .collect::<String>();
+    writeln!(f, "{separator}")?;
+
+    let rows = table.row_count();
+    for row in 0..rows {
+        write_line(f, cells.iter().map(|c| c[row].clone()).collect())?;
+    }
+    Ok(())
+}
+
+fn fmt_dictionary(f: &mut Formatter<'_>, dictionary: &TypeDictionary) -> Result {
+    if let (SuperType::Table(keys), SuperType::Table(values)) =
+        (dictionary.keys(), dictionary.values())
+    {
+        return fmt_table(f, Some(keys), values);
+    }
+
+    let count = dictionary.keys().count();
+    let keys: Vec<String> = (0..count)
+        .map(|i| {
+            dictionary
+                .keys()
+                .item(i)
+                .map(|k| k.fmt_cell())
+                .unwrap_or_default()
+        })
+        .collect();
+    let width = keys.iter().map(|k| k.chars().count()).max().unwrap_or(0);
+    for (i, key) in keys.iter().enumerate() {
+        let value = dictionary.values().item(i).unwrap_or(SuperType::Nothing);
+        writeln!(f, "{key:width$}| {value}")?;
+    }
+    Ok(())
+}
+
+impl Display for SuperType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        if !self.is_atom() && self.type_id() > 0 && self.type_id() < 20 {
+            match self.count() {
+                0 if self.type_id() == 10 => return write!(f, "\"\""),
+                0 => return write!(f, "`{}$()", self.type_name()),
+                1 => write!(f, ",")?,
+                _ => {}
+            }
+        }
+
+        match self {
+            SuperType::Nothing => Ok(()),
+            SuperType::MixedList(list) => {
+                let items: Vec<String> = list.0.iter().map(|item| item.to_string()).collect();
+                match items.len() {
+                    1 => write!(f, ",{}", items[0]),
+                    _ => write!(f, "({})", items.join(";")),
+                }
+            }
+            SuperType::Bool(b) => write!(f, "{}b", b.data().0 as u8),
+            SuperType::Bools(bools) => {
+                let bits: String = bools.iter().map(|b| if b { '1' } else { '0' }).collect();
+                write!(f, "{bits}b")
+            }
+            SuperType::Byte(b) => write!(f, "0x{:02x}", b.data().0),
+            SuperType::Bytes(bytes) => {
+                let hex: String = bytes
+                    .data()
+                    .iter()
+                    .map(|b| format!("{:02x}", b.0))
+                    .collect();
+                write!(f, "0x{hex}")
+            }
+            SuperType::Int(i) => write!(f, "{}", format_int(i.data().0)),
+            SuperType::Ints(ints) => {
+                let items: Vec<String> = ints.data().iter().map(|i| format_int(i.0)).collect();
+                write!(f, "{}", items.join(" "))
+            }
+            SuperType::Float(x) => write!(f, "{}", format_floats(&[x.data().0])),
+            SuperType::Floats(floats) => {
+                let values: Vec<f64> = floats.data().iter().map(|x| x.0).collect();
+                write!(f, "{}", format_floats(&values))
+            }
+            SuperType::Char(c) => write!(f, "{:?}", String::from_utf8_lossy(&[c.data().0])),
+            SuperType::Chars(chars) => write!(f, "{:?}", chars.text()),
+            SuperType::Symbol(s) => write!(f, "`{}", s.data().0),
+            SuperType::Symbols(symbols) => {
+                for s in symbols.data() {
+                    write!(f, "`{}", s.0)?;
+                }
+                Ok(())
+            }
+            SuperType::Date(d) => write!(f, "{}", format_dates(&[*d.data()])),
+            SuperType::Dates(dates) => write!(f, "{}", format_dates(dates.data())),
+            SuperType::Table(table) => fmt_table(f, None, table),
+            SuperType::Dictionary(dictionary) => fmt_dictionary(f, dictionary),
+            // the source is not kept, only the parameters are shown
+            SuperType::Lambda(lambda) => write!(f, "{{[{}] ..}}", lambda.params.join(";")),
+            SuperType::UnaryOperation(unary) => write!(f, "{}", unary.0.name()),
+            SuperType::BinaryOperation(op) => write!(f, "{}", op.0.symbol()),
+            SuperType::Function(function) => write!(f, "{}", function.0.name()),
+            SuperType::Regex(regex) => write!(f, "regex {:?}", regex.0.as_str()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lang::type_system::{Atom, List};
+
+    #[test]
+    fn test_display_lists() {
+        assert_eq!(SuperType::from(vec![1, 2, 3]).to_string(), "1 2 3");
+        assert_eq!(SuperType::from(vec![1.0, 2.5]).to_string(), "1 2.5");
+        assert_eq!(SuperType::from(vec![1.0, 2.0]).to_string(), "1 2f");
+        assert_eq!(SuperType::from(vec![true, false]).to_string(), "10b");
+        assert_eq!(SuperType::from(vec![7]).to_string(), ",7");
+        assert_eq!(SuperType::from(Vec::<i32>::new()).to_string(), "`int$()");
+    }
+
+    #[test]
+    fn test_display_nulls_and_infinities() {
+        let ints = SuperType::from(vec![i32::MIN, i32::MAX, -i32::MAX, 1]);
+        assert_eq!(ints.to_string(), "0N 0W -0W 1");
+        let floats = SuperType::from(vec![f64::NAN, f64::INFINITY, f64::NEG_INFINITY, 1.5]);
+        assert_eq!(floats.to_string(), "0n 0w -0w 1.5");
+        let dates = SuperType::from(List::new(vec![TypeDate(0), TypeDate(-1), TypeDate::NULL]));
+        assert_eq!(dates.to_string(), "2000.01.01 1999.12.31 0N");
+        assert_eq!(
+            SuperType::from(Atom::new(TypeDate::NULL)).to_string(),
+            "0Nd"
+        );
+    }
+
+    #[test]
+    fn test_display_table() {
+        let table = TypeTable::new(
+            vec!["sym".to_owned(), "px".to_owned()],
+            vec![
+                SuperType::symbols(vec!["a".to_owned(), "bb".to_owned()]),
+                SuperType::from(vec![1.5, 20.0]),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            SuperType::Table(table).to_string(),
+            "sym px\n-------\na   1.5\nbb  20\n"
+        );
+    }
+}
@@ -0,0 +1,69 @@
+use std::{collections::HashMap, ops::Range};
+
+use crate::lang::type_system::Key;
+
+/// Lookup structure kept alongside a list with the unique, grouped or parted attribute.
+#[derive(Debug)]
+pub enum Index {
+    /// The position of each item
+    Unique(HashMap<Key, usize>),
+    /// The positions of each distinct item, in order
+    Grouped(HashMap<Key, Vec<usize>>),
+    /// The run of each distinct item
+    Parted(HashMap<Key, Range<usize>>),
+}
+
+impl Index {
+    /// None when an item appears twice.
+    pub fn unique(keys: Vec<Key>) -> Option<Index> {
+        let mut positions = HashMap::with_capacity(keys.len());
+        for (i, key) in keys.into_iter().enumerate() {
+            if positions.insert(key, i).is_some() {
+                return None;
+            }
+        }
+        Some(Index::Unique(positions))
+    }
+
+    pub fn grouped(keys: Vec<Key>) -> Index {
+        let mut groups: HashMap<Key, Vec<usize>> = HashMap::new();
+        for (i, key) in keys.into_iter().enumerate() {
+            groups.entry(key).or_default().push(i);
+        }
+        Index::Grouped(groups)
+    }
+
+    /// None when an item appears again after its run ended.
+    pub fn parted(keys: Vec<Key>) -> Option<Index> {
+        let mut parts: HashMap<Key, Range<usize>> = HashMap::new();
+        let mut start = 0;
+        for i in 1..=keys.len() {
+            if i < keys.len() && keys[i] == keys[start] {
+                continue;
+            }
+            if parts.insert(keys[start].clone(), start..i).is_some() {
+                return None;
+            }
+            start = i;
+        }
+        Some(Index::Parted(parts))
+    }
+
+    /// The first position of `key`.
+    pub fn first(&self, key: &Key) -> Option<usize> {
+        match self {
+            Index::Unique(positions) => positions.get(key).copied(),
+            Index::Grouped(groups) => groups.get(key).and_then(|rows| rows.first().copied()),
+            Index::Parted(parts) => parts.get(key).map(|part| part.start),
+        }
+    }
+
+    /// The positions of `key`, in order.
+    pub fn positions(&self, key: &Key) -> Vec<usize> {
+        match self {
+            Index::Unique(positions) => positions.get(key).copied().into_iter().collect(),
+            Index::Grouped(groups) => groups.get(key).cloned().unwrap_or_default(),
+            Index::Parted(parts) => parts.get(key).cloned().map_or(Vec::new(), Vec::from_iter),
+        }
+    }
+}
@@ -1,13 +1,23 @@
 mod type_bool;
 mod type_byte;
 mod type_char;
+mod type_date;
+mod type_dictionary;
 mod type_float;
+mod type_function;
 mod type_int;
+mod type_lambda;
 mod type_mixed_list;
 mod type_operator;
-// mod type_unary;
+mod type_regex;
+mod type_symbol;
+mod type_table;
+mod type_unary;
 
 pub use self::{
-    type_bool::TypeBool, type_byte::TypeByte, type_char::TypeChar, type_float::TypeFloat,
-    type_int::TypeInt, type_mixed_list::TypeMixedList, type_operator::TypeOperator,
+    type_bool::TypeBool, type_byte::TypeByte, type_char::TypeChar, type_date::TypeDate,
+    type_dictionary::TypeDictionary, type_float::TypeFloat, type_function::TypeFunction,
+    type_int::TypeInt, type_lambda::TypeLambda, type_mixed_list::TypeMixedList,
+    type_operator::TypeOperator, type_regex::TypeRegex, type_symbol::TypeSymbol,
+    type_table::TypeTable, type_unary::TypeUnary,
 };
@@ -1,7 +1,19 @@
 use super::TypeInt;
-use cupid_macros::{InnerType, InnerTypeArithmetic, InnerTypeEquals};
+use cupid_macros::{InnerType, InnerTypeArithmetic, InnerTypeEquals, InnerTypeNull};
 
-#[derive(Clone, Debug, Copy, PartialEq, Eq, InnerType, InnerTypeArithmetic, InnerTypeEquals)]
+#[derive(
+    Clone,
+    Debug,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    InnerType,
+    InnerTypeArithmetic,
+    InnerTypeEquals,
+    InnerTypeNull,
+)]
 #[cupid_type_id(-1)]
+#[cupid_fill(false)]
 #[cupid_arithmetic_promote(TypeInt, i32)]
 pub struct TypeBool(pub bool);
@@ -1,7 +1,19 @@
-use cupid_macros::{InnerType, InnerTypeArithmetic, InnerTypeEquals};
 use super::TypeInt;
+use cupid_macros::{InnerType, InnerTypeArithmetic, InnerTypeEquals, InnerTypeNull};
 
-#[derive(Clone, Debug, Copy, PartialEq, Eq, InnerType, InnerTypeArithmetic, InnerTypeEquals)]
+#[derive(
+    Clone,
+    Debug,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    InnerType,
+    InnerTypeArithmetic,
+    InnerTypeEquals,
+    InnerTypeNull,
+)]
 #[cupid_type_id(-4)]
+#[cupid_fill(0)]
 #[cupid_arithmetic_promote(TypeInt, i32)]
 pub struct TypeByte(pub u8);
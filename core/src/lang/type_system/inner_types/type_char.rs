@@ -1,5 +1,39 @@
-use cupid_macros::{InnerType, InnerTypeEquals};
+use cupid_macros::{InnerType, InnerTypeEquals, InnerTypeNull};
 
-#[derive(Clone, Debug, Copy, PartialEq, Eq, InnerType, InnerTypeEquals)]
+use crate::lang::type_system::List;
+
+/// A char is a byte as in q, strings hold the UTF-8 encoding of their text.
+#[derive(
+    Clone,
+    Debug,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    InnerType,
+    InnerTypeEquals,
+    InnerTypeNull,
+)]
 #[cupid_type_id(-10)]
-pub struct TypeChar(pub char);
+#[cupid_null(b' ')]
+pub struct TypeChar(pub u8);
+
+impl List<TypeChar> {
+    /// The bytes of the string.
+    pub fn bytes(&self) -> Vec<u8> {
+        self.data().iter().map(|c| c.0).collect()
+    }
+
+    /// The text of the string, bytes that are not UTF-8 showing as replacement characters.
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.bytes()).into_owned()
+    }
+}
+
+impl From<&str> for List<TypeChar> {
+    fn from(text: &str) -> Self {
+        List::new(text.bytes().map(TypeChar).collect())
+    }
+}
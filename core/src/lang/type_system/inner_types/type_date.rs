@@ -0,0 +1,63 @@
+use cupid_macros::{InnerType, InnerTypeEquals, InnerTypeNull};
+
+/// A date as the number of days since 2000.01.01, as q keeps it.
+#[derive(
+    Clone,
+    Debug,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    InnerType,
+    InnerTypeEquals,
+    InnerTypeNull,
+)]
+#[cupid_type_id(-14)]
+#[cupid_null(i32::MIN)]
+#[cupid_infinity(i32::MAX)]
+pub struct TypeDate(pub i32);
+
+// Days from 0000.03.01 to 2000.01.01
+const EPOCH: i64 = 730425;
+
+impl TypeDate {
+    /// The date of a year, month and day of the proleptic Gregorian calendar, None when there is
+    /// no such day.
+    pub fn from_ymd(year: i32, month: u32, day: u32) -> Option<Self> {
+        let days_in_month = match month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+            2 => 28,
+            _ => return None,
+        };
+        if day == 0 || day > days_in_month {
+            return None;
+        }
+        // counted in 400 year eras of years starting in March
+        let year = year as i64 - (month <= 2) as i64;
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let day_of_year = (153 * ((month as i64 + 9) % 12) + 2) / 5 + day as i64 - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        let days = era * 146097 + day_of_era - EPOCH;
+        i32::try_from(days).ok().map(TypeDate)
+    }
+
+    /// The year, month and day of the date.
+    pub fn ymd(&self) -> (i32, u32, u32) {
+        let days = self.0 as i64 + EPOCH;
+        let era = days.div_euclid(146097);
+        let day_of_era = days - era * 146097;
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let month_from_march = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * month_from_march + 2) / 5 + 1) as u32;
+        let month = ((month_from_march + 2) % 12 + 1) as u32;
+        let year = year_of_era + era * 400 + (month <= 2) as i64;
+        (year as i32, month, day)
+    }
+}
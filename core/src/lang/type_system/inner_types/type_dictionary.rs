@@ -0,0 +1,69 @@
+use crate::{
+    Error,
+    lang::type_system::{InnerTypeTrait, SuperType},
+};
+
+/// A mapping from a list of keys to a list of values of the same length.
+/// A keyed table is a dictionary from a table of keys to a table of values.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TypeDictionary {
+    keys: Box<SuperType>,
+    values: Box<SuperType>,
+}
+
+impl TypeDictionary {
+    pub fn new(keys: SuperType, values: SuperType) -> Result<Self, Error> {
+        if keys.is_atom() || values.is_atom() {
+            return Err(Error::Type);
+        }
+        if keys.count() != values.count() {
+            return Err(Error::Length);
+        }
+        Ok(TypeDictionary {
+            keys: Box::new(keys),
+            values: Box::new(values),
+        })
+    }
+
+    pub fn keys(&self) -> &SuperType {
+        &self.keys
+    }
+
+    pub fn values(&self) -> &SuperType {
+        &self.values
+    }
+
+    pub fn into_parts(self) -> (SuperType, SuperType) {
+        (*self.keys, *self.values)
+    }
+
+    pub fn is_keyed_table(&self) -> bool {
+        matches!(
+            (self.keys.as_ref(), self.values.as_ref()),
+            (SuperType::Table(_), SuperType::Table(_))
+        )
+    }
+
+    /// Returns the value mapped to the first occurrence of `key`.
+    pub fn get(&self, key: &SuperType) -> Option<SuperType> {
+        let position = self.keys.position(key)?;
+        self.values.item(position)
+    }
+}
+
+impl InnerTypeTrait for TypeDictionary {
+    fn get_type() -> i16 {
+        99
+    }
+}
+
+impl TryFrom<SuperType> for TypeDictionary {
+    type Error = Error;
+
+    fn try_from(value: SuperType) -> Result<Self, Self::Error> {
+        match value {
+            SuperType::Dictionary(d) => Ok(d),
+            _ => Err(Error::Type),
+        }
+    }
+}
@@ -1,5 +1,17 @@
-use cupid_macros::{InnerType, InnerTypeArithmetic, InnerTypeEquals};
+use cupid_macros::{InnerType, InnerTypeArithmetic, InnerTypeEquals, InnerTypeNull};
 
-#[derive(Clone, Debug, Copy, PartialEq, InnerType, InnerTypeArithmetic, InnerTypeEquals)]
+#[derive(
+    Clone,
+    Debug,
+    Copy,
+    PartialEq,
+    PartialOrd,
+    InnerType,
+    InnerTypeArithmetic,
+    InnerTypeEquals,
+    InnerTypeNull,
+)]
 #[cupid_type_id(-9)]
+#[cupid_null(f64::NAN)]
+#[cupid_infinity(f64::INFINITY)]
 pub struct TypeFloat(pub f64);
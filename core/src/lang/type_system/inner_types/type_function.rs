@@ -0,0 +1,7 @@
+use crate::lang::invokable::function::Function;
+use cupid_macros::InnerType;
+
+// Named builtins of more than one argument are typed like operators
+#[derive(Clone, Debug, Copy, PartialEq, Eq, InnerType)]
+#[cupid_type_id(102)]
+pub struct TypeFunction(pub Function);
@@ -1,5 +1,18 @@
-use cupid_macros::{InnerType, InnerTypeArithmetic, InnerTypeEquals};
+use cupid_macros::{InnerType, InnerTypeArithmetic, InnerTypeEquals, InnerTypeNull};
 
-#[derive(Clone, Debug, Copy, PartialEq, Eq, InnerType, InnerTypeArithmetic, InnerTypeEquals)]
+#[derive(
+    Clone,
+    Debug,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    InnerType,
+    InnerTypeArithmetic,
+    InnerTypeEquals,
+    InnerTypeNull,
+)]
 #[cupid_type_id(-6)]
+#[cupid_null(i32::MIN)]
+#[cupid_infinity(i32::MAX)]
 pub struct TypeInt(pub i32);
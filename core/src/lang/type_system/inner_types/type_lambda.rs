@@ -0,0 +1,9 @@
+use crate::lang::parse_tree::ParseTreeNode;
+
+/// A function written in q, e.g. {[a;b] a+b}. Each call runs its body in a child context where
+/// the parameters are bound to the arguments.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TypeLambda {
+    pub params: Vec<String>,
+    pub body: Vec<ParseTreeNode>,
+}
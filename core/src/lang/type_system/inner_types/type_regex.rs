@@ -0,0 +1,14 @@
+use cupid_macros::InnerType;
+use regex::bytes::Regex;
+
+/// A compiled regular expression, kept in a variable to be matched many times.
+#[derive(Clone, Debug, InnerType)]
+#[cupid_type_id(112)]
+pub struct TypeRegex(pub Regex);
+
+// Patterns compile the same way every time, so their source tells them apart
+impl PartialEq for TypeRegex {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_str() == other.0.as_str()
+    }
+}
@@ -0,0 +1,8 @@
+use cupid_macros::{InnerType, InnerTypeEquals, InnerTypeNull};
+
+#[derive(
+    Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, InnerType, InnerTypeEquals, InnerTypeNull,
+)]
+#[cupid_type_id(-11)]
+#[cupid_null(String::new())]
+pub struct TypeSymbol(pub String);
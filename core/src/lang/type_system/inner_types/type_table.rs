@@ -0,0 +1,98 @@
+use crate::{
+    Error,
+    lang::type_system::{InnerTypeTrait, SuperType, TypeDictionary},
+};
+
+/// A flipped dictionary of column names to lists of the same length.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TypeTable {
+    columns: Vec<String>,
+    data: Vec<SuperType>,
+}
+
+impl TypeTable {
+    pub fn new(columns: Vec<String>, data: Vec<SuperType>) -> Result<Self, Error> {
+        if columns.len() != data.len() {
+            return Err(Error::Length);
+        }
+        if data.iter().any(SuperType::is_atom) {
+            return Err(Error::Type);
+        }
+        if let Some(first) = data.first()
+            && data.iter().any(|column| column.count() != first.count())
+        {
+            return Err(Error::Length);
+        }
+        for (i, name) in columns.iter().enumerate() {
+            if columns[..i].contains(name) {
+                return Err(Error::Name(name.clone()));
+            }
+        }
+        Ok(TypeTable { columns, data })
+    }
+
+    pub fn columns(&self) -> &[String] {
+        &self.columns
+    }
+
+    pub fn data(&self) -> &[SuperType] {
+        &self.data
+    }
+
+    pub fn into_parts(self) -> (Vec<String>, Vec<SuperType>) {
+        (self.columns, self.data)
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.data.first().map_or(0, SuperType::count)
+    }
+
+    pub fn column(&self, name: &str) -> Option<&SuperType> {
+        let position = self.columns.iter().position(|c| c == name)?;
+        Some(&self.data[position])
+    }
+
+    /// Returns a table made of the given rows, in the given order.
+    pub fn select_rows(&self, rows: &[usize]) -> Result<Self, Error> {
+        let data = self
+            .data
+            .iter()
+            .map(|column| column.select(rows))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(TypeTable {
+            columns: self.columns.clone(),
+            data,
+        })
+    }
+
+    /// Returns a row as a dictionary of column names to atoms.
+    pub fn row(&self, index: usize) -> Option<TypeDictionary> {
+        let values = self
+            .data
+            .iter()
+            .map(|column| column.item(index))
+            .collect::<Option<Vec<_>>>()?;
+        TypeDictionary::new(
+            SuperType::symbols(self.columns.clone()),
+            SuperType::from_items(values),
+        )
+        .ok()
+    }
+}
+
+impl InnerTypeTrait for TypeTable {
+    fn get_type() -> i16 {
+        98
+    }
+}
+
+impl TryFrom<SuperType> for TypeTable {
+    type Error = Error;
+
+    fn try_from(value: SuperType) -> Result<Self, Self::Error> {
+        match value {
+            SuperType::Table(t) => Ok(t),
+            _ => Err(Error::Type),
+        }
+    }
+}
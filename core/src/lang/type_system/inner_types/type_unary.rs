@@ -0,0 +1,6 @@
+use crate::lang::invokable::unary::Unary;
+use cupid_macros::InnerType;
+
+#[derive(Clone, Debug, Copy, PartialEq, Eq, InnerType)]
+#[cupid_type_id(101)]
+pub struct TypeUnary(pub Unary);
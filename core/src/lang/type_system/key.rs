@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+
+use crate::lang::type_system::{
+    ListItem, SuperType, TypeBool, TypeByte, TypeChar, TypeDate, TypeFloat, TypeInt, TypeSymbol,
+    with_atom, with_list,
+};
+
+/// A hashable stand-in for a value, used to group items and look them up.
+/// Floats are keyed by their bits so that nulls group together, and -0 is 0.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Key {
+    Bool(bool),
+    Byte(u8),
+    Int(i32),
+    Float(u64),
+    Char(u8),
+    Symbol(String),
+    Date(i32),
+    List(Vec<Key>),
+    Dictionary(Box<Key>, Box<Key>),
+    Other(String), // functions and the generic null, keyed by how they print
+}
+
+fn float_key(value: f64) -> u64 {
+    match value {
+        v if v.is_nan() => f64::NAN.to_bits(),
+        0.0 => 0,
+        v => v.to_bits(),
+    }
+}
+
+impl SuperType {
+    /// The key of the value as a whole.
+    pub fn key(&self) -> Key {
+        with_atom!(self, atom => atom.data().key(), other => match other {
+            SuperType::Dictionary(d) => {
+                Key::Dictionary(Box::new(d.keys().key()), Box::new(d.values().key()))
+            }
+            list if !list.is_atom() => Key::List(list.item_keys()),
+            other => Key::Other(other.to_string()),
+        })
+    }
+
+    /// The keys of the items, rows for a table. An atom is its own single item.
+    pub fn item_keys(&self) -> Vec<Key> {
+        with_list!(self, list => list.data().iter().map(ListItem::key).collect(), other => match other {
+            SuperType::Bools(bits) => bits.iter().map(Key::Bool).collect(),
+            SuperType::MixedList(l) => l.0.iter().map(SuperType::key).collect(),
+            SuperType::Table(table) => {
+                let columns: Vec<&SuperType> = table.data().iter().collect();
+                row_keys(&columns)
+            }
+            SuperType::Dictionary(d) => d.values().item_keys(),
+            atom => vec![atom.key()],
+        })
+    }
+}
+
+impl ListItem for TypeBool {
+    fn key(&self) -> Key {
+        Key::Bool(self.0)
+    }
+}
+
+impl ListItem for TypeByte {
+    fn key(&self) -> Key {
+        Key::Byte(self.0)
+    }
+}
+
+impl ListItem for TypeInt {
+    fn key(&self) -> Key {
+        Key::Int(self.0)
+    }
+}
+
+impl ListItem for TypeFloat {
+    fn key(&self) -> Key {
+        Key::Float(float_key(self.0))
+    }
+}
+
+impl ListItem for TypeChar {
+    fn key(&self) -> Key {
+        Key::Char(self.0)
+    }
+}
+
+impl ListItem for TypeSymbol {
+    fn key(&self) -> Key {
+        Key::Symbol(self.0.clone())
+    }
+}
+
+impl ListItem for TypeDate {
+    fn key(&self) -> Key {
+        Key::Date(self.0)
+    }
+}
+
+/// The positions of equal keys, groups in order of first appearance.
+#[derive(Debug, Default)]
+pub struct Grouping {
+    groups: HashMap<Key, usize>,
+    positions: Vec<Vec<usize>>,
+}
+
+impl Grouping {
+    pub fn new(keys: Vec<Key>) -> Self {
+        let mut grouping = Grouping::default();
+        for (position, key) in keys.into_iter().enumerate() {
+            let next = grouping.positions.len();
+            let group = *grouping.groups.entry(key).or_insert(next);
+            match grouping.positions.get_mut(group) {
+                Some(positions) => positions.push(position),
+                None => grouping.positions.push(vec![position]),
+            }
+        }
+        grouping
+    }
+
+    /// The positions of each group.
+    pub fn positions(&self) -> &[Vec<usize>] {
+        &self.positions
+    }
+
+    pub fn into_positions(self) -> Vec<Vec<usize>> {
+        self.positions
+    }
+
+    /// The first position of each group.
+    pub fn firsts(&self) -> Vec<usize> {
+        self.positions
+            .iter()
+            .map(|positions| positions[0])
+            .collect()
+    }
+
+    /// The first position of `key`.
+    pub fn first(&self, key: &Key) -> Option<usize> {
+        self.groups.get(key).map(|&group| self.positions[group][0])
+    }
+}
+
+impl SuperType {
+    /// Groups the items, or the rows of a table, by value.
+    pub fn grouping(&self) -> Grouping {
+        Grouping::new(self.item_keys())
+    }
+}
+
+/// One key per row of the given columns, which must all be as long.
+pub fn row_keys(columns: &[&SuperType]) -> Vec<Key> {
+    match columns {
+        [column] => column.item_keys(),
+        columns => {
+            let keys: Vec<Vec<Key>> = columns.iter().map(|c| c.item_keys()).collect();
+            let count = keys.first().map_or(0, Vec::len);
+            (0..count)
+                .map(|row| Key::List(keys.iter().map(|k| k[row].clone()).collect()))
+                .collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_float_nulls_share_a_key() {
+        let floats = SuperType::from(vec![f64::NAN, -0.0, 0.0, f64::NAN]);
+        let keys = floats.item_keys();
+        assert_eq!(keys[0], keys[3]);
+        assert_eq!(keys[1], keys[2]);
+        assert_ne!(keys[0], keys[1]);
+    }
+
+    #[test]
+    fn test_grouping_keeps_first_appearance() {
+        let grouping = SuperType::from(vec![3, 1, 3, 2, 1]).grouping();
+        assert_eq!(grouping.positions(), &[vec![0, 2], vec![1, 4], vec![3]]);
+        assert_eq!(grouping.firsts(), vec![0, 1, 3]);
+        assert_eq!(grouping.first(&Key::Int(2)), Some(3));
+        assert_eq!(grouping.first(&Key::Int(5)), None);
+    }
+
+    #[test]
+    fn test_row_keys_of_a_table() {
+        let syms = SuperType::symbols(vec!["a".to_owned(), "a".to_owned()]);
+        let ints = SuperType::from(vec![1, 1]);
+        let keys = row_keys(&[&syms, &ints]);
+        assert_eq!(keys[0], keys[1]);
+        assert_eq!(
+            keys[0],
+            Key::List(vec![Key::Symbol("a".to_owned()), Key::Int(1)])
+        );
+    }
+}
@@ -1,17 +1,20 @@
+use std::{cmp::Ordering, sync::Arc};
+
 use crate::{
     Error,
     lang::{
         invokable::operator,
-        type_system::{InnerTypeTrait, TypeTrait},
+        type_system::{Index, InnerTypeTrait, ListItem, TypeTrait},
     },
 };
 
 use super::Attribute;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 pub struct List<T: InnerTypeTrait> {
     data: Vec<T>,
     attributes: u8,
+    index: Option<Arc<Index>>, // built by the unique, grouped and parted attributes
 }
 
 impl<T: InnerTypeTrait> List<T> {
@@ -19,15 +22,44 @@ impl<T: InnerTypeTrait> List<T> {
         List {
             data,
             attributes: Attribute::None as u8,
+            index: None,
         }
     }
 
     pub fn data(&self) -> &Vec<T> {
         &self.data
     }
+
+    /// Gives write access to the items, which drops the attributes since they may not hold anymore.
+    pub fn data_mut(&mut self) -> &mut Vec<T> {
+        self.attributes = Attribute::None as u8;
+        self.index = None;
+        &mut self.data
+    }
+
+    pub fn into_data(self) -> Vec<T> {
+        self.data
+    }
+
+    pub fn index(&self) -> Option<&Index> {
+        self.index.as_deref()
+    }
+
+    /// Marks the list sorted without checking, for results that are sorted by construction.
+    pub(crate) fn assume_sorted(&mut self) {
+        self.attributes = Attribute::Sorted as u8;
+        self.index = None;
+    }
+}
+
+// Attributes only speed operations up, lists with the same items match whatever their attributes
+impl<T: InnerTypeTrait + PartialEq> PartialEq for List<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.data == other.data
+    }
 }
 
-impl<T: InnerTypeTrait> TypeTrait for List<T> {
+impl<T: ListItem> TypeTrait for List<T> {
     fn count(&self) -> usize {
         self.data.len()
     }
@@ -42,12 +74,36 @@ impl<T: InnerTypeTrait> TypeTrait for List<T> {
     }
 
     fn set_attribute(&mut self, attribute: Attribute) -> Result<(), Error> {
-        // TODO: validate if the attribute can be applied
+        let index = attribute_index(&self.data, &attribute)?;
         self.attributes = attribute as u8;
+        self.index = index.map(Arc::new);
         Ok(())
     }
 }
 
+/// Checks that `attribute` holds for the items and builds the index it keeps, if any.
+pub(super) fn attribute_index<T: ListItem>(
+    data: &[T],
+    attribute: &Attribute,
+) -> Result<Option<Index>, Error> {
+    let keys = || data.iter().map(ListItem::key).collect();
+    Ok(match attribute {
+        Attribute::None => None,
+        Attribute::Sorted => {
+            let sorted = data
+                .windows(2)
+                .all(|pair| pair[0].order(&pair[1]) != Ordering::Greater);
+            if !sorted {
+                return Err(Error::AttributeFail("s"));
+            }
+            None
+        }
+        Attribute::Unique => Some(Index::unique(keys()).ok_or(Error::AttributeFail("u"))?),
+        Attribute::Parted => Some(Index::parted(keys()).ok_or(Error::AttributeFail("p"))?),
+        Attribute::Grouped => Some(Index::grouped(keys())),
+    })
+}
+
 impl<T: InnerTypeTrait> IntoIterator for List<T> {
     type Item = T;
     type IntoIter = std::vec::IntoIter<T>;
@@ -63,10 +119,10 @@ macro_rules! impl_list_operator {
             operator::$trait_name<&List<T>, List<T>> for &List<T>
         {
             fn $method_name(lhs: &List<T>, rhs: &List<T>) -> Result<List<T>, Error> {
-                if lhs.count() != rhs.count() {
+                if lhs.data.len() != rhs.data.len() {
                     return Err(Error::Length);
                 }
-                let mut data = Vec::with_capacity(lhs.count());
+                let mut data = Vec::with_capacity(lhs.data.len());
                 for (l, r) in lhs.data.iter().zip(rhs.data.iter()) {
                     data.push(T::$method_name(l, r)?);
                 }
@@ -85,6 +141,7 @@ impl_list_operator!(OperatorDivide, div);
 mod tests {
     use super::*;
     use crate::lang::invokable::operator::OperatorAdd;
+    use crate::lang::type_system::ListItem;
     use crate::lang::type_system::inner_types::TypeInt;
 
     #[test]
@@ -99,4 +156,52 @@ mod tests {
         let expected_list = List::new(vec![TypeInt::from(5), TypeInt::from(7), TypeInt::from(9)]);
         assert_eq!(result_list, expected_list);
     }
+
+    #[test]
+    fn test_set_attribute_validates() {
+        let mut sorted = List::<TypeInt>::from(vec![1, 2, 2, 5]);
+        assert!(sorted.set_attribute(Attribute::Sorted).is_ok());
+        assert!(Attribute::has_sorted(sorted.get_attributes()));
+        assert_eq!(
+            sorted.set_attribute(Attribute::Unique),
+            Err(Error::AttributeFail("u"))
+        );
+
+        let mut unsorted = List::<TypeInt>::from(vec![3, 1, 2]);
+        assert_eq!(
+            unsorted.set_attribute(Attribute::Sorted),
+            Err(Error::AttributeFail("s"))
+        );
+        assert!(unsorted.set_attribute(Attribute::Unique).is_ok());
+        assert_eq!(
+            unsorted.index().unwrap().positions(&TypeInt(2).key()),
+            vec![2]
+        );
+
+        // writing to the items drops the attribute and its index
+        unsorted.data_mut().push(TypeInt(1));
+        assert_eq!(unsorted.get_attributes(), Attribute::None as u8);
+        assert!(unsorted.index().is_none());
+    }
+
+    #[test]
+    fn test_parted_and_grouped_indexes() {
+        let mut runs = List::<TypeInt>::from(vec![7, 7, 3, 3, 3, 9]);
+        assert!(runs.set_attribute(Attribute::Parted).is_ok());
+        assert_eq!(
+            runs.index().unwrap().positions(&TypeInt(3).key()),
+            vec![2, 3, 4]
+        );
+
+        let mut scattered = List::<TypeInt>::from(vec![7, 3, 7]);
+        assert_eq!(
+            scattered.set_attribute(Attribute::Parted),
+            Err(Error::AttributeFail("p"))
+        );
+        assert!(scattered.set_attribute(Attribute::Grouped).is_ok());
+        assert_eq!(
+            scattered.index().unwrap().positions(&TypeInt(7).key()),
+            vec![0, 2]
+        );
+    }
 }
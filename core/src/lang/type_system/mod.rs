@@ -1,14 +1,25 @@
 mod atom;
+mod bits;
+mod display;
+mod index;
 mod inner_types;
+mod key;
 mod list;
 mod super_type;
 
 pub use atom::Atom;
+pub use bits::Bits;
+pub use index::Index;
 pub use inner_types::{
-    TypeBool, TypeByte, TypeChar, TypeFloat, TypeInt, TypeMixedList, TypeOperator,
+    TypeBool, TypeByte, TypeChar, TypeDate, TypeDictionary, TypeFloat, TypeFunction, TypeInt,
+    TypeLambda, TypeMixedList, TypeOperator, TypeRegex, TypeSymbol, TypeTable, TypeUnary,
 };
+pub use key::{Grouping, Key, row_keys};
 pub use list::List;
 pub use super_type::SuperType;
+pub(crate) use super_type::{with_atom, with_list};
+
+use std::cmp::Ordering;
 
 use crate::Error;
 
@@ -38,6 +49,19 @@ impl Attribute {
     pub fn has_grouped(v: u8) -> bool {
         (v & Attribute::Grouped as u8) != 0
     }
+
+    /// The attribute named by `s, `u, `p or `g, the empty symbol is no attribute.
+    pub fn from_name(name: &str) -> Option<Attribute> {
+        let attribute = match name {
+            "" => Attribute::None,
+            "s" => Attribute::Sorted,
+            "u" => Attribute::Unique,
+            "p" => Attribute::Parted,
+            "g" => Attribute::Grouped,
+            _ => return None,
+        };
+        Some(attribute)
+    }
 }
 
 pub trait TypeTrait {
@@ -54,3 +78,24 @@ pub trait TypeTrait {
 pub trait InnerTypeTrait {
     fn get_type() -> i16;
 }
+
+/// Typed nulls and infinities: 0N and 0W for ints, 0n and 0w for floats, 0Nd and 0Wd for dates,
+/// ` for symbols and " " for chars. Bools and bytes have no null, missing items are filled with their zero.
+pub trait Nullable: Sized {
+    const NULL: Self;
+    const INFINITY: Option<Self>;
+    const NEGATIVE_INFINITY: Option<Self>;
+
+    fn is_null(&self) -> bool;
+}
+
+/// The items of typed lists, ordered and hashable so that attributes can be checked and indexed.
+pub trait ListItem: InnerTypeTrait + Nullable + Clone + PartialOrd {
+    fn key(&self) -> Key;
+
+    // Nulls are the only items without an order, they come first
+    fn order(&self, other: &Self) -> Ordering {
+        self.partial_cmp(other)
+            .unwrap_or_else(|| self.eq(self).cmp(&other.eq(other)))
+    }
+}
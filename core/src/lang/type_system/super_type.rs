@@ -1,6 +1,13 @@
-use crate::lang::type_system::{
-    Atom, List,
-    inner_types::{TypeBool, TypeByte, TypeChar, TypeFloat, TypeInt, TypeMixedList, TypeOperator},
+use crate::{
+    Error,
+    lang::type_system::{
+        Atom, Bits, InnerTypeTrait, List, Nullable, TypeTrait,
+        inner_types::{
+            TypeBool, TypeByte, TypeChar, TypeDate, TypeDictionary, TypeFloat, TypeFunction,
+            TypeInt, TypeLambda, TypeMixedList, TypeOperator, TypeRegex, TypeSymbol, TypeTable,
+            TypeUnary,
+        },
+    },
 };
 
 #[derive(Clone, Debug, PartialEq)]
@@ -9,7 +16,7 @@ pub enum SuperType {
     MixedList(TypeMixedList), // Mixed list is sort of special so it deserves an additional wrapper
 
     Bool(Atom<TypeBool>),
-    Bools(List<TypeBool>),
+    Bools(Bits),
 
     Byte(Atom<TypeByte>),
     Bytes(List<TypeByte>),
@@ -23,11 +30,277 @@ pub enum SuperType {
     Char(Atom<TypeChar>),
     Chars(List<TypeChar>),
 
-    Table,
-    Dictionary,
-    Lambda,
-    UnaryOperation,
+    Symbol(Atom<TypeSymbol>),
+    Symbols(List<TypeSymbol>),
+
+    Date(Atom<TypeDate>),
+    Dates(List<TypeDate>),
+
+    Table(TypeTable),
+    Dictionary(TypeDictionary),
+    Lambda(TypeLambda),
+    UnaryOperation(TypeUnary),
     BinaryOperation(TypeOperator),
+    Function(TypeFunction),
+    Regex(TypeRegex),
+}
+
+/// Matches every homogeneous list variant, binding the inner `List<T>` to `$list`.
+/// Anything else is matched by `$other`, packed bools included.
+macro_rules! with_list {
+    ($value:expr, $list:ident => $body:expr, $other:pat => $fallback:expr) => {
+        match $value {
+            $crate::lang::type_system::SuperType::Bytes($list) => $body,
+            $crate::lang::type_system::SuperType::Ints($list) => $body,
+            $crate::lang::type_system::SuperType::Floats($list) => $body,
+            $crate::lang::type_system::SuperType::Chars($list) => $body,
+            $crate::lang::type_system::SuperType::Symbols($list) => $body,
+            $crate::lang::type_system::SuperType::Dates($list) => $body,
+            $other => $fallback,
+        }
+    };
+}
+
+/// Matches every atom variant, binding the inner `Atom<T>` to `$atom`.
+/// Anything else is matched by `$other`.
+macro_rules! with_atom {
+    ($value:expr, $atom:ident => $body:expr, $other:pat => $fallback:expr) => {
+        match $value {
+            $crate::lang::type_system::SuperType::Bool($atom) => $body,
+            $crate::lang::type_system::SuperType::Byte($atom) => $body,
+            $crate::lang::type_system::SuperType::Int($atom) => $body,
+            $crate::lang::type_system::SuperType::Float($atom) => $body,
+            $crate::lang::type_system::SuperType::Char($atom) => $body,
+            $crate::lang::type_system::SuperType::Symbol($atom) => $body,
+            $crate::lang::type_system::SuperType::Date($atom) => $body,
+            $other => $fallback,
+        }
+    };
+}
+
+pub(crate) use {with_atom, with_list};
+
+macro_rules! impl_from_inner_type {
+    ($inner:ty, $atom_variant:ident, $list_variant:ident) => {
+        impl From<Atom<$inner>> for SuperType {
+            fn from(value: Atom<$inner>) -> Self {
+                SuperType::$atom_variant(value)
+            }
+        }
+
+        impl From<List<$inner>> for SuperType {
+            fn from(values: List<$inner>) -> Self {
+                SuperType::$list_variant(values)
+            }
+        }
+    };
+}
+
+impl From<Atom<TypeBool>> for SuperType {
+    fn from(value: Atom<TypeBool>) -> Self {
+        SuperType::Bool(value)
+    }
+}
+
+// Boolean lists are packed into bits
+impl From<List<TypeBool>> for SuperType {
+    fn from(values: List<TypeBool>) -> Self {
+        SuperType::Bools(values.into())
+    }
+}
+
+impl From<Bits> for SuperType {
+    fn from(values: Bits) -> Self {
+        SuperType::Bools(values)
+    }
+}
+
+impl_from_inner_type!(TypeByte, Byte, Bytes);
+impl_from_inner_type!(TypeInt, Int, Ints);
+impl_from_inner_type!(TypeFloat, Float, Floats);
+impl_from_inner_type!(TypeChar, Char, Chars);
+impl_from_inner_type!(TypeSymbol, Symbol, Symbols);
+impl_from_inner_type!(TypeDate, Date, Dates);
+
+// Collapses a vector of atoms into the typed list of the first listed variant they all share
+macro_rules! collapse_atoms {
+    ($items:ident, $($atom_variant:ident => $list_variant:ident),*) => {
+        match $items.first() {
+            $(
+                Some(SuperType::$atom_variant(_))
+                    if $items.iter().all(|item| matches!(item, SuperType::$atom_variant(_))) =>
+                {
+                    SuperType::from(List::new(
+                        $items
+                            .into_iter()
+                            .map(|item| match item {
+                                SuperType::$atom_variant(atom) => atom.into_data(),
+                                _ => unreachable!(),
+                            })
+                            .collect(),
+                    ))
+                }
+            )*
+            _ => SuperType::MixedList(TypeMixedList($items)),
+        }
+    };
+}
+
+fn type_of<T: TypeTrait>(_: &T) -> i16 {
+    T::get_type()
+}
+
+fn gather<T: InnerTypeTrait + Clone>(list: &List<T>, indices: &[usize]) -> Option<List<T>> {
+    indices
+        .iter()
+        .map(|&i| list.data().get(i).cloned())
+        .collect::<Option<Vec<_>>>()
+        .map(List::new)
+}
+
+impl SuperType {
+    pub fn symbol(name: impl Into<String>) -> Self {
+        SuperType::Symbol(TypeSymbol(name.into()).into())
+    }
+
+    pub fn symbols(names: Vec<String>) -> Self {
+        SuperType::Symbols(names.into())
+    }
+
+    /// A char atom, chars being bytes.
+    pub fn char(c: u8) -> Self {
+        SuperType::Char(Atom::new(TypeChar(c)))
+    }
+
+    /// Returns the q type number: negative for atoms, positive for lists, 98 for tables...
+    pub fn type_id(&self) -> i16 {
+        with_atom!(self, atom => type_of(atom), other => with_list!(other, list => type_of(list), other => match other {
+            SuperType::Bools(bits) => type_of(bits),
+            SuperType::MixedList(_) => TypeMixedList::get_type(),
+            SuperType::Table(_) => TypeTable::get_type(),
+            SuperType::Dictionary(_) => TypeDictionary::get_type(),
+            SuperType::Lambda(_) => 100,
+            SuperType::Nothing | SuperType::UnaryOperation(_) => TypeUnary::get_type(),
+            SuperType::BinaryOperation(_) => TypeOperator::get_type(),
+            SuperType::Function(_) => TypeFunction::get_type(),
+            SuperType::Regex(_) => TypeRegex::get_type(),
+            _ => unreachable!(),
+        }))
+    }
+
+    /// Atoms, functions and the generic null are not lists
+    pub fn is_atom(&self) -> bool {
+        let type_id = self.type_id();
+        !(0..100).contains(&type_id)
+    }
+
+    /// Lambdas, builtins and operators, the values that are applied rather than indexed.
+    pub fn is_function(&self) -> bool {
+        matches!(
+            self,
+            SuperType::Lambda(_)
+                | SuperType::UnaryOperation(_)
+                | SuperType::BinaryOperation(_)
+                | SuperType::Function(_)
+        )
+    }
+
+    /// Returns the number of items, 1 for atoms and the number of rows for tables.
+    pub fn count(&self) -> usize {
+        with_list!(self, list => list.count(), other => match other {
+            SuperType::Bools(bits) => bits.count(),
+            SuperType::MixedList(list) => list.0.len(),
+            SuperType::Table(table) => table.row_count(),
+            SuperType::Dictionary(dictionary) => dictionary.keys().count(),
+            _ => 1,
+        })
+    }
+
+    /// Returns the item at `index`: an atom for typed lists, a row dictionary for tables.
+    pub fn item(&self, index: usize) -> Option<SuperType> {
+        with_list!(self, list => list.data().get(index).cloned().map(|data| Atom::new(data).into()), other => match other {
+            SuperType::Bools(bits) => bits.get(index).map(SuperType::from),
+            SuperType::MixedList(list) => list.0.get(index).cloned(),
+            SuperType::Table(table) => table.row(index).map(SuperType::Dictionary),
+            SuperType::Dictionary(dictionary) => dictionary.values().item(index),
+            atom if index == 0 => Some(atom.clone()),
+            _ => None,
+        })
+    }
+
+    /// Returns the items at `indices`, keeping the type of the list.
+    pub fn select(&self, indices: &[usize]) -> Result<SuperType, Error> {
+        let selected = with_list!(self, list => gather(list, indices).map(SuperType::from), other => match other {
+            SuperType::Bools(bits) => bits.select(indices).map(SuperType::Bools),
+            SuperType::MixedList(list) => indices
+                .iter()
+                .map(|&i| list.0.get(i).cloned())
+                .collect::<Option<Vec<_>>>()
+                .map(|items| SuperType::MixedList(TypeMixedList(items))),
+            SuperType::Table(table) => return table.select_rows(indices).map(SuperType::Table),
+            SuperType::Dictionary(dictionary) => return dictionary.values().select(indices),
+            _ => return Err(Error::Type),
+        });
+        selected.ok_or(Error::Length)
+    }
+
+    /// Returns the position of the first item equal to `value`.
+    pub fn position(&self, value: &SuperType) -> Option<usize> {
+        (0..self.count()).find(|&i| self.item(i).as_ref() == Some(value))
+    }
+
+    /// Builds a list out of items, it is a typed list when all items are atoms of the same type.
+    pub fn from_items(items: Vec<SuperType>) -> SuperType {
+        collapse_atoms!(
+            items,
+            Bool => Bools,
+            Byte => Bytes,
+            Int => Ints,
+            Float => Floats,
+            Char => Chars,
+            Symbol => Symbols,
+            Date => Dates
+        )
+    }
+
+    /// Splits a list into its items, an atom is its own single item.
+    pub fn into_items(self) -> Vec<SuperType> {
+        with_list!(self, list => list.into_iter().map(|data| Atom::new(data).into()).collect(), other => match other {
+            SuperType::Bools(bits) => bits.iter().map(SuperType::from).collect(),
+            SuperType::MixedList(list) => list.0,
+            SuperType::Dictionary(dictionary) => dictionary.into_parts().1.into_items(),
+            table @ SuperType::Table(_) => (0..table.count()).filter_map(|i| table.item(i)).collect(),
+            atom => vec![atom],
+        })
+    }
+
+    /// Returns the null of the type of the items: 0N for ints, 0n for floats, ` for symbols...
+    /// Types without a null use their zero, mixed lists use Nothing.
+    pub fn null_item(&self) -> SuperType {
+        match self {
+            SuperType::Bool(_) | SuperType::Bools(_) => Atom::new(TypeBool::NULL).into(),
+            SuperType::Byte(_) | SuperType::Bytes(_) => Atom::new(TypeByte::NULL).into(),
+            SuperType::Int(_) | SuperType::Ints(_) => Atom::new(TypeInt::NULL).into(),
+            SuperType::Float(_) | SuperType::Floats(_) => Atom::new(TypeFloat::NULL).into(),
+            SuperType::Char(_) | SuperType::Chars(_) => Atom::new(TypeChar::NULL).into(),
+            SuperType::Symbol(_) | SuperType::Symbols(_) => Atom::new(TypeSymbol::NULL).into(),
+            SuperType::Date(_) | SuperType::Dates(_) => Atom::new(TypeDate::NULL).into(),
+            _ => SuperType::Nothing,
+        }
+    }
+
+    /// Repeats an atom `count` times, lists must already hold `count` items.
+    pub fn broadcast(self, count: usize) -> Result<SuperType, Error> {
+        if !self.is_atom() {
+            return match self.count() == count {
+                true => Ok(self),
+                false => Err(Error::Length),
+            };
+        }
+        Ok(
+            with_atom!(self, atom => atom.repeat(count).into(), other => SuperType::MixedList(TypeMixedList(vec![other; count]))),
+        )
+    }
 }
 
 impl From<bool> for SuperType {
@@ -78,14 +351,51 @@ impl From<Vec<f64>> for SuperType {
     }
 }
 
-impl From<char> for SuperType {
-    fn from(value: char) -> Self {
-        SuperType::Char(value.into())
+impl From<&str> for SuperType {
+    fn from(text: &str) -> Self {
+        SuperType::Chars(text.into())
     }
 }
 
-impl From<Vec<char>> for SuperType {
-    fn from(values: Vec<char>) -> Self {
-        SuperType::Chars(values.into())
+impl From<TypeTable> for SuperType {
+    fn from(value: TypeTable) -> Self {
+        SuperType::Table(value)
+    }
+}
+
+impl From<TypeDictionary> for SuperType {
+    fn from(value: TypeDictionary) -> Self {
+        SuperType::Dictionary(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_items_collapses_uniform_atoms() {
+        let items = vec![SuperType::from(1), SuperType::from(2)];
+        assert_eq!(SuperType::from_items(items), SuperType::from(vec![1, 2]));
+
+        let items = vec![SuperType::from(1), SuperType::from(2.0)];
+        assert_eq!(SuperType::from_items(items).type_id(), 0);
+    }
+
+    #[test]
+    fn test_select_keeps_list_type() {
+        let list = SuperType::from(vec![10, 20, 30]);
+        assert_eq!(list.select(&[2, 0]), Ok(SuperType::from(vec![30, 10])));
+        assert_eq!(list.select(&[3]), Err(Error::Length));
+    }
+
+    #[test]
+    fn test_broadcast_atom() {
+        let atom = SuperType::symbol("a");
+        assert_eq!(
+            atom.broadcast(2),
+            Ok(SuperType::symbols(vec!["a".to_owned(), "a".to_owned()]))
+        );
+        assert_eq!(SuperType::from(vec![1]).broadcast(2), Err(Error::Length));
     }
 }
@@ -3,14 +3,21 @@ use std::fmt::Display;
 pub mod actor;
 pub mod core;
 pub mod lang;
+#[cfg(test)]
+mod testing;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Error {
     Type,
     Rank,
     Length,
+    Domain,
+    Overflow,                    // an int result out of range when overflows raise
+    NoUpdate,                    // a secondary thread setting a global
+    AttributeFail(&'static str), // e.g. s when applying `s# to an unsorted list
     Name(String),
     MalformedProgram(String),
+    Parse(String),
     NotYetImplemented,
 }
 
@@ -20,8 +27,13 @@ impl Display for Error {
             Error::Type => write!(f, "'type"),
             Error::Rank => write!(f, "'rank"),
             Error::Length => write!(f, "'length"),
+            Error::Domain => write!(f, "'domain"),
+            Error::Overflow => write!(f, "'overflow"),
+            Error::NoUpdate => write!(f, "'noupdate"),
+            Error::AttributeFail(name) => write!(f, "'{name}-fail"),
             Error::Name(name) => write!(f, "'{name}"),
             Error::MalformedProgram(msg) => write!(f, "Malformed program: {msg}"),
+            Error::Parse(msg) => write!(f, "'parse: {msg}"),
             Error::NotYetImplemented => write!(f, "'nyi"),
         }
     }
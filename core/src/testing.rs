@@ -0,0 +1,21 @@
+//! Helpers shared by the tests of the modules.
+
+use crate::{Error, core::execution_context::ExecutionContext, lang::SuperType};
+
+/// Runs a program on a fresh context, giving the value of its last statement.
+pub(crate) fn run(program: &str) -> Result<SuperType, Error> {
+    ExecutionContext::default().run(program)
+}
+
+/// Prepends to a program the tables the query and join tests share: t, with a sym, px and qty
+/// column, and kt, keyed by sym.
+pub(crate) fn with_tables(program: &str) -> String {
+    let tables = "t:([] sym:`a`b`a`c; px:1 2 3 4; qty:10 20 30 40); \
+        kt:([sym:`a`c`d] px:10 30 40; name:`x`z`w)";
+    format!("{tables}; {program}")
+}
+
+/// The value of a program as the shell shows it.
+pub(crate) fn show(program: &str) -> String {
+    run(program).unwrap().to_string()
+}
@@ -104,15 +104,112 @@ pub fn derive_inner_type(input: TokenStream) -> TokenStream {
     TokenStream::from(expanded)
 }
 
+fn get_expr_attribute(attrs: &[syn::Attribute], name: &str) -> Option<syn::Expr> {
+    attrs
+        .iter()
+        .find(|attr| attr.path().is_ident(name))
+        .map(|attr| attr.parse_args().expect("Expected a value"))
+}
+
+/// Typed nulls and infinities. `cupid_null(value)` gives the null, `cupid_fill(value)` the value
+/// missing items get in types without a null, and `cupid_infinity(value)` the positive infinity.
+#[proc_macro_derive(InnerTypeNull, attributes(cupid_null, cupid_fill, cupid_infinity))]
+pub fn derive_inner_type_null(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+    let inner_type = get_inner_type(&input);
+    let type_str = quote!(#inner_type).to_string();
+
+    let null = get_expr_attribute(&input.attrs, "cupid_null");
+    let fill = get_expr_attribute(&input.attrs, "cupid_fill");
+    let (value, is_null) = match (null, fill) {
+        // NaN is the only value not equal to itself
+        (Some(null), None) if type_str == "f64" => (null, quote! { self.0.is_nan() }),
+        (Some(null), None) => (null, quote! { self.0 == Self::NULL.0 }),
+        (None, Some(fill)) => (fill, quote! { false }),
+        _ => panic!("InnerTypeNull needs exactly one of cupid_null or cupid_fill"),
+    };
+    let (infinity, negative_infinity) = match get_expr_attribute(&input.attrs, "cupid_infinity") {
+        Some(infinity) => (
+            quote! { Some(#name(#infinity)) },
+            quote! { Some(#name(-(#infinity))) },
+        ),
+        None => (quote! { None }, quote! { None }),
+    };
+
+    let expanded = quote! {
+        impl crate::lang::type_system::Nullable for #name {
+            const NULL: Self = #name(#value);
+            const INFINITY: Option<Self> = #infinity;
+            const NEGATIVE_INFINITY: Option<Self> = #negative_infinity;
+
+            fn is_null(&self) -> bool {
+                #is_null
+            }
+        }
+    };
+    TokenStream::from(expanded)
+}
+
+// Floats follow IEEE 754. Ints wrap around as in q, or raise Error::Overflow when asked to, and
+// dividing an int by zero gives the null.
+fn int_or_float(
+    name: &syn::Ident,
+    inner_type: &syn::Type,
+    op: proc_macro2::TokenStream,
+    method: &str,
+) -> proc_macro2::TokenStream {
+    if quote!(#inner_type).to_string() == "f64" {
+        return quote! { Ok(#name(lhs.0 #op rhs.0)) };
+    }
+    let checked = syn::Ident::new(&format!("checked_{method}"), proc_macro2::Span::call_site());
+    let wrapping = syn::Ident::new(
+        &format!("wrapping_{method}"),
+        proc_macro2::Span::call_site(),
+    );
+    let zero = match method {
+        "div" => quote! {
+            if rhs.0 == 0 {
+                return Ok(Self::NULL);
+            }
+        },
+        _ => quote! {},
+    };
+    quote! {
+        #zero
+        // landing on the null is overflowing too
+        let checked = lhs.0.#checked(rhs.0).map(#name).filter(|v| !v.is_null());
+        crate::lang::invokable::operator::overflowing(checked, #name(lhs.0.#wrapping(rhs.0)))
+    }
+}
+
+// Promoted types do their arithmetic as the type they promote to, its nulls, overflows and
+// division by zero included
+fn promoted(
+    target_type: &syn::Path,
+    cast_type: &syn::Type,
+    op_trait: proc_macro2::TokenStream,
+    method: &str,
+) -> proc_macro2::TokenStream {
+    let method = syn::Ident::new(method, proc_macro2::Span::call_site());
+    quote! {
+        let (lhs, rhs) = (#target_type(lhs.0 as #cast_type), #target_type(rhs.0 as #cast_type));
+        <#target_type as crate::lang::invokable::operator::#op_trait<&#target_type, #target_type>>::#method(&lhs, &rhs)
+    }
+}
+
 fn impl_add(
     name: &syn::Ident,
+    inner_type: &syn::Type,
     promote: Option<&(syn::Path, syn::Type)>,
 ) -> proc_macro2::TokenStream {
+    let body = int_or_float(name, inner_type, quote! { + }, "add");
     if let Some((target_type, cast_type)) = promote {
+        let promoted = promoted(target_type, cast_type, quote! { OperatorAdd }, "add");
         quote! {
             impl crate::lang::invokable::operator::OperatorAdd<&Self, #target_type> for #name {
                 fn add(lhs: &Self, rhs: &Self) -> Result<#target_type, crate::Error> {
-                    Ok(#target_type((lhs.0 as #cast_type) + (rhs.0 as #cast_type)))
+                    #promoted
                 }
             }
         }
@@ -120,7 +217,11 @@ fn impl_add(
         quote! {
             impl crate::lang::invokable::operator::OperatorAdd<&Self, Self> for #name {
                 fn add(lhs: &Self, rhs: &Self) -> Result<Self, crate::Error> {
-                    Ok(#name(lhs.0 + rhs.0))
+                    use crate::lang::type_system::Nullable;
+                    if lhs.is_null() || rhs.is_null() {
+                        return Ok(Self::NULL);
+                    }
+                    #body
                 }
             }
         }
@@ -131,10 +232,9 @@ fn impl_add(
 pub fn derive_inner_type_add(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let name = &input.ident;
-    // We don't strictly need inner type here as we access .0, but good to validate structure
-    let _ = get_inner_type(&input);
+    let inner_type = get_inner_type(&input);
     let promote = get_arithmetic_promote(&input.attrs);
-    TokenStream::from(impl_add(name, promote.as_ref()))
+    TokenStream::from(impl_add(name, inner_type, promote.as_ref()))
 }
 
 #[proc_macro_derive(InnerTypeEquals)]
@@ -155,13 +255,16 @@ pub fn derive_inner_type_equals(input: TokenStream) -> TokenStream {
 
 fn impl_sub(
     name: &syn::Ident,
+    inner_type: &syn::Type,
     promote: Option<&(syn::Path, syn::Type)>,
 ) -> proc_macro2::TokenStream {
+    let body = int_or_float(name, inner_type, quote! { - }, "sub");
     if let Some((target_type, cast_type)) = promote {
+        let promoted = promoted(target_type, cast_type, quote! { OperatorSubtract }, "sub");
         quote! {
             impl crate::lang::invokable::operator::OperatorSubtract<&Self, #target_type> for #name {
                 fn sub(lhs: &Self, rhs: &Self) -> Result<#target_type, crate::Error> {
-                    Ok(#target_type((lhs.0 as #cast_type) - (rhs.0 as #cast_type)))
+                    #promoted
                 }
             }
         }
@@ -169,7 +272,11 @@ fn impl_sub(
         quote! {
             impl crate::lang::invokable::operator::OperatorSubtract<&Self, Self> for #name {
                 fn sub(lhs: &Self, rhs: &Self) -> Result<Self, crate::Error> {
-                    Ok(#name(lhs.0 - rhs.0))
+                    use crate::lang::type_system::Nullable;
+                    if lhs.is_null() || rhs.is_null() {
+                        return Ok(Self::NULL);
+                    }
+                    #body
                 }
             }
         }
@@ -180,20 +287,23 @@ fn impl_sub(
 pub fn derive_inner_type_sub(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let name = &input.ident;
-    let _ = get_inner_type(&input);
+    let inner_type = get_inner_type(&input);
     let promote = get_arithmetic_promote(&input.attrs);
-    TokenStream::from(impl_sub(name, promote.as_ref()))
+    TokenStream::from(impl_sub(name, inner_type, promote.as_ref()))
 }
 
 fn impl_mul(
     name: &syn::Ident,
+    inner_type: &syn::Type,
     promote: Option<&(syn::Path, syn::Type)>,
 ) -> proc_macro2::TokenStream {
+    let body = int_or_float(name, inner_type, quote! { * }, "mul");
     if let Some((target_type, cast_type)) = promote {
+        let promoted = promoted(target_type, cast_type, quote! { OperatorMultiply }, "mul");
         quote! {
             impl crate::lang::invokable::operator::OperatorMultiply<&Self, #target_type> for #name {
                 fn mul(lhs: &Self, rhs: &Self) -> Result<#target_type, crate::Error> {
-                    Ok(#target_type((lhs.0 as #cast_type) * (rhs.0 as #cast_type)))
+                    #promoted
                 }
             }
         }
@@ -201,7 +311,11 @@ fn impl_mul(
         quote! {
             impl crate::lang::invokable::operator::OperatorMultiply<&Self, Self> for #name {
                 fn mul(lhs: &Self, rhs: &Self) -> Result<Self, crate::Error> {
-                    Ok(#name(lhs.0 * rhs.0))
+                    use crate::lang::type_system::Nullable;
+                    if lhs.is_null() || rhs.is_null() {
+                        return Ok(Self::NULL);
+                    }
+                    #body
                 }
             }
         }
@@ -212,20 +326,23 @@ fn impl_mul(
 pub fn derive_inner_type_mul(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let name = &input.ident;
-    let _ = get_inner_type(&input);
+    let inner_type = get_inner_type(&input);
     let promote = get_arithmetic_promote(&input.attrs);
-    TokenStream::from(impl_mul(name, promote.as_ref()))
+    TokenStream::from(impl_mul(name, inner_type, promote.as_ref()))
 }
 
 fn impl_div(
     name: &syn::Ident,
+    inner_type: &syn::Type,
     promote: Option<&(syn::Path, syn::Type)>,
 ) -> proc_macro2::TokenStream {
+    let body = int_or_float(name, inner_type, quote! { / }, "div");
     if let Some((target_type, cast_type)) = promote {
+        let promoted = promoted(target_type, cast_type, quote! { OperatorDivide }, "div");
         quote! {
             impl crate::lang::invokable::operator::OperatorDivide<&Self, #target_type> for #name {
                 fn div(lhs: &Self, rhs: &Self) -> Result<#target_type, crate::Error> {
-                    Ok(#target_type((lhs.0 as #cast_type) / (rhs.0 as #cast_type)))
+                    #promoted
                 }
             }
         }
@@ -233,7 +350,11 @@ fn impl_div(
         quote! {
             impl crate::lang::invokable::operator::OperatorDivide<&Self, Self> for #name {
                 fn div(lhs: &Self, rhs: &Self) -> Result<Self, crate::Error> {
-                    Ok(#name(lhs.0 / rhs.0))
+                    use crate::lang::type_system::Nullable;
+                    if lhs.is_null() || rhs.is_null() {
+                        return Ok(Self::NULL);
+                    }
+                    #body
                 }
             }
         }
@@ -244,9 +365,9 @@ fn impl_div(
 pub fn derive_inner_type_div(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let name = &input.ident;
-    let _ = get_inner_type(&input);
+    let inner_type = get_inner_type(&input);
     let promote = get_arithmetic_promote(&input.attrs);
-    TokenStream::from(impl_div(name, promote.as_ref()))
+    TokenStream::from(impl_div(name, inner_type, promote.as_ref()))
 }
 
 fn impl_cast(name: &syn::Ident, inner_type: &syn::Type) -> proc_macro2::TokenStream {
@@ -267,7 +388,7 @@ fn impl_cast(name: &syn::Ident, inner_type: &syn::Type) -> proc_macro2::TokenStr
         let primitive_ident = syn::Ident::new(primitive, proc_macro2::Span::call_site());
 
         // Don't implement From<Self> for Self (already exists)
-        if name.to_string() == wrapper {
+        if *name == wrapper {
             continue;
         }
 
@@ -277,7 +398,10 @@ fn impl_cast(name: &syn::Ident, inner_type: &syn::Type) -> proc_macro2::TokenStr
             quote! { if val.0 { 1 as #primitive_ident } else { 0 as #primitive_ident } }
         } else if primitive == "bool" {
             quote! { val.0 != 0 as #inner_type }
-        } else if type_str == "f64" && (primitive == "i32" || primitive == "u8") {
+        } else if type_str == "f64" && primitive == "i32" {
+            // -0w must not become the int null
+            quote! { val.0.round().max(-(i32::MAX as f64)) as #primitive_ident }
+        } else if type_str == "f64" && primitive == "u8" {
             quote! { val.0.round() as #primitive_ident }
         } else {
             quote! { val.0 as #primitive_ident }
@@ -286,6 +410,24 @@ fn impl_cast(name: &syn::Ident, inner_type: &syn::Type) -> proc_macro2::TokenStr
         impls.extend(quote! {
             impl From<#name> for crate::lang::type_system::#wrapper_ident {
                 fn from(val: #name) -> Self {
+                    use crate::lang::type_system::Nullable;
+                    // nulls and infinities map onto those of the target type when it has them
+                    if val.is_null() {
+                        return Self::NULL;
+                    }
+                    match (#name::INFINITY, #name::NEGATIVE_INFINITY) {
+                        (Some(infinity), _) if val == infinity => {
+                            if let Some(infinity) = Self::INFINITY {
+                                return infinity;
+                            }
+                        }
+                        (_, Some(infinity)) if val == infinity => {
+                            if let Some(infinity) = Self::NEGATIVE_INFINITY {
+                                return infinity;
+                            }
+                        }
+                        _ => {}
+                    }
                     crate::lang::type_system::#wrapper_ident(#cast_expr)
                 }
             }
@@ -301,10 +443,10 @@ pub fn derive_inner_type_arithmetic(input: TokenStream) -> TokenStream {
     let inner_type = get_inner_type(&input);
     let promote = get_arithmetic_promote(&input.attrs);
 
-    let add = impl_add(name, promote.as_ref());
-    let sub = impl_sub(name, promote.as_ref());
-    let mul = impl_mul(name, promote.as_ref());
-    let div = impl_div(name, promote.as_ref());
+    let add = impl_add(name, inner_type, promote.as_ref());
+    let sub = impl_sub(name, inner_type, promote.as_ref());
+    let mul = impl_mul(name, inner_type, promote.as_ref());
+    let div = impl_div(name, inner_type, promote.as_ref());
     let cast = impl_cast(name, inner_type);
 
     let expanded = quote! {#add
@@ -0,0 +1,767 @@
+use crate::{INT_INFINITY, INT_NULL, Token};
+
+/// A parsed expression. It only carries syntax, names are resolved by whoever evaluates it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Integer(i64),
+    Integers(Vec<i64>),
+    Decimal(f64),
+    Decimals(Vec<f64>),
+    Date(i64),
+    Dates(Vec<i64>),
+    Bool(bool),
+    Bools(Vec<bool>),
+    Char(char),
+    Str(String),
+    Symbol(String),
+    Symbols(Vec<String>),
+
+    Identifier(String),
+    Operator(String), // a verb used as a value, e.g. (+)
+
+    Apply(Box<Expr>, Vec<Expr>),     // f[x;y], f x and x f y
+    Assign(String, Box<Expr>),       // x: expr
+    AssignGlobal(String, Box<Expr>), // x:: expr, from within a lambda
+    List(Vec<Expr>),                 // (a;b;c)
+
+    // x op: expr, x[i]: expr and x[i] op: expr change the variable x where it is defined
+    Amend {
+        name: String,
+        index: Option<Box<Expr>>,
+        op: Option<String>,
+        value: Box<Expr>,
+    },
+
+    // {[a;b] ...}, the parameters are x, y and z as far as the body uses them when not named
+    Lambda {
+        params: Vec<String>,
+        body: Vec<Expr>,
+    },
+
+    // ([k: ...] c: ...), keys is empty for a simple table
+    Table {
+        keys: Vec<(String, Expr)>,
+        columns: Vec<(String, Expr)>,
+    },
+    Query(Box<Query>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryKind {
+    Select,
+    Exec,
+    Update,
+    Delete,
+}
+
+/// A qSQL template: `select columns by groups from table where constraints`.
+/// Column names are optional, the evaluator derives one when missing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    pub kind: QueryKind,
+    pub columns: Vec<(Option<String>, Expr)>,
+    pub by: Vec<(Option<String>, Expr)>,
+    pub from: Expr,
+    pub constraints: Vec<Expr>,
+}
+
+// What, on top of closing brackets and semicolons, ends the expression being parsed
+#[derive(Clone, Copy, Default)]
+struct Stop {
+    comma: bool,   // columns and constraints of a query are separated by commas
+    keyword: bool, // `by`, `from` and `where` end a phrase of a query
+}
+
+const QUERY_PHRASE: Stop = Stop {
+    comma: true,
+    keyword: true,
+};
+
+// Builtins that take their left argument before their name
+const INFIX_WORDS: [&str; 41] = [
+    "lj",
+    "ij",
+    "uj",
+    "pj",
+    "in",
+    "within",
+    "bin",
+    "binr",
+    "xasc",
+    "xdesc",
+    "union",
+    "inter",
+    "except",
+    "cross",
+    "mod",
+    "div",
+    "xexp",
+    "xlog",
+    "mavg",
+    "msum",
+    "mmax",
+    "mmin",
+    "mcount",
+    "mdev",
+    "ema",
+    "cov",
+    "cor",
+    "wsum",
+    "wavg",
+    "percentile",
+    "mmu",
+    "lsq",
+    "like",
+    "ss",
+    "vs",
+    "sv",
+    "rmatch",
+    "rss",
+    "rfind",
+    "rvs",
+    "peach",
+];
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+}
+
+/// Parses tokens into a sequence of statements, one per `;` separated expression.
+pub fn parse(tokens: &[Token]) -> Result<Vec<Expr>, String> {
+    let mut parser = Parser { tokens, pos: 0 };
+    let mut statements = Vec::new();
+
+    loop {
+        if !parser.is_terminator(Stop::default()) {
+            statements.push(parser.parse_expr(Stop::default())?);
+        }
+        match parser.next() {
+            None => break,
+            Some(Token::Semicolon) => continue,
+            Some(token) => return Err(format!("Unexpected token: {token:?}")),
+        }
+    }
+
+    Ok(statements)
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&'a Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<&'a Token> {
+        self.tokens.get(self.pos + offset)
+    }
+
+    fn next(&mut self) -> Option<&'a Token> {
+        let token = self.tokens.get(self.pos);
+        self.pos += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: &Token) -> Result<(), String> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            Some(token) => Err(format!("Expected {expected:?}, found {token:?}")),
+            None => Err(format!("Expected {expected:?}, found end of input")),
+        }
+    }
+
+    fn is_terminator(&self, stop: Stop) -> bool {
+        match self.peek() {
+            None
+            | Some(Token::Semicolon)
+            | Some(Token::RParenthesis)
+            | Some(Token::RBracket)
+            | Some(Token::RCurly) => true,
+            Some(Token::Keyword(word)) => stop.keyword && matches!(word.as_str(), "by" | "from"),
+            // within a query `where` starts the constraints rather than applying the function
+            Some(Token::Identifier(word)) => stop.keyword && word == "where",
+            Some(Token::Operator(op)) => stop.comma && op == ",",
+            _ => false,
+        }
+    }
+
+    // q evaluates right to left: the right operand of a verb is everything after it
+    fn parse_expr(&mut self, stop: Stop) -> Result<Expr, String> {
+        if let (Some(Token::Identifier(name)), Some(Token::Colon), Some(Token::Colon)) =
+            (self.peek(), self.peek_at(1), self.peek_at(2))
+        {
+            self.pos += 3;
+            let value = self.parse_expr(stop)?;
+            return Ok(Expr::AssignGlobal(name.clone(), Box::new(value)));
+        }
+        if let (Some(Token::Identifier(name)), Some(Token::Colon)) = (self.peek(), self.peek_at(1))
+        {
+            self.pos += 2;
+            let value = self.parse_expr(stop)?;
+            return Ok(Expr::Assign(name.clone(), Box::new(value)));
+        }
+
+        let term = self.parse_term()?;
+        if self.is_terminator(stop) {
+            return Ok(term);
+        }
+        if let Some(amend) = self.parse_amend(&term, stop)? {
+            return Ok(amend);
+        }
+
+        // a bare verb applied to its right operand, e.g. -x
+        if matches!(term, Expr::Operator(_)) {
+            let operand = self.parse_expr(stop)?;
+            return Ok(Expr::Apply(Box::new(term), vec![operand]));
+        }
+
+        // named functions used infix, e.g. t lj kt
+        if let Some(Token::Identifier(word)) = self.peek()
+            && INFIX_WORDS.contains(&word.as_str())
+        {
+            self.pos += 1;
+            if self.is_terminator(stop) {
+                return Err(format!("Missing right operand for {word}"));
+            }
+            let rhs = self.parse_expr(stop)?;
+            return Ok(Expr::Apply(
+                Box::new(Expr::Identifier(word.clone())),
+                vec![term, rhs],
+            ));
+        }
+
+        if let Some(Token::Operator(op)) = self.peek() {
+            self.pos += 1;
+            if self.is_terminator(stop) {
+                return Err(format!("Missing right operand for {op}"));
+            }
+            let rhs = self.parse_expr(stop)?;
+            return Ok(Expr::Apply(
+                Box::new(Expr::Operator(op.clone())),
+                vec![term, rhs],
+            ));
+        }
+
+        // juxtaposition, e.g. count x
+        let operand = self.parse_expr(stop)?;
+        Ok(Expr::Apply(Box::new(term), vec![operand]))
+    }
+
+    fn parse_term(&mut self) -> Result<Expr, String> {
+        let mut term = match self.next() {
+            Some(Token::Integer(_) | Token::Decimal(_)) => {
+                self.pos -= 1;
+                self.parse_numbers()
+            }
+            Some(Token::Date(date)) => {
+                let mut dates = vec![*date];
+                while let Some(Token::Date(date)) = self.peek() {
+                    dates.push(*date);
+                    self.pos += 1;
+                }
+                match dates.len() {
+                    1 => Expr::Date(dates[0]),
+                    _ => Expr::Dates(dates),
+                }
+            }
+            Some(Token::Symbol(symbol)) => {
+                let mut symbols = vec![symbol.clone()];
+                while let Some(Token::Symbol(symbol)) = self.peek() {
+                    symbols.push(symbol.clone());
+                    self.pos += 1;
+                }
+                match symbols.len() {
+                    1 => Expr::Symbol(symbols.remove(0)),
+                    _ => Expr::Symbols(symbols),
+                }
+            }
+            Some(Token::Booleans(bools)) => match bools.as_slice() {
+                [b] => Expr::Bool(*b),
+                _ => Expr::Bools(bools.clone()),
+            },
+            Some(Token::StringLiteral(string)) => {
+                let mut chars = string.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Expr::Char(c),
+                    _ => Expr::Str(string.clone()),
+                }
+            }
+            Some(Token::Identifier(name)) => Expr::Identifier(name.clone()),
+            Some(Token::Operator(op)) => Expr::Operator(op.clone()),
+            Some(Token::LParenthesis) => self.parse_parenthesis()?,
+            Some(Token::LCurly) => self.parse_lambda()?,
+            Some(Token::Keyword(word)) => match word.as_str() {
+                "select" => self.parse_query(QueryKind::Select)?,
+                "exec" => self.parse_query(QueryKind::Exec)?,
+                "update" => self.parse_query(QueryKind::Update)?,
+                "delete" => self.parse_query(QueryKind::Delete)?,
+                _ => return Err(format!("Unexpected keyword: {word}")),
+            },
+            Some(token) => return Err(format!("Unexpected token: {token:?}")),
+            None => return Err("Unexpected end of input".to_owned()),
+        };
+
+        // bracket application and indexing, e.g. f[x;y] or x[0]
+        while let Some(Token::LBracket) = self.peek() {
+            self.pos += 1;
+            let args = self.parse_sequence(&Token::RBracket)?;
+            term = Expr::Apply(Box::new(term), args);
+        }
+
+        Ok(term)
+    }
+
+    // x op: y and x[i] op: y after the term x or x[i], and x[i]: y as x: y is an assignment
+    fn parse_amend(&mut self, term: &Expr, stop: Stop) -> Result<Option<Expr>, String> {
+        let (name, index) = match term {
+            Expr::Identifier(name) => (name, None),
+            Expr::Apply(function, arguments) => match (function.as_ref(), arguments.as_slice()) {
+                (Expr::Identifier(name), [index]) => (name, Some(Box::new(index.clone()))),
+                _ => return Ok(None),
+            },
+            _ => return Ok(None),
+        };
+        let op = match (self.peek(), self.peek_at(1)) {
+            (Some(Token::Colon), _) if index.is_some() => None,
+            (Some(Token::Operator(op)), Some(Token::Colon)) => Some(op.clone()),
+            _ => return Ok(None),
+        };
+        self.pos += 1 + op.is_some() as usize;
+        let value = self.parse_expr(stop)?;
+        Ok(Some(Expr::Amend {
+            name: name.clone(),
+            index,
+            op,
+            value: Box::new(value),
+        }))
+    }
+
+    // a run of numbers is a vector, it is a float vector as soon as one of them is, the int
+    // null and infinities becoming the float ones
+    fn parse_numbers(&mut self) -> Expr {
+        let mut integers = Vec::new();
+        let mut decimals = Vec::new();
+        let mut is_decimal = false;
+        while let Some(token) = self.peek() {
+            match token {
+                Token::Integer(i) => {
+                    integers.push(*i);
+                    decimals.push(match *i {
+                        INT_NULL => f64::NAN,
+                        INT_INFINITY => f64::INFINITY,
+                        i if i == -INT_INFINITY => f64::NEG_INFINITY,
+                        i => i as f64,
+                    });
+                }
+                Token::Decimal(f) => {
+                    is_decimal = true;
+                    decimals.push(*f);
+                }
+                _ => break,
+            }
+            self.pos += 1;
+        }
+
+        match (is_decimal, decimals.len()) {
+            (true, 1) => Expr::Decimal(decimals[0]),
+            (true, _) => Expr::Decimals(decimals),
+            (false, 1) => Expr::Integer(integers[0]),
+            (false, _) => Expr::Integers(integers),
+        }
+    }
+
+    // `;` separated expressions up to the closing token, which gets consumed
+    fn parse_sequence(&mut self, close: &Token) -> Result<Vec<Expr>, String> {
+        let mut items = Vec::new();
+        if self.peek() == Some(close) {
+            self.pos += 1;
+            return Ok(items);
+        }
+        loop {
+            items.push(self.parse_expr(Stop::default())?);
+            match self.next() {
+                Some(Token::Semicolon) => continue,
+                Some(token) if token == close => return Ok(items),
+                Some(token) => return Err(format!("Unexpected token: {token:?}")),
+                None => return Err(format!("Expected {close:?}, found end of input")),
+            }
+        }
+    }
+
+    fn parse_parenthesis(&mut self) -> Result<Expr, String> {
+        if let Some(Token::LBracket) = self.peek() {
+            self.pos += 1;
+            return self.parse_table();
+        }
+
+        let mut items = self.parse_sequence(&Token::RParenthesis)?;
+        match items.len() {
+            1 => Ok(items.remove(0)),
+            _ => Ok(Expr::List(items)),
+        }
+    }
+
+    // {[a;b] ...; ...}, the opening curly brace is already consumed
+    fn parse_lambda(&mut self) -> Result<Expr, String> {
+        let named = match self.peek() {
+            Some(Token::LBracket) => {
+                self.pos += 1;
+                let params = self.parse_sequence(&Token::RBracket)?;
+                let names = params.into_iter().map(|param| match param {
+                    Expr::Identifier(name) => Ok(name),
+                    other => Err(format!("Parameters must be names: {other:?}")),
+                });
+                Some(names.collect::<Result<Vec<_>, _>>()?)
+            }
+            _ => None,
+        };
+        let body = self.parse_sequence(&Token::RCurly)?;
+        // x is always a parameter, y when y or z is used, z when z is
+        let params = named.unwrap_or_else(|| {
+            let rank = match ["z", "y"]
+                .iter()
+                .position(|p| body.iter().any(|e| uses(e, p)))
+            {
+                Some(0) => 3,
+                Some(_) => 2,
+                None => 1,
+            };
+            ["x", "y", "z"][..rank]
+                .iter()
+                .map(|p| p.to_string())
+                .collect()
+        });
+        Ok(Expr::Lambda { params, body })
+    }
+
+    // ([k1: ...; k2: ...] c1: ...; c2: ...), the opening bracket is already consumed
+    fn parse_table(&mut self) -> Result<Expr, String> {
+        let keys = self.parse_table_columns(&Token::RBracket)?;
+        let columns = self.parse_table_columns(&Token::RParenthesis)?;
+        Ok(Expr::Table { keys, columns })
+    }
+
+    fn parse_table_columns(&mut self, close: &Token) -> Result<Vec<(String, Expr)>, String> {
+        self.parse_sequence(close)?
+            .into_iter()
+            .map(|column| match column {
+                Expr::Assign(name, value) => Ok((name, *value)),
+                Expr::Identifier(name) => Ok((name.clone(), Expr::Identifier(name))),
+                other => Err(format!("Table columns must be named: {other:?}")),
+            })
+            .collect()
+    }
+
+    fn parse_query(&mut self, kind: QueryKind) -> Result<Expr, String> {
+        let columns = match self.peek() {
+            Some(Token::Keyword(word)) if word == "by" || word == "from" => Vec::new(),
+            _ => self.parse_query_columns()?,
+        };
+
+        let by = match self.peek() {
+            Some(Token::Keyword(word)) if word == "by" => {
+                self.pos += 1;
+                self.parse_query_columns()?
+            }
+            _ => Vec::new(),
+        };
+
+        self.expect(&Token::Keyword("from".to_owned()))?;
+        let from = self.parse_expr(Stop {
+            comma: false,
+            keyword: true,
+        })?;
+
+        let mut constraints = Vec::new();
+        if let Some(Token::Identifier(word)) = self.peek()
+            && word == "where"
+        {
+            self.pos += 1;
+            loop {
+                constraints.push(self.parse_expr(QUERY_PHRASE)?);
+                if !self.skip_comma() {
+                    break;
+                }
+            }
+        }
+
+        Ok(Expr::Query(Box::new(Query {
+            kind,
+            columns,
+            by,
+            from,
+            constraints,
+        })))
+    }
+
+    fn parse_query_columns(&mut self) -> Result<Vec<(Option<String>, Expr)>, String> {
+        let mut columns = Vec::new();
+        loop {
+            let name = match (self.peek(), self.peek_at(1)) {
+                (Some(Token::Identifier(name)), Some(Token::Colon)) => {
+                    self.pos += 2;
+                    Some(name.clone())
+                }
+                _ => None,
+            };
+            columns.push((name, self.parse_expr(QUERY_PHRASE)?));
+            if !self.skip_comma() {
+                return Ok(columns);
+            }
+        }
+    }
+
+    fn skip_comma(&mut self) -> bool {
+        match self.peek() {
+            Some(Token::Operator(op)) if op == "," => {
+                self.pos += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+// Whether `expr` refers to `name`, leaving out the lambdas within it that have their own scope
+fn uses(expr: &Expr, name: &str) -> bool {
+    let any = |exprs: &[Expr]| exprs.iter().any(|e| uses(e, name));
+    let columns = |columns: &[(Option<String>, Expr)]| columns.iter().any(|(_, e)| uses(e, name));
+    match expr {
+        Expr::Identifier(identifier) => identifier == name,
+        Expr::Apply(function, arguments) => uses(function, name) || any(arguments),
+        Expr::Assign(_, value) | Expr::AssignGlobal(_, value) => uses(value, name),
+        Expr::Amend {
+            name: amended,
+            index,
+            value,
+            ..
+        } => {
+            amended == name || index.as_deref().is_some_and(|i| uses(i, name)) || uses(value, name)
+        }
+        Expr::List(items) => any(items),
+        Expr::Table { keys, columns } => keys.iter().chain(columns).any(|(_, e)| uses(e, name)),
+        Expr::Query(query) => {
+            columns(&query.columns)
+                || columns(&query.by)
+                || uses(&query.from, name)
+                || any(&query.constraints)
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lex;
+
+    fn parse_str(input: &str) -> Vec<Expr> {
+        parse(&lex(input).expect("should lex")).expect("should parse")
+    }
+
+    fn ident(name: &str) -> Expr {
+        Expr::Identifier(name.to_owned())
+    }
+
+    fn dyad(op: &str, lhs: Expr, rhs: Expr) -> Expr {
+        Expr::Apply(Box::new(Expr::Operator(op.to_owned())), vec![lhs, rhs])
+    }
+
+    #[test]
+    fn parse_right_to_left() {
+        // 2*3+4 is 2*(3+4) in q
+        let statements = parse_str("2*3+4");
+        assert_eq!(
+            statements,
+            vec![dyad(
+                "*",
+                Expr::Integer(2),
+                dyad("+", Expr::Integer(3), Expr::Integer(4))
+            )]
+        );
+    }
+
+    #[test]
+    fn parse_int_nulls_among_floats() {
+        // the int null and infinities become the float ones rather than numbers
+        let statements = parse_str("1.5 0N 0W -0W");
+        let [Expr::Decimals(decimals)] = statements.as_slice() else {
+            panic!("expected floats, got {statements:?}");
+        };
+        assert_eq!(decimals[0], 1.5);
+        assert!(decimals[1].is_nan());
+        assert_eq!(decimals[2..], [f64::INFINITY, f64::NEG_INFINITY]);
+        assert_eq!(
+            parse_str("0Nd 0Wd"),
+            vec![Expr::Dates(vec![INT_NULL, INT_INFINITY])]
+        );
+    }
+
+    #[test]
+    fn parse_statements_and_assignment() {
+        let statements = parse_str("x:1 2 3;count x");
+        assert_eq!(
+            statements,
+            vec![
+                Expr::Assign("x".to_owned(), Box::new(Expr::Integers(vec![1, 2, 3]))),
+                Expr::Apply(Box::new(ident("count")), vec![ident("x")]),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_table_literal() {
+        let statements = parse_str("([k:`a`b] v:1 2)");
+        assert_eq!(
+            statements,
+            vec![Expr::Table {
+                keys: vec![(
+                    "k".to_owned(),
+                    Expr::Symbols(vec!["a".to_owned(), "b".to_owned()])
+                )],
+                columns: vec![("v".to_owned(), Expr::Integers(vec![1, 2]))],
+            }]
+        );
+    }
+
+    #[test]
+    fn parse_select_phrases() {
+        let statements = parse_str("select total:sum p, q by s from t where p>1, s=`a");
+        let Expr::Query(query) = &statements[0] else {
+            panic!("expected a query, got {statements:?}");
+        };
+        assert_eq!(query.kind, QueryKind::Select);
+        assert_eq!(
+            query.columns,
+            vec![
+                (
+                    Some("total".to_owned()),
+                    Expr::Apply(Box::new(ident("sum")), vec![ident("p")])
+                ),
+                (None, ident("q")),
+            ]
+        );
+        assert_eq!(query.by, vec![(None, ident("s"))]);
+        assert_eq!(query.from, ident("t"));
+        assert_eq!(
+            query.constraints,
+            vec![
+                dyad(">", ident("p"), Expr::Integer(1)),
+                dyad("=", ident("s"), Expr::Symbol("a".to_owned())),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_where_outside_queries() {
+        let statements = parse_str("where 0101b; select from t where where b");
+        assert_eq!(
+            statements[0],
+            Expr::Apply(
+                Box::new(ident("where")),
+                vec![Expr::Bools(vec![false, true, false, true])]
+            )
+        );
+        let Expr::Query(query) = &statements[1] else {
+            panic!("expected a query, got {statements:?}");
+        };
+        assert_eq!(
+            query.constraints,
+            vec![Expr::Apply(Box::new(ident("where")), vec![ident("b")])]
+        );
+    }
+
+    #[test]
+    fn parse_delete_columns() {
+        let statements = parse_str("delete a,b from t");
+        let Expr::Query(query) = &statements[0] else {
+            panic!("expected a query, got {statements:?}");
+        };
+        assert_eq!(query.kind, QueryKind::Delete);
+        assert_eq!(query.columns, vec![(None, ident("a")), (None, ident("b"))]);
+        assert!(query.constraints.is_empty());
+    }
+
+    #[test]
+    fn parse_comma_joins_inside_brackets() {
+        let statements = parse_str("select x:(a,b) from t");
+        let Expr::Query(query) = &statements[0] else {
+            panic!("expected a query, got {statements:?}");
+        };
+        assert_eq!(
+            query.columns,
+            vec![(Some("x".to_owned()), dyad(",", ident("a"), ident("b")))]
+        );
+    }
+
+    #[test]
+    fn parse_lambdas() {
+        let statements = parse_str("{x+z}; {[a;b] c:a*b; c}; {1}; {y:x::1}");
+        let lambda = |params: &[&str], body| Expr::Lambda {
+            params: params.iter().map(|p| p.to_string()).collect(),
+            body,
+        };
+        let assign = |name: &str, value| Expr::Assign(name.to_owned(), Box::new(value));
+        assert_eq!(
+            statements,
+            vec![
+                lambda(&["x", "y", "z"], vec![dyad("+", ident("x"), ident("z"))]),
+                lambda(
+                    &["a", "b"],
+                    vec![assign("c", dyad("*", ident("a"), ident("b"))), ident("c")]
+                ),
+                lambda(&["x"], vec![Expr::Integer(1)]),
+                // assigning y does not make it a parameter
+                lambda(
+                    &["x"],
+                    vec![assign(
+                        "y",
+                        Expr::AssignGlobal("x".to_owned(), Box::new(Expr::Integer(1)))
+                    )]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_amends() {
+        let statements = parse_str("x,:1 2; x[0]:3; x[i]+:y; {x-:1}");
+        let amend = |index: Option<Expr>, op: Option<&str>, value| Expr::Amend {
+            name: "x".to_owned(),
+            index: index.map(Box::new),
+            op: op.map(str::to_owned),
+            value: Box::new(value),
+        };
+        assert_eq!(statements.len(), 4, "{statements:?}");
+        assert_eq!(
+            statements[..3],
+            [
+                amend(None, Some(","), Expr::Integers(vec![1, 2])),
+                amend(Some(Expr::Integer(0)), None, Expr::Integer(3)),
+                amend(Some(ident("i")), Some("+"), ident("y")),
+            ]
+        );
+        // amending x makes it a parameter
+        assert_eq!(
+            statements[3],
+            Expr::Lambda {
+                params: vec!["x".to_owned()],
+                body: vec![amend(None, Some("-"), Expr::Integer(1))],
+            }
+        );
+        assert!(parse(&lex("x[0;1]:2").unwrap()).is_err());
+    }
+
+    #[test]
+    fn parse_infix_words() {
+        let statements = parse_str("t lj kt ij s");
+        let apply = |word: &str, lhs, rhs| Expr::Apply(Box::new(ident(word)), vec![lhs, rhs]);
+        assert_eq!(
+            statements,
+            vec![apply(
+                "lj",
+                ident("t"),
+                apply("ij", ident("kt"), ident("s"))
+            )]
+        );
+    }
+}
@@ -1,9 +1,16 @@
+mod ast;
+
+pub use ast::{Expr, Query, QueryKind, parse};
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Token {
     Integer(i64),
     Decimal(f64),
+    Date(i64),           // days since 2000.01.01, only 0Nd and 0Wd for now
+    Booleans(Vec<bool>), // e.g. 1b or 0101b
     StringLiteral(String),
-    Operator(char), // e.g. +, -, *, %
+    Symbol(String),   // e.g. `abc, or ` for the empty symbol
+    Operator(String), // e.g. +, -, *, %, <=, <>
 
     Colon,
     Semicolon,
@@ -21,6 +28,33 @@ pub enum Token {
     Identifier(String), // user-defined identifiers
 }
 
+// Words reserved by qSQL, they delimit the phrases of a query. `where` is not one of them as
+// it is also a function, the parser tells it apart within a query
+const KEYWORDS: [&str; 6] = ["select", "exec", "update", "delete", "by", "from"];
+
+impl Token {
+    // A noun ends an operand, so a following `-` is a minus rather than a negative literal
+    fn is_noun(&self) -> bool {
+        matches!(
+            self,
+            Token::Integer(_)
+                | Token::Decimal(_)
+                | Token::Date(_)
+                | Token::Booleans(_)
+                | Token::StringLiteral(_)
+                | Token::Symbol(_)
+                | Token::Identifier(_)
+                | Token::RBracket
+                | Token::RParenthesis
+                | Token::RCurly
+        )
+    }
+}
+
+// Ints are 32 bits wide, their null and infinity are the extremes
+const INT_NULL: i64 = i32::MIN as i64;
+const INT_INFINITY: i64 = i32::MAX as i64;
+
 fn parse_number<I>(input: &mut std::iter::Peekable<I>) -> Result<Token, String>
 where
     I: Iterator<Item = char>,
@@ -48,27 +82,93 @@ where
         input.next();
     }
 
+    // 0N and 0W are the int null and infinity, 0n and 0w the float ones; 0N and 0W take the
+    // type of a suffix: i and j for int, ints standing in for q's longs, f for float, d for date
+    if num == "0"
+        && let Some(&c) = input.peek()
+        && matches!(c, 'N' | 'W' | 'n' | 'w')
+    {
+        input.next();
+        let suffix = match input.peek() {
+            Some(&t) if matches!(c, 'N' | 'W') && matches!(t, 'i' | 'j' | 'f' | 'd') => {
+                input.next();
+                Some(t)
+            }
+            _ => None,
+        };
+        if input.peek().is_some_and(|c| c.is_alphanumeric()) {
+            let suffix = suffix.map(String::from).unwrap_or_default();
+            return Err(format!("Invalid number format: 0{c}{suffix}"));
+        }
+        return Ok(match (c, suffix) {
+            ('N', Some('f')) | ('n', _) => Token::Decimal(f64::NAN),
+            ('W', Some('f')) | ('w', _) => Token::Decimal(f64::INFINITY),
+            ('N', Some('d')) => Token::Date(INT_NULL),
+            ('W', Some('d')) => Token::Date(INT_INFINITY),
+            ('N', _) => Token::Integer(INT_NULL),
+            _ => Token::Integer(INT_INFINITY),
+        });
+    }
+
+    // a run of 0s and 1s followed by b is a boolean vector
+    if !is_decimal && input.peek() == Some(&'b') && num.chars().all(|c| c == '0' || c == '1') {
+        input.next();
+        return Ok(Token::Booleans(num.chars().map(|c| c == '1').collect()));
+    }
+
     if is_decimal {
         if let Ok(float_val) = num.parse::<f64>() {
             Ok(Token::Decimal(float_val))
         } else {
             Err(format!("Invalid number format: {}", num))
         }
+    } else if let Ok(int_val) = num.parse::<i64>() {
+        Ok(Token::Integer(int_val))
     } else {
-        if let Ok(int_val) = num.parse::<i64>() {
-            Ok(Token::Integer(int_val))
+        Err(format!("Invalid number format: {}", num))
+    }
+}
+
+fn parse_name<I>(input: &mut std::iter::Peekable<I>) -> String
+where
+    I: Iterator<Item = char>,
+{
+    let mut name = String::new();
+    while let Some(&c) = input.peek() {
+        if c.is_alphanumeric() || c == '_' || c == '.' {
+            name.push(c);
+            input.next();
         } else {
-            Err(format!("Invalid number format: {}", num))
+            break;
         }
     }
+    name
+}
+
+// Whether the tokens so far last assign a lambda to `name`, e.g. f:{x*2} or f::{x*2}
+fn assigns_lambda(tokens: &[Token], name: &str) -> bool {
+    let assigned = (0..tokens.len()).rev().find_map(|i| match &tokens[i..] {
+        [Token::Identifier(n), Token::Colon, Token::Colon, value, ..] if n == name => Some(value),
+        [Token::Identifier(n), Token::Colon, value, ..] if n == name => Some(value),
+        _ => None,
+    });
+    assigned == Some(&Token::LCurly)
 }
 
 pub fn lex(input: &str) -> Result<Vec<Token>, String> {
-    let mut tokens = Vec::new();
+    lex_with(input, |_| false)
+}
+
+/// Lexes `input`, `is_verb` telling which names stand for functions: a minus after one starts a
+/// negative literal, `abs -3` applying abs to -3, where after a noun it subtracts.
+pub fn lex_with(input: &str, is_verb: impl Fn(&str) -> bool) -> Result<Vec<Token>, String> {
+    let mut tokens: Vec<Token> = Vec::new();
     let mut chars = input.chars().peekable();
+    let mut after_space = true;
 
     while let Some(&ch) = chars.peek() {
         // println!("Current char: {}", ch);
+        let is_space = matches!(ch, ' ' | '\t' | '\n' | '\r');
         match ch {
             '0'..='9' => {
                 let token = parse_number(&mut chars)?;
@@ -77,24 +177,81 @@ pub fn lex(input: &str) -> Result<Vec<Token>, String> {
 
             '-' => {
                 chars.next();
-                if let Some(&next_ch) = chars.peek() {
-                    if next_ch.is_ascii_digit() {
-                        let token = parse_number(&mut chars)?;
-                        let token = match token {
-                            Token::Integer(i) => Token::Integer(-i),
-                            Token::Decimal(f) => Token::Decimal(-f),
-                            _ => unreachable!(),
-                        };
-                        tokens.push(token);
-                        continue;
+                // `x-1` and `x -1` are subtractions, `2 -1` and `(-1)` hold a negative literal
+                let negates = match tokens.last() {
+                    Some(Token::Integer(_) | Token::Decimal(_) | Token::Date(_)) => after_space,
+                    Some(Token::Identifier(name)) => is_verb(name) || assigns_lambda(&tokens, name),
+                    Some(token) => !token.is_noun(),
+                    None => true,
+                };
+                if let Some(&next_ch) = chars.peek()
+                    && (next_ch.is_ascii_digit() || next_ch == '.')
+                    && negates
+                {
+                    let token = parse_number(&mut chars)?;
+                    let token = match token {
+                        // the null has no sign
+                        Token::Integer(INT_NULL) => Token::Integer(INT_NULL),
+                        Token::Integer(i) => Token::Integer(-i),
+                        Token::Decimal(f) => Token::Decimal(-f),
+                        Token::Date(INT_NULL) => Token::Date(INT_NULL),
+                        Token::Date(d) => Token::Date(-d),
+                        token => return Err(format!("Cannot negate {token:?}")),
+                    };
+                    tokens.push(token);
+                    after_space = false;
+                    continue;
+                }
+                tokens.push(Token::Operator("-".to_owned()));
+            }
+
+            '<' | '>' => {
+                chars.next();
+                let mut op = ch.to_string();
+                if let Some(&next_ch) = chars.peek()
+                    && (next_ch == '=' || (ch == '<' && next_ch == '>'))
+                {
+                    op.push(next_ch);
+                    chars.next();
+                }
+                tokens.push(Token::Operator(op));
+            }
+
+            '.' => {
+                chars.next();
+                match chars.peek() {
+                    Some(c) if c.is_ascii_digit() => {
+                        let mut num = String::from("0.");
+                        while let Some(&c) = chars.peek() {
+                            if !c.is_ascii_digit() {
+                                break;
+                            }
+                            num.push(c);
+                            chars.next();
+                        }
+                        let value = num
+                            .parse::<f64>()
+                            .map_err(|_| format!("Invalid number format: {}", num))?;
+                        tokens.push(Token::Decimal(value));
+                    }
+                    // namespaced identifiers, e.g. .Q.fc
+                    Some(c) if c.is_alphabetic() => {
+                        let name = parse_name(&mut chars);
+                        tokens.push(Token::Identifier(format!(".{name}")));
                     }
+                    _ => tokens.push(Token::Operator(".".to_owned())),
                 }
-                tokens.push(Token::Operator('-'));
             }
 
-            '+' | '*' | '/' | ',' | '%' => {
-                tokens.push(Token::Operator(ch));
+            '+' | '*' | '/' | ',' | '%' | '=' | '~' | '&' | '|' | '#' | '_' | '^' | '!' | '?'
+            | '$' | '@' | '\'' | '\\' => {
+                tokens.push(Token::Operator(ch.to_string()));
+                chars.next();
+            }
+
+            '`' => {
                 chars.next();
+                tokens.push(Token::Symbol(parse_name(&mut chars)));
             }
 
             ';' => {
@@ -113,57 +270,116 @@ pub fn lex(input: &str) -> Result<Vec<Token>, String> {
                 tokens.push(Token::RBracket);
                 chars.next();
             }
+            '(' => {
+                tokens.push(Token::LParenthesis);
+                chars.next();
+            }
+            ')' => {
+                tokens.push(Token::RParenthesis);
+                chars.next();
+            }
+            '{' => {
+                tokens.push(Token::LCurly);
+                chars.next();
+            }
+            '}' => {
+                tokens.push(Token::RCurly);
+                chars.next();
+            }
             '"' => {
                 let mut string_literal = String::new();
                 chars.next();
                 let mut is_string_terminated = false;
-                while let Some(&c) = chars.peek() {
-                    if c == '"' {
-                        is_string_terminated = true;
-                        chars.next();
-                        break;
-                    } else {
-                        string_literal.push(c);
-                        chars.next();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => {
+                            is_string_terminated = true;
+                            break;
+                        }
+                        '\\' => match chars.next() {
+                            Some('n') => string_literal.push('\n'),
+                            Some('t') => string_literal.push('\t'),
+                            Some('r') => string_literal.push('\r'),
+                            Some(escaped) => string_literal.push(escaped),
+                            None => break,
+                        },
+                        c => string_literal.push(c),
                     }
                 }
                 if !is_string_terminated {
-                    return Err(format!("Unterminated string literal"));
+                    return Err("Unterminated string literal".to_owned());
                 }
                 tokens.push(Token::StringLiteral(string_literal));
             }
 
             // skip whitespace
-            ' ' | '\t' | '\n' => {
+            ' ' | '\t' | '\n' | '\r' => {
                 chars.next();
             }
             _ => {
                 // identifiers (like foo, bar)
                 if ch.is_alphabetic() {
-                    let mut ident = String::new();
-                    while let Some(&c) = chars.peek() {
-                        if c.is_alphanumeric() {
-                            ident.push(c);
-                            chars.next();
-                        } else {
-                            break;
-                        }
+                    let ident = parse_name(&mut chars);
+                    if KEYWORDS.contains(&ident.as_str()) {
+                        tokens.push(Token::Keyword(ident));
+                    } else {
+                        tokens.push(Token::Identifier(ident));
                     }
-                    tokens.push(Token::Identifier(ident));
                 } else {
                     return Err(format!("Unexpected char: {ch}"));
                 }
             }
         }
+        after_space = is_space;
     }
 
     Ok(tokens)
 }
 
 #[cfg(test)]
+// 3.14 is a literal to lex rather than an approximation of pi
+#[allow(clippy::approx_constant)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn lex_nulls_and_infinities() {
+        let tokens = lex("0N -0W 0W 0w -0w").unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Integer(INT_NULL),
+                Token::Integer(-INT_INFINITY),
+                Token::Integer(INT_INFINITY),
+                Token::Decimal(f64::INFINITY),
+                Token::Decimal(f64::NEG_INFINITY),
+            ]
+        );
+        assert!(matches!(lex("0n").unwrap()[..], [Token::Decimal(f)] if f.is_nan()));
+        assert_eq!(lex("-0N").unwrap(), vec![Token::Integer(INT_NULL)]);
+        assert!(lex("0Nx").is_err());
+    }
+
+    #[test]
+    fn lex_typed_nulls_and_infinities() {
+        let tokens = lex("0Ni 0Nj 0Wf 0Nd 0Wd -0Wd -0Nd").unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Integer(INT_NULL),
+                Token::Integer(INT_NULL),
+                Token::Decimal(f64::INFINITY),
+                Token::Date(INT_NULL),
+                Token::Date(INT_INFINITY),
+                Token::Date(-INT_INFINITY),
+                Token::Date(INT_NULL),
+            ]
+        );
+        assert!(matches!(lex("0Nf").unwrap()[..], [Token::Decimal(f)] if f.is_nan()));
+        assert!(lex("0Ndx").is_err());
+        assert!(lex("0nd").is_err());
+    }
+
     #[test]
     fn parse_number_integer() {
         let mut chars = "123abc".chars().peekable();
@@ -210,4 +426,102 @@ mod tests {
         let tok = parse_number(&mut chars).expect("should parse -5");
         assert_eq!(tok, Token::Integer(-5));
     }
+
+    #[test]
+    fn parse_number_booleans() {
+        let mut chars = "101b ".chars().peekable();
+        let tok = parse_number(&mut chars).expect("should parse booleans");
+        assert_eq!(tok, Token::Booleans(vec![true, false, true]));
+
+        let mut chars = "12b".chars().peekable();
+        assert_eq!(parse_number(&mut chars), Ok(Token::Integer(12)));
+    }
+
+    #[test]
+    fn lex_minus_is_operator_after_noun() {
+        let tokens = lex("x-1").expect("should lex");
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Identifier("x".to_owned()),
+                Token::Operator("-".to_owned()),
+                Token::Integer(1)
+            ]
+        );
+    }
+
+    #[test]
+    fn lex_negative_literal_after_verb() {
+        let is_verb = |name: &str| ["abs", "neg", "signum", "count"].contains(&name);
+        let lex = |input| lex_with(input, is_verb).expect("should lex");
+        let ident = |name: &str| Token::Identifier(name.to_owned());
+        assert_eq!(lex("abs -3"), vec![ident("abs"), Token::Integer(-3)]);
+        assert_eq!(
+            lex("neg -1 2"),
+            vec![ident("neg"), Token::Integer(-1), Token::Integer(2)]
+        );
+        assert_eq!(
+            lex("signum -2 0 3 0N"),
+            vec![
+                ident("signum"),
+                Token::Integer(-2),
+                Token::Integer(0),
+                Token::Integer(3),
+                Token::Integer(INT_NULL)
+            ]
+        );
+        assert_eq!(
+            lex("neg -0W"),
+            vec![ident("neg"), Token::Integer(-INT_INFINITY)]
+        );
+        assert_eq!(
+            lex("count -2#1 2 3")[..3],
+            [
+                ident("count"),
+                Token::Integer(-2),
+                Token::Operator("#".to_owned())
+            ]
+        );
+
+        // names assigned a lambda earlier are verbs, other names nouns
+        let tokens = lex("f:{x}; f -3; x -3");
+        assert_eq!(tokens[6..8], [ident("f"), Token::Integer(-3)]);
+        assert_eq!(tokens[10], Token::Operator("-".to_owned()));
+        let tokens = lex("f:{x}; f:2; f -3");
+        assert_eq!(tokens[tokens.len() - 2], Token::Operator("-".to_owned()));
+    }
+
+    #[test]
+    fn lex_negative_literal_in_vector() {
+        let tokens = lex("2 -1").expect("should lex");
+        assert_eq!(tokens, vec![Token::Integer(2), Token::Integer(-1)]);
+    }
+
+    #[test]
+    fn lex_query_keywords_and_symbols() {
+        let tokens = lex("select a by s from t where s=`ab`c").expect("should lex");
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Keyword("select".to_owned()),
+                Token::Identifier("a".to_owned()),
+                Token::Keyword("by".to_owned()),
+                Token::Identifier("s".to_owned()),
+                Token::Keyword("from".to_owned()),
+                Token::Identifier("t".to_owned()),
+                Token::Identifier("where".to_owned()),
+                Token::Identifier("s".to_owned()),
+                Token::Operator("=".to_owned()),
+                Token::Symbol("ab".to_owned()),
+                Token::Symbol("c".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn lex_two_char_comparisons() {
+        let tokens = lex("a<=b<>c").expect("should lex");
+        assert_eq!(tokens[1], Token::Operator("<=".to_owned()));
+        assert_eq!(tokens[3], Token::Operator("<>".to_owned()));
+    }
 }
@@ -1,14 +1,31 @@
+use std::path::PathBuf;
+
 use rustyline::DefaultEditor;
 use rustyline::error::ReadlineError;
 
-// use language_parsing::Parser;
-use cupid_parser::lex;
+use cupid_core::{core::host::Host, lang::SuperType};
+
+// The history is kept in the home directory rather than wherever the shell is started from
+fn history_path() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cupid_history"))
+}
 
 fn main() {
     let mut rl = DefaultEditor::new().unwrap();
-    let _ = rl.load_history("history.txt");
+    let history = history_path();
+    if let Some(path) = &history {
+        let _ = rl.load_history(path);
+    }
 
-    let mut context = cupid_core::context::Context::default();
+    let mut host = Host::default();
+    // -s N gives N secondary threads to operations on long lists, as in q
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(threads) = args.windows(2).find(|arg| arg[0] == "-s") {
+        match threads[1].parse() {
+            Ok(threads) => host.set_threads(threads).unwrap(),
+            Err(_) => println!("-s takes a number of threads"),
+        }
+    }
 
     loop {
         match rl.readline("q> ") {
@@ -23,39 +40,11 @@ fn main() {
 
                 rl.add_history_entry(line).unwrap();
 
-                let tokens = lex(line);
-
-                println!("{:?}", tokens);
-
-                let tokens = match tokens {
-                    Ok(toks) => toks,
-                    Err(e) => {
-                        println!("Lexing error: {}", e);
-                        continue;
-                    }
-                };
-
-                if let Err(e) = context.load(tokens) {
-                    println!("Context load error: {}", e);
-                    continue;
+                match host.global_context().run(line) {
+                    Ok(SuperType::Nothing) => {}
+                    Ok(result) => println!("{result}"),
+                    Err(e) => println!("{e}"),
                 }
-
-                loop {
-                    match context.execute_next() {
-                        Ok(result) => {
-                            if context.is_complete() {
-                                println!("Result: {:?}", result);
-                                break;
-                            }
-                        }
-                        Err(e) => {
-                            println!("Execution error: {}", e);
-                            break;
-                        }
-                    }
-                }
-
-                println!("Variables: {:?}", context.variables);
             }
             Err(ReadlineError::Interrupted) => {
                 println!("^C");
@@ -72,5 +61,9 @@ fn main() {
         }
     }
 
-    rl.save_history("history.txt").unwrap();
+    if let Some(path) = &history
+        && let Err(err) = rl.save_history(path)
+    {
+        println!("Could not save the history: {err}");
+    }
 }